@@ -32,6 +32,113 @@ pub struct Args {
     #[clap(short, long)]
     pub function: Option<String>,
 
+    /// Write the tree of explored paths as a Graphviz DOT graph to this file.
+    #[clap(long)]
+    pub dot: Option<PathBuf>,
+
+    /// Run global constructors before, and global destructors after, the analyzed function.
+    #[clap(long)]
+    pub global_ctors: bool,
+
+    /// Prefer small values for the reported inputs and symbolic values.
+    #[clap(long)]
+    pub minimize: bool,
+
+    /// Continue in the unwind landing pad after a panic to find errors in cleanup code.
+    #[clap(long)]
+    pub continue_after_panic: bool,
+
+    /// Write the constraints of each path as an SMT-LIB script to this directory.
+    #[clap(long)]
+    pub constraint_log: Option<PathBuf>,
+
+    /// Fill uninitialized stack and heap memory with this byte instead of symbolic values.
+    #[clap(long)]
+    pub uninitialized_byte: Option<u8>,
+
+    /// Analyze the `core::fmt` formatting functions instead of skipping them.
+    #[clap(long)]
+    pub analyze_fmt: bool,
+
+    /// Experimental: summarize loops instead of unrolling them, this can miss errors.
+    #[clap(long)]
+    pub loop_summary: bool,
+
+    /// Execute small functions in the caller's frame if they have at most this many instructions.
+    #[clap(long, default_value_t = 0)]
+    pub inline_threshold: usize,
+
+    /// Report the functions entered and exited on each path.
+    #[clap(long)]
+    pub call_trace: bool,
+
+    /// Report how many times each loop ran on each path.
+    #[clap(long)]
+    pub loop_counts: bool,
+
+    /// Fail paths that enter a loop again without any change to the state, i.e. loop forever.
+    #[clap(long)]
+    pub detect_infinite_loops: bool,
+
+    /// Fail loads and stores whose address can be misaligned, for targets that fault on them.
+    #[clap(long)]
+    pub check_alignment: bool,
+
+    /// Assume functions without a definition return any value and have no side effects, instead
+    /// of stopping.
+    #[clap(long, conflicts_with = "havoc_unknown_functions")]
+    pub assume_unknown_functions_pure: bool,
+
+    /// Assume functions without a definition return any value and overwrite the memory their
+    /// pointer arguments point to, instead of stopping.
+    #[clap(long)]
+    pub havoc_unknown_functions: bool,
+
+    /// Explore paths breadth-first, to find the paths that take the fewest branches first.
+    #[clap(long, conflicts_with = "random_search")]
+    pub bfs: bool,
+
+    /// Explore a random path next, using this seed. Running again with the same seed explores the
+    /// paths in the same order.
+    #[clap(long)]
+    pub random_search: Option<u64>,
+
+    /// Fail paths that execute more than this many instructions, e.g. in a loop that never ends.
+    #[clap(long)]
+    pub max_iter_count: Option<u64>,
+
+    /// Fail paths that make the call stack deeper than this, e.g. with unbounded recursion.
+    #[clap(long, default_value_t = 100)]
+    pub max_call_depth: usize,
+
+    /// Stop exploring new paths after this many seconds, reporting only the paths found so far.
+    #[clap(long)]
+    pub timeout: Option<u64>,
+
+    /// Show the number of explored paths and executed instructions while running.
+    #[clap(long)]
+    pub progress: bool,
+
+    /// Fail if a reported value can take more than one value, e.g. an under-constrained input.
+    #[clap(long, conflicts_with = "count_solutions")]
+    pub fail_on_multiple_solutions: bool,
+
+    /// Report how many values each reported value can take, counting up to this many.
+    #[clap(long)]
+    pub count_solutions: Option<usize>,
+
+    /// Only print failed paths and the summary.
+    #[clap(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Also print the condition of each symbolic branch taken on a path.
+    #[clap(short, long)]
+    pub verbose: bool,
+
+    /// Print integers as signed decimal values instead of unsigned hexadecimal values.
+    #[clap(long)]
+    pub signed: bool,
+
     #[clap(subcommand)]
     pub subcommand: Option<Subcommands>,
 
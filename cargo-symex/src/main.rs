@@ -1,8 +1,13 @@
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use log::debug;
-use std::{fs, path::PathBuf};
-use symex::run::{self, RunConfig, SolveFor};
+use std::{fs, path::PathBuf, time::Duration};
+use symex::{
+    memory::UninitializedMemory,
+    run::{self, MaxSolutionsExceeded, RunConfig, SearchStrategy, SolveFor, Verbosity},
+    smt::SolverConfig,
+    vm::UnknownFunctionPolicy,
+};
 use tracing_subscriber;
 
 const BINARY_NAME: &str = "symex";
@@ -98,6 +103,52 @@ fn run_rs(args: Args) -> Result<()> {
         solve_symbolics: true,
         solve_output: true,
         solve_for: SolveFor::All,
+        dot_output: args.dot.clone(),
+        solver: SolverConfig::default(),
+        run_global_ctors: args.global_ctors,
+        minimize_witnesses: args.minimize,
+        continue_after_panic: args.continue_after_panic,
+        constraint_log: args.constraint_log.clone(),
+        uninitialized_memory: match args.uninitialized_byte {
+            Some(byte) => UninitializedMemory::Byte(byte),
+            None => UninitializedMemory::Symbolic,
+        },
+        stub_formatting: !args.analyze_fmt,
+        loop_summary: args.loop_summary,
+        inline_threshold: args.inline_threshold,
+        call_trace: args.call_trace,
+        loop_counts: args.loop_counts,
+        detect_infinite_loops: args.detect_infinite_loops,
+        check_alignment: args.check_alignment,
+        unknown_function_policy: match (
+            args.assume_unknown_functions_pure,
+            args.havoc_unknown_functions,
+        ) {
+            (true, _) => UnknownFunctionPolicy::ReturnSymbolic,
+            (false, true) => UnknownFunctionPolicy::HavocArgsAndReturnSymbolic,
+            (false, false) => UnknownFunctionPolicy::Error,
+        },
+        progress: args.progress,
+        max_solutions_exceeded_action: match (args.fail_on_multiple_solutions, args.count_solutions)
+        {
+            (true, _) => MaxSolutionsExceeded::Error,
+            (false, Some(cap)) => MaxSolutionsExceeded::Count(cap),
+            (false, None) => MaxSolutionsExceeded::Truncate,
+        },
+        verbosity: match (args.quiet, args.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, true) => Verbosity::Verbose,
+            (false, false) => Verbosity::Normal,
+        },
+        signed_integers: args.signed,
+        search_strategy: match (args.bfs, args.random_search) {
+            (true, _) => SearchStrategy::BreadthFirst,
+            (false, Some(seed)) => SearchStrategy::Random { seed },
+            (false, None) => SearchStrategy::DepthFirst,
+        },
+        max_iter_count: args.max_iter_count,
+        max_call_depth: args.max_call_depth,
+        timeout: args.timeout.map(Duration::from_secs),
     };
 
     run::run(&target_path, &fn_name, &cfg)?;
@@ -6,7 +6,10 @@ use llvm_sys::{
     LLVMOpcode, LLVMValueKind,
 };
 
-use crate::{instruction, Type, Value};
+use crate::{
+    instruction::{self, BasicBlock},
+    Function, Type, Value,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Constant {
@@ -20,6 +23,7 @@ pub enum Constant {
     Array(Array),
     Vector(Vector),
     Structure(Structure),
+    BlockAddress(BlockAddress),
 
     Expression(Expression),
 }
@@ -67,7 +71,7 @@ impl Constant {
             LLVMValueKind::LLVMGlobalAliasValueKind => todo!(),
             LLVMValueKind::LLVMGlobalIFuncValueKind => todo!(),
             LLVMValueKind::LLVMGlobalVariableValueKind => todo!(),
-            LLVMValueKind::LLVMBlockAddressValueKind => todo!(),
+            LLVMValueKind::LLVMBlockAddressValueKind => BlockAddress::new(value_ref).into(),
             LLVMValueKind::LLVMMetadataAsValueValueKind => todo!(),
             LLVMValueKind::LLVMInlineAsmValueKind => todo!(),
             LLVMValueKind::LLVMInstructionValueKind => todo!(),
@@ -90,6 +94,7 @@ impl Constant {
             Constant::Array(c) => c.ty(),
             Constant::Vector(c) => c.ty(),
             Constant::Structure(c) => c.ty(),
+            Constant::BlockAddress(c) => c.ty(),
         }
     }
 }
@@ -108,6 +113,7 @@ impl std::fmt::Display for Constant {
             Constant::Vector(c) => write!(f, "{c}"),
             Constant::Expression(c) => write!(f, "{c}"),
             Constant::Structure(c) => write!(f, "{c}"),
+            Constant::BlockAddress(c) => write!(f, "{c}"),
         }
     }
 }
@@ -312,6 +318,23 @@ impl_constant!(Undef);
 pub struct TargetNone(LLVMValueRef);
 impl_constant!(TargetNone);
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlockAddress(LLVMValueRef);
+impl_constant!(BlockAddress);
+
+impl BlockAddress {
+    /// The function containing the basic block.
+    pub fn function(&self) -> Function {
+        Function::new(unsafe { core::LLVMGetOperand(self.0, 0) })
+    }
+
+    /// The basic block whose address is taken.
+    pub fn basic_block(&self) -> BasicBlock {
+        let value_ref = unsafe { core::LLVMGetOperand(self.0, 1) };
+        BasicBlock::new(unsafe { core::LLVMValueAsBasicBlock(value_ref) })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PointerNull(LLVMValueRef);
 impl_constant!(PointerNull);
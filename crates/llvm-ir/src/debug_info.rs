@@ -0,0 +1,116 @@
+use llvm_sys::{
+    core::{
+        LLVMGetMDNodeNumOperands, LLVMGetMDNodeOperands, LLVMIsAMDNode, LLVMMetadataAsValue,
+        LLVMValueAsMetadata,
+    },
+    debuginfo::{
+        LLVMDITypeGetName, LLVMDITypeGetOffsetInBits, LLVMGetDINodeTag, LLVMGetMetadataKind,
+        LLVMMetadataKind,
+    },
+    prelude::*,
+};
+
+const DW_TAG_MEMBER: u16 = 0x0d;
+const DW_TAG_STRUCTURE_TYPE: u16 = 0x13;
+
+/// Structure type from the debug info, with the source names of the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugStructure {
+    /// Source name of the structure.
+    pub name: String,
+
+    /// Fields in the order they are declared in the source.
+    pub fields: Vec<DebugField>,
+}
+
+/// Field of a [`DebugStructure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugField {
+    /// Source name of the field.
+    pub name: String,
+
+    /// Offset of the field from the start of the structure.
+    pub offset_in_bits: u64,
+}
+
+/// Get the operands of a metadata node, null operands are `None`.
+///
+/// Returns an empty list if the metadata is not a node.
+pub(crate) unsafe fn operands(
+    context: LLVMContextRef,
+    node: LLVMMetadataRef,
+) -> Vec<Option<LLVMMetadataRef>> {
+    let value = LLVMMetadataAsValue(context, node);
+    if LLVMIsAMDNode(value).is_null() {
+        return Vec::new();
+    }
+
+    let num_operands = LLVMGetMDNodeNumOperands(value);
+    let mut operands = vec![std::ptr::null_mut(); num_operands as usize];
+    LLVMGetMDNodeOperands(value, operands.as_mut_ptr());
+
+    operands
+        .into_iter()
+        .map(|operand| (!operand.is_null()).then(|| LLVMValueAsMetadata(operand)))
+        .collect()
+}
+
+/// Get operand `index` of a metadata node.
+pub(crate) unsafe fn operand(
+    context: LLVMContextRef,
+    node: LLVMMetadataRef,
+    index: usize,
+) -> Option<LLVMMetadataRef> {
+    operands(context, node).get(index).copied().flatten()
+}
+
+unsafe fn type_name(ty: LLVMMetadataRef) -> String {
+    let mut len = 0;
+    let ptr = LLVMDITypeGetName(ty, &mut len);
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Parse a debug info type as a structure, returns `None` for any other kind of type.
+pub(crate) unsafe fn structure(
+    context: LLVMContextRef,
+    ty: LLVMMetadataRef,
+) -> Option<DebugStructure> {
+    let is_structure = matches!(
+        LLVMGetMetadataKind(ty),
+        LLVMMetadataKind::LLVMDICompositeTypeMetadataKind
+    ) && LLVMGetDINodeTag(ty) == DW_TAG_STRUCTURE_TYPE;
+    if !is_structure {
+        return None;
+    }
+
+    // The fields of a composite type are in operand 4.
+    let elements = match operand(context, ty, 4) {
+        Some(elements) => operands(context, elements),
+        None => Vec::new(),
+    };
+
+    let fields = elements
+        .into_iter()
+        .flatten()
+        .filter(|member| {
+            matches!(
+                LLVMGetMetadataKind(*member),
+                LLVMMetadataKind::LLVMDIDerivedTypeMetadataKind
+            ) && LLVMGetDINodeTag(*member) == DW_TAG_MEMBER
+        })
+        .map(|member| DebugField {
+            name: type_name(member),
+            offset_in_bits: LLVMDITypeGetOffsetInBits(member),
+        })
+        .collect();
+
+    Some(DebugStructure {
+        name: type_name(ty),
+        fields,
+    })
+}
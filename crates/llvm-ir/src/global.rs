@@ -2,20 +2,24 @@ use std::ffi::CStr;
 
 use llvm_sys::{
     core::{
-        LLVMGetAlignment, LLVMGetDLLStorageClass, LLVMGetFirstBasicBlock, LLVMGetFirstParam,
-        LLVMGetFunctionCallConv, LLVMGetGC, LLVMGetInitializer, LLVMGetIntrinsicID, LLVMGetLinkage,
-        LLVMGetNextParam, LLVMGetPersonalityFn, LLVMGetSection, LLVMGetThreadLocalMode,
-        LLVMGetUnnamedAddress, LLVMGetValueKind, LLVMGetValueName2, LLVMGetVisibility,
-        LLVMGlobalGetValueType, LLVMHasPersonalityFn, LLVMIntrinsicGetName,
+        LLVMAliasGetAliasee, LLVMCountBasicBlocks, LLVMGetAlignment, LLVMGetDLLStorageClass,
+        LLVMGetEnumAttributeAtIndex, LLVMGetEnumAttributeKindForName, LLVMGetFirstBasicBlock,
+        LLVMGetFirstParam, LLVMGetFunctionCallConv, LLVMGetGC, LLVMGetInitializer,
+        LLVMGetIntrinsicID, LLVMGetLinkage, LLVMGetNextBasicBlock, LLVMGetNextParam,
+        LLVMGetPersonalityFn, LLVMGetSection, LLVMGetThreadLocalMode, LLVMGetTypeAttributeValue,
+        LLVMGetTypeContext, LLVMGetUnnamedAddress, LLVMGetValueKind, LLVMGetValueName2,
+        LLVMGetVisibility, LLVMGlobalGetValueType, LLVMHasPersonalityFn, LLVMIntrinsicGetName,
         LLVMIntrinsicIsOverloaded, LLVMIsDeclaration, LLVMIsExternallyInitialized,
         LLVMIsGlobalConstant, LLVMIsThreadLocal, LLVMPrintValueToString, LLVMTypeOf,
     },
+    debuginfo::LLVMGetSubprogram,
     prelude::*,
-    LLVMDLLStorageClass, LLVMLinkage, LLVMThreadLocalMode, LLVMUnnamedAddr, LLVMValueKind,
-    LLVMVisibility,
+    LLVMAttributeFunctionIndex, LLVMDLLStorageClass, LLVMLinkage, LLVMThreadLocalMode,
+    LLVMUnnamedAddr, LLVMValueKind, LLVMVisibility,
 };
 
 use crate::{
+    debug_info::{self, DebugStructure},
     instruction::BasicBlock,
     types::Type,
     util::{debug_location_without_column, DebugLocation},
@@ -222,6 +226,28 @@ impl Function {
         unsafe { LLVMGetFunctionCallConv(self.0) }
     }
 
+    /// Returns true if the function has the `noreturn` attribute, e.g. Rust functions returning
+    /// `!`.
+    pub fn is_noreturn(&self) -> bool {
+        let name = "noreturn";
+        unsafe {
+            let kind = LLVMGetEnumAttributeKindForName(name.as_ptr() as *const _, name.len());
+            !LLVMGetEnumAttributeAtIndex(self.0, LLVMAttributeFunctionIndex, kind).is_null()
+        }
+    }
+
+    /// Returns the type of parameter `index` if it has the `byval` attribute, i.e. the pointed to
+    /// value is passed by value and the function gets its own copy of it.
+    pub fn byval_type(&self, index: u32) -> Option<Type> {
+        let name = "byval";
+        unsafe {
+            let kind = LLVMGetEnumAttributeKindForName(name.as_ptr() as *const _, name.len());
+            // Attribute index 0 is the return value, the parameters start at 1.
+            let attribute = LLVMGetEnumAttributeAtIndex(self.0, index + 1, kind);
+            (!attribute.is_null()).then(|| Type::new(LLVMGetTypeAttributeValue(attribute)))
+        }
+    }
+
     pub fn instrinsic_id(&self) -> u32 {
         unsafe { LLVMGetIntrinsicID(self.0) }
     }
@@ -261,9 +287,41 @@ impl Function {
         }
     }
 
+    pub fn basic_blocks(&self) -> BasicBlockIter {
+        unsafe { BasicBlockIter::new(self.0) }
+    }
+
+    pub fn num_basic_blocks(&self) -> u32 {
+        unsafe { LLVMCountBasicBlocks(self.0) }
+    }
+
     pub fn parameters(&self) -> ParameterIter {
         unsafe { ParameterIter::new(self.0) }
     }
+
+    /// Location of the function definition, if the function has debug info.
+    pub fn debug_location(&self) -> Option<DebugLocation> {
+        debug_location_without_column(self.0)
+    }
+
+    /// Returns the source level structure the function returns, if the function has debug info
+    /// and returns a structure.
+    pub fn debug_return_structure(&self) -> Option<DebugStructure> {
+        unsafe {
+            let subprogram = LLVMGetSubprogram(self.0);
+            if subprogram.is_null() {
+                return None;
+            }
+            let context = LLVMGetTypeContext(LLVMTypeOf(self.0));
+
+            // The subprogram type is operand 4, and its first type is the return type.
+            let subroutine = debug_info::operand(context, subprogram, 4)?;
+            let types = debug_info::operand(context, subroutine, 3)?;
+            let return_type = debug_info::operand(context, types, 0)?;
+
+            debug_info::structure(context, return_type)
+        }
+    }
 }
 
 pub struct ParameterIter(LLVMValueRef);
@@ -288,6 +346,28 @@ impl Iterator for ParameterIter {
     }
 }
 
+pub struct BasicBlockIter(LLVMBasicBlockRef);
+
+impl BasicBlockIter {
+    pub(crate) unsafe fn new(function_ref: LLVMValueRef) -> Self {
+        Self(unsafe { LLVMGetFirstBasicBlock(function_ref) })
+    }
+}
+
+impl Iterator for BasicBlockIter {
+    type Item = BasicBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_null() {
+            None
+        } else {
+            let current = self.0;
+            self.0 = unsafe { LLVMGetNextBasicBlock(self.0) };
+            Some(BasicBlock::new(current))
+        }
+    }
+}
+
 impl From<LLVMValueRef> for Function {
     fn from(value: LLVMValueRef) -> Self {
         Self(value)
@@ -490,6 +570,19 @@ impl GlobalAlias {
     pub fn ty(&self) -> Type {
         Type::new(unsafe { LLVMTypeOf(self.0) })
     }
+
+    pub fn name(&self) -> &CStr {
+        unsafe {
+            let mut len = 0;
+            let ptr = LLVMGetValueName2(self.0, &mut len);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Returns the value the alias refers to.
+    pub fn aliasee(&self) -> Value {
+        Value::new(unsafe { LLVMAliasGetAliasee(self.0) })
+    }
 }
 
 impl From<LLVMValueRef> for GlobalAlias {
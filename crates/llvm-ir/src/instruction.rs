@@ -1,16 +1,17 @@
 use llvm_sys::{
     core::{
         LLVMCountIncoming, LLVMGetAlignment, LLVMGetAllocatedType, LLVMGetAtomicRMWBinOp,
-        LLVMGetCalledFunctionType, LLVMGetCalledValue, LLVMGetCmpXchgFailureOrdering,
-        LLVMGetCmpXchgSuccessOrdering, LLVMGetFCmpPredicate, LLVMGetFirstInstruction,
-        LLVMGetFunctionCallConv, LLVMGetGEPSourceElementType, LLVMGetICmpPredicate,
-        LLVMGetIncomingBlock, LLVMGetIncomingValue, LLVMGetIndices, LLVMGetInstructionOpcode,
-        LLVMGetLastInstruction, LLVMGetMaskValue, LLVMGetNSW, LLVMGetNUW, LLVMGetNextInstruction,
-        LLVMGetNormalDest, LLVMGetNumArgOperands, LLVMGetNumIndices, LLVMGetNumMaskElements,
-        LLVMGetNumOperands, LLVMGetNumSuccessors, LLVMGetOperand, LLVMGetOrdering,
-        LLVMGetSuccessor, LLVMGetSwitchDefaultDest, LLVMGetUndefMaskElem, LLVMGetUnwindDest,
-        LLVMGetVolatile, LLVMGetWeak, LLVMIsAtomicSingleThread, LLVMIsInBounds, LLVMTypeOf,
-        LLVMValueAsBasicBlock, LLVMValueIsBasicBlock,
+        LLVMGetBasicBlockName, LLVMGetBasicBlockParent, LLVMGetCalledFunctionType,
+        LLVMGetCalledValue, LLVMGetCmpXchgFailureOrdering, LLVMGetCmpXchgSuccessOrdering,
+        LLVMGetFCmpPredicate, LLVMGetFirstInstruction, LLVMGetFunctionCallConv,
+        LLVMGetGEPSourceElementType, LLVMGetICmpPredicate, LLVMGetIncomingBlock,
+        LLVMGetIncomingValue, LLVMGetIndices, LLVMGetInstructionOpcode, LLVMGetLastInstruction,
+        LLVMGetMaskValue, LLVMGetNSW, LLVMGetNUW, LLVMGetNextInstruction, LLVMGetNormalDest,
+        LLVMGetNumArgOperands, LLVMGetNumIndices, LLVMGetNumMaskElements, LLVMGetNumOperands,
+        LLVMGetNumSuccessors, LLVMGetOperand, LLVMGetOrdering, LLVMGetSuccessor,
+        LLVMGetSwitchDefaultDest, LLVMGetUndefMaskElem, LLVMGetUnwindDest, LLVMGetVolatile,
+        LLVMGetWeak, LLVMIsAtomicSingleThread, LLVMIsInBounds, LLVMTypeOf, LLVMValueAsBasicBlock,
+        LLVMValueIsBasicBlock,
     },
     prelude::*,
     LLVMOpcode,
@@ -21,7 +22,7 @@ pub use llvm_sys::LLVMAtomicRMWBinOp;
 pub use llvm_sys::LLVMIntPredicate;
 pub use llvm_sys::LLVMRealPredicate;
 
-use crate::{util::DebugLocation, Type, Value};
+use crate::{util::DebugLocation, Function, Type, Value};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct BasicBlock(LLVMBasicBlockRef);
@@ -55,6 +56,29 @@ impl BasicBlock {
         }
     }
 
+    /// Basic blocks the terminator of this block can branch to.
+    pub fn successors(&self) -> Vec<BasicBlock> {
+        let Some(terminator) = self.terminator() else {
+            return Vec::new();
+        };
+        let value_ref = terminator.get_ptr();
+        let num_successors = unsafe { LLVMGetNumSuccessors(value_ref) };
+        (0..num_successors)
+            .map(|i| unsafe { LLVMGetSuccessor(value_ref, i) })
+            .map(|v| BasicBlock(v))
+            .collect()
+    }
+
+    /// Name of the block, empty if the block is unnamed.
+    pub fn name(&self) -> &core::ffi::CStr {
+        unsafe { core::ffi::CStr::from_ptr(LLVMGetBasicBlockName(self.0)) }
+    }
+
+    /// Function containing the block.
+    pub fn parent(&self) -> Function {
+        Function::new(unsafe { LLVMGetBasicBlockParent(self.0) })
+    }
+
     pub(crate) fn new(src: LLVMBasicBlockRef) -> Self {
         Self(src)
     }
@@ -944,7 +968,6 @@ impl ShuffleVector {
             .map(|i| match is_scalable {
                 false => match i {
                     v if v == undefined_element_value => None,
-                    0 => panic!("fixed vector mask element is not 0 or undef"),
                     _ => Some(i),
                 },
                 true => match i {
@@ -1499,8 +1522,21 @@ impl CallBr {
     pub fn arguments(&self) -> Vec<Value> {
         let num_arguments = unsafe { LLVMGetNumArgOperands(self.0) };
         (0..num_arguments)
-            .map(|i| unsafe { LLVMGetOperand(self.0, i + 1) })
-            .map(|v| Value::new(v).into())
+            .map(|i| unsafe { LLVMGetOperand(self.0, i) })
+            .map(|v| Value::new(v))
+            .collect()
+    }
+
+    /// Destination taken when the assembly falls through.
+    pub fn default_destination(&self) -> BasicBlock {
+        BasicBlock(unsafe { LLVMGetSuccessor(self.0, 0) })
+    }
+
+    /// Destinations the assembly can jump to, in the order they are listed.
+    pub fn indirect_destinations(&self) -> Vec<BasicBlock> {
+        let num_successors = unsafe { LLVMGetNumSuccessors(self.0) };
+        (1..num_successors)
+            .map(|i| BasicBlock(unsafe { LLVMGetSuccessor(self.0, i) }))
             .collect()
     }
 
@@ -1,4 +1,5 @@
 pub mod constant;
+mod debug_info;
 mod global;
 pub mod instruction;
 mod module;
@@ -6,6 +7,7 @@ mod types;
 mod util;
 mod value;
 
+pub use debug_info::{DebugField, DebugStructure};
 pub use global::*;
 pub use module::*;
 pub use types::*;
@@ -14,9 +14,10 @@ use llvm_sys::{
         LLVMGetNextGlobalAlias, LLVMGetNextGlobalIFunc, LLVMGetSourceFileName, LLVMGetTarget,
     },
     prelude::*,
+    target::LLVMGetModuleDataLayout,
 };
 
-use crate::{Function, GlobalAlias, GlobalIFunc, GlobalVariable};
+use crate::{DataLayout, Function, GlobalAlias, GlobalIFunc, GlobalVariable};
 
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
 pub enum ModuleError {
@@ -130,6 +131,10 @@ impl Module {
         }
     }
 
+    pub fn data_layout(&self) -> DataLayout {
+        DataLayout::new(unsafe { LLVMGetModuleDataLayout(self.0) })
+    }
+
     pub fn target_triple(&self) -> &CStr {
         unsafe {
             let ptr = LLVMGetTarget(self.0);
@@ -144,6 +149,10 @@ impl Module {
     pub fn globals(&self) -> GlobalIter {
         unsafe { GlobalIter::new(self.0) }
     }
+
+    pub fn aliases(&self) -> GlobalAliasIter {
+        unsafe { GlobalAliasIter::new(self.0) }
+    }
 }
 
 macro_rules! impl_iter {
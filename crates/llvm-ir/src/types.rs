@@ -8,6 +8,7 @@ use llvm_sys::{
         LLVMIsFunctionVarArg, LLVMIsLiteralStruct, LLVMIsOpaqueStruct,
     },
     prelude::*,
+    target::{LLVMABISizeOfType, LLVMOffsetOfElement, LLVMTargetDataRef},
     LLVMTypeKind,
 };
 
@@ -20,7 +21,7 @@ pub enum Type {
     Vector(VectorType),
     Array(ArrayType),
     Structure(StructureType),
-    OpaqueStructure,
+    OpaqueStructure(StructureType),
     Function(FunctionType),
     Label,
     Token,
@@ -45,7 +46,7 @@ impl Type {
             LLVMTypeKind::LLVMIntegerTypeKind => Type::Integer(IntegerType::new(ty)),
             LLVMTypeKind::LLVMFunctionTypeKind => Type::Function(FunctionType::new(ty)),
             LLVMTypeKind::LLVMStructTypeKind => match unsafe { LLVMIsOpaqueStruct(ty) != 0 } {
-                true => Type::OpaqueStructure,
+                true => Type::OpaqueStructure(StructureType::new(ty)),
                 false => Type::Structure(StructureType::new(ty)),
             },
             LLVMTypeKind::LLVMArrayTypeKind => Type::Array(ArrayType::new(ty)),
@@ -124,7 +125,10 @@ impl std::fmt::Display for Type {
             Type::Vector(t) => write!(f, "{}", t),
             Type::Array(t) => write!(f, "{}", t),
             Type::Structure(t) => write!(f, "{}", t),
-            Type::OpaqueStructure => write!(f, "type opaque"),
+            Type::OpaqueStructure(t) => match t.name() {
+                Some(name) => write!(f, "%{} = type opaque", name.to_string_lossy()),
+                None => write!(f, "type opaque"),
+            },
             Type::Function(t) => write!(f, "{}", t),
             Type::Label => write!(f, "label"),
             Type::Token => write!(f, "token"),
@@ -206,6 +210,21 @@ impl FloatingPointType {
         }
     }
 
+    /// Returns the number of exponent and mantissa bits for IEEE-like types.
+    ///
+    /// The mantissa bits do not include the implicit leading bit. Returns `None` for types that
+    /// are not IEEE-like.
+    pub fn exponent_and_mantissa_bits(&self) -> Option<(u32, u32)> {
+        match self {
+            FloatingPointType::Half => Some((5, 10)),
+            FloatingPointType::BFloat => Some((8, 7)),
+            FloatingPointType::Float => Some((8, 23)),
+            FloatingPointType::Double => Some((11, 52)),
+            FloatingPointType::Fp128 => Some((15, 112)),
+            FloatingPointType::X86Fp80 | FloatingPointType::PpcFp128 => None,
+        }
+    }
+
     pub fn bits(&self) -> u32 {
         match self {
             FloatingPointType::Half => 16,
@@ -360,6 +379,29 @@ impl StructureType {
         };
         fields.into_iter().map(Type::new).collect()
     }
+
+    /// Byte offsets of every field, including any padding required by the data layout.
+    pub fn field_offsets(&self, layout: &DataLayout) -> Vec<u64> {
+        let num_elements = unsafe { LLVMCountStructElementTypes(self.0) };
+        (0..num_elements)
+            .map(|i| unsafe { LLVMOffsetOfElement(layout.0, self.0, i) })
+            .collect()
+    }
+
+    /// Size in bytes of the structure, including tail padding.
+    pub fn abi_size(&self, layout: &DataLayout) -> u64 {
+        unsafe { LLVMABISizeOfType(layout.0, self.0) }
+    }
+}
+
+/// Target data layout of a module, used to query ABI sizes and offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataLayout(LLVMTargetDataRef);
+
+impl DataLayout {
+    pub(crate) fn new(data_ref: LLVMTargetDataRef) -> Self {
+        Self(data_ref)
+    }
 }
 
 impl std::fmt::Display for StructureType {
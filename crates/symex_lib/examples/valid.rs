@@ -20,4 +20,16 @@ enum Enum2 {
     U2,
 }
 
+#[derive(Validate)]
+struct Named {
+    x: u32,
+    e: Enum,
+}
+
+#[derive(Validate)]
+struct Unnamed(u32, Enum2);
+
+#[derive(Validate)]
+struct Unit;
+
 fn main() {}
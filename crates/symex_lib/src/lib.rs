@@ -28,6 +28,29 @@ pub fn assume(condition: bool) {
     black_box(&mut condition);
 }
 
+/// Assert the condition.
+///
+/// Checks that the passed condition is true. If the condition can be false, the executor reports
+/// a failing path together with the inputs that make it false. This is the opposite of [`assume`],
+/// which removes the paths where the condition is false instead.
+///
+/// # Example
+///
+/// ```rust
+/// # use symex_lib::{assert, symbolic};
+/// fn foo() {
+///     let mut x = 1;
+///     symbolic(&mut x);
+///     // Reported as a failing path with `x <= 0`.
+///     assert(x > 0);
+/// }
+/// ```
+#[inline(never)]
+pub fn assert(condition: bool) {
+    let mut condition = condition;
+    black_box(&mut condition);
+}
+
 /// Creates a new symbolic value for `value`. This removes all constraints.
 ///
 /// This creates a new symbolic variable and assigns overwrites the passed `value`. This must be
@@ -54,6 +77,67 @@ pub fn symbolic<T>(value: &mut T) {
     black_box(value);
 }
 
+/// Creates a new symbolic value for `value`, reported as `name` in the results.
+///
+/// Same as [`symbolic`], but the name is used as is instead of a generated one, which gives stable
+/// names when processing the results.
+///
+/// # Example
+///
+/// ```rust
+/// # use symex_lib::symbolic_named;
+/// fn foo() {
+///     let mut x = 0;
+///     // Reported as `x` instead of a generated name.
+///     symbolic_named(&mut x, "x");
+/// }
+/// ```
+#[inline(never)]
+pub fn symbolic_named<T>(value: &mut T, name: &str) {
+    // Keep the name so it is passed to the call.
+    let mut name = name;
+    black_box(&mut name);
+    black_box(value);
+}
+
+/// Fixes `value` to one of the values it can currently have.
+///
+/// Adds a constraint that `value` is equal to a single solution, picked by the solver. This is the
+/// opposite of [`symbolic`], which removes all constraints.
+///
+/// Note that this affects the completeness of the analysis, the other values `value` could have
+/// are never explored on this path, so errors that depend on them can be missed.
+///
+/// # Example
+///
+/// ```rust
+/// # use symex_lib::{concretize, symbolic};
+/// fn foo() {
+///     let mut x = 0u32;
+///     symbolic(&mut x);
+///     // `x` now has a single value, picked by the solver.
+///     concretize(&mut x);
+///     if x == 5 {
+///         // Only explored if the solver picked 5.
+///     }
+/// }
+/// ```
+#[inline(always)]
+pub fn concretize<T>(value: &mut T) {
+    concretize_bytes(value as *mut T as *mut u8, core::mem::size_of::<T>());
+}
+
+/// Same as [`concretize`] for the `size` bytes at `ptr`, the executor hooks this function as it
+/// cannot get the size of `T` from an opaque pointer.
+#[doc(hidden)]
+#[inline(never)]
+pub fn concretize_bytes(ptr: *mut u8, size: usize) {
+    let mut size = size;
+    black_box(&mut size);
+    let mut ptr = ptr;
+    black_box(&mut ptr);
+}
+
 /// Assume the passed value contains a valid representation.
 ///
 /// # Example
@@ -97,6 +181,29 @@ pub fn ignore_path() -> ! {
     unsafe { core::hint::unreachable_unchecked() }
 }
 
+/// Marks a location that should be reachable.
+///
+/// After the analysis the runner reports every marker that was not reached on any path. This
+/// points to dead code, or to a harness that is too constrained to exercise that part of the code.
+///
+/// # Example
+///
+/// ```rust
+/// # use symex_lib::reachable;
+/// fn foo(var: i32) -> i32 {
+///     if var > 10 {
+///         // Reported if no path with `var > 10` is found.
+///         reachable();
+///     }
+///     var
+/// }
+/// ```
+#[inline(never)]
+pub fn reachable() {
+    let mut marker = 0u8;
+    black_box(&mut marker);
+}
+
 /// Try and trick the optimizer.
 ///
 /// It is hard to create a "can be anything" value in pure rust, this function tries to trick the
@@ -1,7 +1,7 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 #[proc_macro_derive(Validate)]
@@ -11,7 +11,7 @@ pub fn validate_macro_derive(input: TokenStream) -> TokenStream {
 
     let id = input.ident;
 
-    let exp = match input.data {
+    let body = match input.data {
         Data::Enum(de) => {
             let mut variants = vec![];
 
@@ -39,8 +39,34 @@ pub fn validate_macro_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
-            variants
+            quote!(
+                #(#variants)*
+                {
+                     symex_lib::ignore_path()
+                }
+            )
         }
+        // Bind the fields by reference the same way as for enum variants, so each field is
+        // checked with the same `is_valid` as a field of a variant.
+        Data::Struct(ds) => match ds.fields {
+            Fields::Named(f) => {
+                let mut fields = vec![];
+                for n in f.named {
+                    let f_id = n.ident;
+                    fields.push(quote!(#f_id));
+                }
+
+                quote!(let #id { #(#fields, )* } = self; #(#fields.is_valid() &&)* true)
+            }
+            Fields::Unnamed(f) => {
+                let fields: Vec<_> = (0..f.unnamed.len())
+                    .map(|i| format_ident!("f{}", i))
+                    .collect();
+
+                quote!(let #id(#(#fields, )*) = self; #(#fields.is_valid() &&)* true)
+            }
+            Fields::Unit => quote!(true),
+        },
         _ => {
             panic!("not supported")
         }
@@ -49,10 +75,10 @@ pub fn validate_macro_derive(input: TokenStream) -> TokenStream {
     let expanded = quote!(
         impl symex_lib::Valid for #id {
             fn is_valid(&self) -> bool {
-                #(#exp)*
-                {
-                     symex_lib::ignore_path()
-                }
+                // The fields are checked with method calls, which needs the trait in scope.
+                #[allow(unused_imports)]
+                use symex_lib::Valid as _;
+                #body
             }
         }
     );
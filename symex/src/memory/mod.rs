@@ -1,10 +1,22 @@
 mod linear_allocator;
 mod object_memory;
 
-pub use object_memory::ObjectMemory;
+pub use object_memory::{MemorySnapshot, ObjectMemory};
 
 use crate::smt::SolverError;
 
+/// Value of memory that is read before anything has been written to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UninitializedMemory {
+    /// Each allocation starts out unconstrained, so reads can return any value.
+    #[default]
+    Symbolic,
+
+    /// Every byte of a new allocation has this value, e.g. to mimic memory that is zeroed or
+    /// filled with a known pattern.
+    Byte(u8),
+}
+
 /// The number of bits per byte the memory system expects.
 pub const BITS_IN_BYTE: u32 = 8;
 
@@ -54,6 +66,14 @@ pub enum MemoryError {
     #[error("Out of bounds")]
     OutOfBounds,
 
+    /// Tried to read, write or free an allocation that has already been freed.
+    #[error("Use after free")]
+    UseAfterFree,
+
+    /// Tried to free an address that is not the start of an allocation.
+    #[error("Free of an address that is not the start of an allocation")]
+    InvalidFree,
+
     /// Errors passed on from the solver.
     #[error(transparent)]
     Solver(#[from] SolverError),
@@ -1,20 +1,24 @@
 //! Object memory
 //!
-use std::collections::BTreeMap;
-use tracing::{trace, warn};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::trace;
 
-use super::MemoryError;
+use super::{MemoryError, UninitializedMemory, BITS_IN_BYTE};
 use crate::{
     memory::linear_allocator::LinearAllocator,
     smt::{DContext, DExpr, DSolver, Solutions},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MemoryObject {
     address: u64,
 
     size: u64,
 
+    /// Size in bits of an allocation with a symbolic size, `size` is then the largest value it
+    /// can take. See [`ObjectMemory::allocate_symbolic`].
+    symbolic_size: Option<DExpr>,
+
     bv: DExpr,
 }
 
@@ -22,6 +26,21 @@ impl MemoryObject {
     pub fn bit_size(&self) -> u64 {
         self.size
     }
+
+    /// Size in bits if the allocation has a symbolic size.
+    pub fn symbolic_size(&self) -> Option<&DExpr> {
+        self.symbolic_size.as_ref()
+    }
+}
+
+/// Contents and allocations of an [`ObjectMemory`] at some point, see
+/// [`ObjectMemory::snapshot`].
+#[derive(Debug, Clone)]
+pub struct MemorySnapshot {
+    allocator: LinearAllocator,
+    objects: BTreeMap<u64, MemoryObject>,
+    freed: BTreeSet<u64>,
+    alloc_id: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +52,10 @@ pub struct ObjectMemory {
 
     objects: BTreeMap<u64, MemoryObject>,
 
+    /// Base addresses of the allocations that have been freed. Addresses are never reused, so the
+    /// base address identifies the allocation.
+    freed: BTreeSet<u64>,
+
     solver: DSolver,
 
     ptr_size: u32,
@@ -46,6 +69,7 @@ impl ObjectMemory {
             ctx,
             allocator: LinearAllocator::new(),
             objects: BTreeMap::new(),
+            freed: BTreeSet::new(),
             ptr_size,
             alloc_id: 0,
             solver,
@@ -56,26 +80,145 @@ impl ObjectMemory {
         self.objects.get(&address)
     }
 
+    /// Save the current contents and allocations, to be restored with [`Self::restore`].
+    ///
+    /// Expressions are never modified, a write replaces the expression of the allocation, so this
+    /// only copies the handles of the allocations and not their contents.
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            allocator: self.allocator.clone(),
+            objects: self.objects.clone(),
+            freed: self.freed.clone(),
+            alloc_id: self.alloc_id,
+        }
+    }
+
+    /// Restore the contents and allocations from `snapshot`, undoing all writes and allocations
+    /// made after it was taken.
+    pub fn restore(&mut self, snapshot: MemorySnapshot) {
+        self.allocator = snapshot.allocator;
+        self.objects = snapshot.objects;
+        self.freed = snapshot.freed;
+        self.alloc_id = snapshot.alloc_id;
+    }
+
+    /// Check if both memories have the same allocations with the same contents.
+    pub fn same_contents(&self, other: &Self) -> bool {
+        self.objects == other.objects && self.freed == other.freed
+    }
+
+    /// Check if `address` is inside an allocation that has been freed.
+    pub fn is_freed(&self, address: u64) -> bool {
+        match self.allocation_containing(address) {
+            Some((base, _)) => self.freed.contains(&base),
+            None => false,
+        }
+    }
+
+    /// Get the base address and size in bits of the allocation containing `address`.
+    pub fn allocation_containing(&self, address: u64) -> Option<(u64, u64)> {
+        let (base, obj) = self.objects.range(0..=address).next_back()?;
+        let end = base + obj.size / BITS_IN_BYTE as u64;
+        (address < end).then_some((*base, obj.size))
+    }
+
     /// Allocate `bits` of memory returning the newly allocated address.
-    #[tracing::instrument(skip(self))]
     pub fn allocate(&mut self, bits: u64, align: u64) -> Result<u64, MemoryError> {
+        self.allocate_with(bits, align, UninitializedMemory::Symbolic)
+    }
+
+    /// Allocate `bits` of memory where reads before any write return the value given by
+    /// `uninitialized`.
+    #[tracing::instrument(skip(self))]
+    pub fn allocate_with(
+        &mut self,
+        bits: u64,
+        align: u64,
+        uninitialized: UninitializedMemory,
+    ) -> Result<u64, MemoryError> {
         let (addr, _bytes) = self.allocator.get_address(bits, align)?;
 
-        let name = format!("alloc{}-{}", self.alloc_id, rand::random::<u32>());
+        let name = self.ctx.fresh_name(&format!("alloc{}", self.alloc_id));
         trace!(name = name, addr = format!("{addr:?}"), bits = bits);
         self.alloc_id += 1;
 
+        let bv = match uninitialized {
+            UninitializedMemory::Symbolic => self.ctx.unconstrained(bits as u32, &name),
+            UninitializedMemory::Byte(byte) => {
+                let byte = format!("{byte:08b}");
+                let bytes = (bits as usize + BITS_IN_BYTE as usize - 1) / BITS_IN_BYTE as usize;
+                let value = byte.repeat(bytes);
+                let start = value.len() - bits as usize;
+                self.ctx.from_binary_string(&value[start..])
+            }
+        };
+
         let obj = MemoryObject {
             //name: name.clone(),
             address: addr,
             size: bits,
-            bv: self.ctx.unconstrained(bits as u32, &name),
+            symbolic_size: None,
+            bv,
         };
         self.objects.insert(addr, obj);
 
         Ok(addr)
     }
 
+    /// Allocate memory for a symbolic size of `bits` bits, returning the newly allocated address.
+    ///
+    /// The largest value `bits` can take is allocated, and `bits` is kept as the size of the
+    /// allocation so accesses can be checked against it, see [`MemoryObject::symbolic_size`].
+    /// Sizes larger than `max_bits` are not considered, if `bits` can be larger `max_bits` are
+    /// allocated and `bits` is constrained to fit. Returns [`MemoryError::AddressSpaceExhausted`]
+    /// if `bits` is always larger than `max_bits`. Only sizes of at most 64 bits are supported.
+    pub fn allocate_symbolic(
+        &mut self,
+        bits: &DExpr,
+        align: u64,
+        max_bits: u64,
+        uninitialized: UninitializedMemory,
+    ) -> Result<u64, MemoryError> {
+        trace!("allocate_symbolic bits={bits:?}, max_bits={max_bits}");
+        let min = self.solver.min_unsigned(bits)?;
+        if min > max_bits {
+            return Err(MemoryError::AddressSpaceExhausted(min));
+        }
+
+        let max = self.solver.max_unsigned(bits)?;
+        if max > max_bits {
+            let bound = self.ctx.from_u64(max_bits, bits.len());
+            self.solver.assert(&bits.ulte(&bound));
+        }
+
+        // Round up to whole bytes, and allocate at least one byte so the address is unique.
+        let size = max.min(max_bits).max(1);
+        let size = size.div_ceil(BITS_IN_BYTE as u64) * BITS_IN_BYTE as u64;
+        let addr = self.allocate_with(size, align, uninitialized)?;
+        if let Some(obj) = self.objects.get_mut(&addr) {
+            obj.symbolic_size = Some(bits.clone());
+        }
+        Ok(addr)
+    }
+
+    /// Free the allocation starting at `address`.
+    ///
+    /// The allocation is kept so later accesses to it can be detected, reading or writing it
+    /// returns [`MemoryError::UseAfterFree`].
+    pub fn deallocate(&mut self, addr: &DExpr) -> Result<(), MemoryError> {
+        trace!("deallocate addr={addr:?}");
+        assert_eq!(addr.len(), self.ptr_size, "passed wrong sized address");
+
+        let addr = addr.get_constant().unwrap();
+        if !self.objects.contains_key(&addr) {
+            return Err(MemoryError::InvalidFree);
+        }
+        match self.freed.insert(addr) {
+            true => Ok(()),
+            false => Err(MemoryError::UseAfterFree),
+        }
+    }
+
     /// Read `bits` from `address`.
     #[tracing::instrument(skip(self))]
     pub fn read(&self, addr: &DExpr, bits: u32) -> Result<DExpr, MemoryError> {
@@ -108,6 +251,94 @@ impl ObjectMemory {
         Ok(())
     }
 
+    /// Write the concrete `bytes` starting at `address`.
+    ///
+    /// All bytes are combined into a single value, so the write is as cheap as writing one value.
+    pub fn write_bytes(&mut self, addr: &DExpr, bytes: &[u8]) -> Result<(), MemoryError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        // Memory is little endian, so the first byte ends up in the least significant bits.
+        let value = bytes
+            .iter()
+            .rev()
+            .map(|byte| format!("{byte:08b}"))
+            .collect::<String>();
+        let value = self.ctx.from_binary_string(&value);
+        self.write(addr, value)
+    }
+
+    /// Write a value to a symbolic `address` which is known to be inside the allocation at `base`.
+    ///
+    /// The caller is responsible for constraining the address to be in bounds, see
+    /// [`resolve_allocations`](Self::resolve_allocations).
+    pub fn write_in_allocation(
+        &mut self,
+        base: u64,
+        addr: &DExpr,
+        value: DExpr,
+    ) -> Result<(), MemoryError> {
+        trace!("write base={base:#x}, addr={addr:?}, len={}", value.len());
+        assert_eq!(addr.len(), self.ptr_size, "passed wrong sized address");
+
+        if self.freed.contains(&base) {
+            return Err(MemoryError::UseAfterFree);
+        }
+        let obj = self
+            .objects
+            .get_mut(&base)
+            .expect("Memory object not found");
+        let base = self.ctx.from_u64(base, self.ptr_size);
+        let size = obj.size as u32;
+        if value.len() == size {
+            obj.bv = value;
+            return Ok(());
+        }
+
+        // Shift the value into place and mask out the old value at the same location.
+        let offset = addr.sub(&base).resize_unsigned(size);
+        let offset = offset.mul(&self.ctx.from_u64(BITS_IN_BYTE as u64, size));
+        let mask = self.ctx.low_mask(value.len(), size).sll(&offset);
+        let value = value.zero_ext(size).sll(&offset);
+        obj.bv = obj.bv.and(&mask.not()).or(&value);
+
+        Ok(())
+    }
+
+    /// Copy `bytes` bytes from `src` to `dst`.
+    ///
+    /// When both addresses are concrete and the size in bits fits in a `u32` the whole region is
+    /// read and written at once, otherwise the region is copied byte by byte. The source is read
+    /// before anything is written, so the regions are allowed to overlap.
+    pub fn copy(&mut self, dst: &DExpr, src: &DExpr, bytes: u64) -> Result<(), MemoryError> {
+        trace!("copy dst={dst:?}, src={src:?}, bytes={bytes}");
+        if bytes == 0 {
+            return Ok(());
+        }
+
+        let bits = bytes
+            .checked_mul(BITS_IN_BYTE as u64)
+            .and_then(|bits| u32::try_from(bits).ok());
+        if let (Some(_), Some(_), Some(bits)) = (dst.get_constant(), src.get_constant(), bits) {
+            let value = self.read(src, bits)?;
+            return self.write(dst, value);
+        }
+
+        let values = (0..bytes)
+            .map(|byte| {
+                let offset = self.ctx.from_u64(byte, self.ptr_size);
+                self.read(&src.add(&offset), BITS_IN_BYTE)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (byte, value) in values.into_iter().enumerate() {
+            let offset = self.ctx.from_u64(byte as u64, self.ptr_size);
+            self.write(&dst.add(&offset), value)?;
+        }
+        Ok(())
+    }
+
     /// For a symbolic address, get addresses to read or write from.
     ///
     /// Certain memory models may not support fully symbolic pointers. This function allows the
@@ -117,26 +348,86 @@ impl ObjectMemory {
         &self,
         address: &DExpr,
         upper_bound: usize,
-    ) -> Result<Vec<DExpr>, MemoryError> {
+    ) -> Result<Solutions<DExpr>, MemoryError> {
         // Fast path if address is a constant.
         if let Some(_) = address.get_constant() {
-            return Ok(vec![address.clone()]);
+            return Ok(Solutions::Exactly(vec![address.clone()]));
         }
 
         // Otherwise, get solutions for addresses.
-        let addresses = self.solver.get_values(address, upper_bound)?;
-        let addresses = match addresses {
-            Solutions::Exactly(s) => s,
-            Solutions::AtLeast(s) => {
-                warn!(
-                    "More than {} possible addresses, suppressing other paths",
-                    upper_bound
-                );
-                s
+        Ok(self.solver.get_values(address, upper_bound)?)
+    }
+
+    /// Find the allocations that an access of `bytes` bytes at a symbolic `address` can be
+    /// completely inside of.
+    ///
+    /// Returns the base address of each allocation together with the constraint for the access to
+    /// be inside of it. Allocations that have been freed are treated as outside of all
+    /// allocations. At most `upper_bound` solutions of `address` are tried, if the address can
+    /// take more values than that the allocations found so far are returned as
+    /// [`Solutions::AtLeast`].
+    pub fn resolve_allocations(
+        &self,
+        address: &DExpr,
+        bytes: u64,
+        upper_bound: usize,
+    ) -> Result<Solutions<(u64, DExpr)>, MemoryError> {
+        // Exclude each found allocation so the next solution is somewhere else.
+        self.solver.push();
+        let result = || {
+            let mut allocations = Vec::new();
+            for _ in 0..upper_bound {
+                if !self.solver.is_sat()? {
+                    return Ok(Solutions::Exactly(allocations));
+                }
+
+                let value = self.solver.get_value(address)?.get_constant().unwrap();
+                let object = self
+                    .objects
+                    .range(0..=value)
+                    .next_back()
+                    .map(|(_, obj)| obj);
+                match object {
+                    Some(obj)
+                        if !self.freed.contains(&obj.address)
+                            && value - obj.address + bytes <= obj.size / BITS_IN_BYTE as u64 =>
+                    {
+                        let start = self.ctx.from_u64(obj.address, self.ptr_size);
+                        let end = obj.address + obj.size / BITS_IN_BYTE as u64 - bytes;
+                        let end = self.ctx.from_u64(end, self.ptr_size);
+                        let in_allocation = address.ugte(&start).and(&address.ulte(&end));
+                        self.solver.assert(&in_allocation.not());
+
+                        // With a symbolic size the access must also fit in the actual size.
+                        let in_bounds = match &obj.symbolic_size {
+                            Some(size) => {
+                                let eight = self.ctx.from_u64(BITS_IN_BYTE as u64, size.len());
+                                let bits =
+                                    self.ctx.from_u64(bytes * BITS_IN_BYTE as u64, size.len());
+                                let offset = address.sub(&start).resize_unsigned(size.len());
+                                let access_end = offset.mul(&eight).add(&bits);
+                                in_allocation.and(&access_end.ulte(size))
+                            }
+                            None => in_allocation,
+                        };
+                        allocations.push((obj.address, in_bounds));
+                    }
+                    _ => {
+                        let value = self.ctx.from_u64(value, self.ptr_size);
+                        self.solver.assert(&address._ne(&value));
+                    }
+                }
+            }
+
+            match self.solver.is_sat()? {
+                true => Ok(Solutions::AtLeast(allocations)),
+                false => Ok(Solutions::Exactly(allocations)),
             }
         };
+        let result = result();
+        self.solver.pop();
 
-        Ok(addresses)
+        result
     }
 
     fn resolve_address(&self, address: &DExpr) -> Result<(u64, &MemoryObject), MemoryError> {
@@ -145,6 +436,9 @@ impl ObjectMemory {
         // Get the memory object with the address that is the closest below the passed address.
         for obj in self.objects.range(0..=address).rev().take(1) {
             // TODO: Perform bounds check.
+            if self.freed.contains(obj.0) {
+                return Err(MemoryError::UseAfterFree);
+            }
             return Ok((address, obj.1));
         }
 
@@ -160,9 +454,117 @@ impl ObjectMemory {
         // Get the memory object with the address that is the closest below the passed address.
         for obj in self.objects.range_mut(0..=address).rev().take(1) {
             // TODO: Perform bounds check.
+            if self.freed.contains(obj.0) {
+                return Err(MemoryError::UseAfterFree);
+            }
             return Ok((address, obj.1));
         }
 
         panic!("Memory object not found");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> ObjectMemory {
+        let ctx = Box::leak(Box::new(DContext::new()));
+        ObjectMemory::new(ctx, 64, DSolver::new(ctx))
+    }
+
+    #[test]
+    fn write_bytes_reads_back() {
+        let mut memory = memory();
+        let addr = memory.allocate(64, 1).unwrap();
+        let addr = memory.ctx.from_u64(addr, 64);
+
+        memory
+            .write_bytes(&addr, &[0xde, 0xad, 0xbe, 0xef])
+            .unwrap();
+        let value = memory.read(&addr, 32).unwrap();
+        assert_eq!(value.get_constant(), Some(0xefbeadde));
+
+        let second = addr.add(&memory.ctx.from_u64(1, 64));
+        let value = memory.read(&second, 8).unwrap();
+        assert_eq!(value.get_constant(), Some(0xad));
+    }
+
+    #[test]
+    fn write_bytes_keeps_surrounding_memory() {
+        let mut memory = memory();
+        let addr = memory.allocate(32, 1).unwrap();
+        let addr = memory.ctx.from_u64(addr, 64);
+        let zero = memory.ctx.zero(32);
+        memory.write(&addr, zero).unwrap();
+
+        let second = addr.add(&memory.ctx.from_u64(1, 64));
+        memory.write_bytes(&second, &[0x12, 0x34]).unwrap();
+        memory.write_bytes(&second, &[]).unwrap();
+        let value = memory.read(&addr, 32).unwrap();
+        assert_eq!(value.get_constant(), Some(0x00341200));
+    }
+
+    #[test]
+    fn restore_undoes_writes_and_allocations() {
+        let mut memory = memory();
+        let addr = memory.allocate(32, 1).unwrap();
+        let addr = memory.ctx.from_u64(addr, 64);
+        let (one, two) = (memory.ctx.from_u64(1, 32), memory.ctx.from_u64(2, 32));
+        memory.write(&addr, one).unwrap();
+
+        let snapshot = memory.snapshot();
+        memory.write(&addr, two).unwrap();
+        let discarded = memory.allocate(32, 1).unwrap();
+        memory.restore(snapshot);
+
+        let value = memory.read(&addr, 32).unwrap();
+        assert_eq!(value.get_constant(), Some(1));
+        assert!(memory.get_object(discarded).is_none());
+        assert_eq!(memory.allocate(32, 1).unwrap(), discarded);
+    }
+
+    #[test]
+    fn allocate_symbolic_allocates_largest_size() {
+        let mut memory = memory();
+        let bits = memory.ctx.unconstrained(64, "bits");
+        let symbolic = UninitializedMemory::Symbolic;
+        memory
+            .solver
+            .assert(&bits.ulte(&memory.ctx.from_u64(32, 64)));
+
+        let addr = memory.allocate_symbolic(&bits, 1, 64, symbolic).unwrap();
+        assert_eq!(memory.allocation_containing(addr), Some((addr, 32)));
+        let object = memory.get_object(addr).unwrap();
+        assert_eq!(object.symbolic_size(), Some(&bits));
+
+        // Larger sizes than the limit are excluded from the path.
+        let addr = memory.allocate_symbolic(&bits, 1, 16, symbolic).unwrap();
+        assert_eq!(memory.allocation_containing(addr), Some((addr, 16)));
+        assert_eq!(memory.solver.max_unsigned(&bits), Ok(16));
+
+        memory
+            .solver
+            .assert(&bits.ugte(&memory.ctx.from_u64(8, 64)));
+        assert_eq!(
+            memory.allocate_symbolic(&bits, 1, 4, symbolic),
+            Err(MemoryError::AddressSpaceExhausted(8))
+        );
+    }
+
+    #[test]
+    fn accesses_after_deallocate_fail() {
+        let mut memory = memory();
+        let addr = memory.allocate(32, 1).unwrap();
+        let addr = memory.ctx.from_u64(addr, 64);
+        let second = addr.add(&memory.ctx.from_u64(1, 64));
+
+        assert_eq!(memory.deallocate(&second), Err(MemoryError::InvalidFree));
+        memory.deallocate(&addr).unwrap();
+        assert!(memory.is_freed(second.get_constant().unwrap()));
+        assert_eq!(memory.read(&second, 8), Err(MemoryError::UseAfterFree));
+        let value = memory.ctx.zero(32);
+        assert_eq!(memory.write(&addr, value), Err(MemoryError::UseAfterFree));
+        assert_eq!(memory.deallocate(&addr), Err(MemoryError::UseAfterFree));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //!
 use std::{
-    path::Path,
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
@@ -10,14 +12,25 @@ use rustc_demangle::demangle;
 use tracing::{debug, info};
 
 use crate::{
-    smt::DContext,
-    util::{ErrorReason, ExpressionType, LineTrace, PathStatus, Variable, VisualPathResult},
-    vm::{AnalysisError, LLVMExecutorError, LLVMState, PathResult, Project, VM},
+    memory::UninitializedMemory,
+    smt::{DContext, SolverConfig},
+    util::{
+        ErrorReason, ExpressionType, Limit, LineTrace, PathStatus, Signed, SolutionCount, Variable,
+        VisualPathResult,
+    },
+    vm::{
+        type_to_expr_type, AnalysisError, BFSPathSelection, LLVMExecutorError, LLVMState,
+        PathResult, Progress, Project, RandomPathSelection, UnknownFunctionPolicy, VM,
+    },
 };
 
 #[derive(Debug)]
 pub struct RunConfig {
     /// Which paths should the solver be invoked on.
+    ///
+    /// Paths that are not solved are still explored and counted in the summary, but are not
+    /// reported. The `solve_*` options below select which values are solved for the paths that
+    /// are.
     pub solve_for: SolveFor,
 
     /// If concretized inputs should be shown.
@@ -28,22 +41,158 @@ pub struct RunConfig {
 
     /// If concretized output values should be shown.
     pub solve_output: bool,
+
+    /// If set, the tree of explored paths is written as a Graphviz DOT graph to this file.
+    pub dot_output: Option<PathBuf>,
+
+    /// Tuning options for the solver.
+    pub solver: SolverConfig,
+
+    /// If the global constructors and destructors should be run before and after the entry
+    /// function. Off by default since they are executed symbolically and can fork paths.
+    pub run_global_ctors: bool,
+
+    /// If the solved inputs and symbolic values should be minimized before being reported.
+    ///
+    /// Each value is constrained to its smallest possible value, in order, which gives more
+    /// readable reproducers at the cost of some extra solver queries per path.
+    pub minimize_witnesses: bool,
+
+    /// If paths should continue in the landing pad after a panic, as if the panic unwound. The
+    /// panic is still reported. Off by default.
+    pub continue_after_panic: bool,
+
+    /// If set, the constraints of each reported path are written to this directory as an SMT-LIB
+    /// script `path-<n>.smt2`, with the taken branches listed in comments.
+    pub constraint_log: Option<PathBuf>,
+
+    /// Value read from memory that has been allocated but not yet written to. Symbolic by default.
+    pub uninitialized_memory: UninitializedMemory,
+
+    /// If the `core::fmt` formatting machinery should be stubbed out instead of analyzed.
+    pub stub_formatting: bool,
+
+    /// Experimental: summarize loops over symbolic bounds instead of unrolling them. This is
+    /// unsound, see [`VM::loop_summary`] for the limitations.
+    pub loop_summary: bool,
+
+    /// Inline leaf functions with at most this many instructions, see [`VM::inline_threshold`].
+    /// Zero disables inlining.
+    pub inline_threshold: usize,
+
+    /// If the functions entered and exited on each path should be reported.
+    pub call_trace: bool,
+
+    /// If the number of iterations of each loop on each path should be reported.
+    pub loop_counts: bool,
+
+    /// If paths should fail when a loop repeats the same state, see
+    /// [`VM::detect_non_termination`].
+    pub detect_infinite_loops: bool,
+
+    /// If loads and stores should fail when their address can be misaligned, see
+    /// [`VM::check_alignment`].
+    pub check_alignment: bool,
+
+    /// What to do when calling a function without a definition, see [`UnknownFunctionPolicy`].
+    pub unknown_function_policy: UnknownFunctionPolicy,
+
+    /// If a status line with the progress should be shown while running.
+    pub progress: bool,
+
+    /// What to do when a reported value has more solutions than the one that is shown.
+    pub max_solutions_exceeded_action: MaxSolutionsExceeded,
+
+    /// How much of each path is printed.
+    pub verbosity: Verbosity,
+
+    /// If integers are printed as signed decimal values instead of unsigned hexadecimal values.
+    pub signed_integers: bool,
+
+    /// Order in which paths are explored.
+    pub search_strategy: SearchStrategy,
+
+    /// Maximum number of instructions executed on a single path, if any.
+    pub max_iter_count: Option<u64>,
+
+    /// Maximum depth of the call stack.
+    pub max_call_depth: usize,
+
+    /// Stop exploring new paths after this long, if set.
+    ///
+    /// The time is checked between paths, so the path that is running when the timeout is reached
+    /// is finished first. A run that times out gives incomplete results by design, paths that were
+    /// not explored yet are not reported, see [`RunOutput::timed_out`]. A path that never ends is
+    /// not interrupted, so set [`max_iter_count`](Self::max_iter_count) as well to bound the time
+    /// spent on a single path.
+    pub timeout: Option<Duration>,
 }
 
 impl RunConfig {
     /// Determine if the solver should be invoked this specific result.
     ///
     /// Returns true of all paths should be solved, or if the result variant matches the given
-    /// `SolveFor`.
-    fn should_solve(&self, result: &PathResult) -> bool {
+    /// `SolveFor`. `solved` is the number of paths that have been solved so far.
+    fn should_solve(&self, result: &PathResult, solved: usize) -> bool {
         match self.solve_for {
             SolveFor::All => true,
             SolveFor::Error => matches!(result, PathResult::Success(_)),
             SolveFor::Success => matches!(result, PathResult::Failure(_)),
+            SolveFor::FirstN(n) => solved < n,
+            SolveFor::Sampled { total_budget } => {
+                // Halve the chance for each solved path, so the samples are spread out over the
+                // whole run instead of only covering the first paths.
+                solved < total_budget && rand::random::<f64>() < 0.5f64.powi(solved as i32)
+            }
         }
     }
 }
 
+/// How much of each path is printed while running, the summary is always printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only print the failed paths.
+    Quiet,
+
+    /// Print all reported paths.
+    #[default]
+    Normal,
+
+    /// Print all reported paths together with the condition of each symbolic branch taken.
+    Verbose,
+}
+
+/// What to do when a reported input, symbolic value or output can take more than the one value
+/// that is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaxSolutionsExceeded {
+    /// Only show one of the solutions.
+    #[default]
+    Truncate,
+
+    /// Fail the run with [`LLVMExecutorError::TooManySolutions`], so under-constrained values are
+    /// noticed in e.g. CI.
+    Error,
+
+    /// Count the solutions of each value, up to the given cap, and report the counts.
+    Count(usize),
+}
+
+/// Order in which the paths are explored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// Run each path to the end before continuing with the most recently created path.
+    #[default]
+    DepthFirst,
+
+    /// Run the paths in the order they were created, which finds the paths that take the fewest
+    /// branches first.
+    BreadthFirst,
+
+    /// Run a random path next, the same seed explores the paths in the same order.
+    Random { seed: u64 },
+}
+
 /// Determine for which types of paths the solver should be invoked on.
 #[derive(Debug)]
 pub enum SolveFor {
@@ -55,63 +204,302 @@ pub enum SolveFor {
 
     /// Paths which are sucessful.
     Success,
+
+    /// The first `n` paths.
+    FirstN(usize),
+
+    /// A random sample of at most `total_budget` paths.
+    ///
+    /// The first path is always solved, after that each path is solved with a chance that halves
+    /// for every solved path. This gives a quick overview of large path spaces.
+    Sampled { total_budget: usize },
+}
+
+/// Reported paths of a run.
+#[derive(Debug)]
+pub struct RunOutput {
+    /// The reported paths, in the order they were explored.
+    pub results: Vec<VisualPathResult>,
+
+    /// If the run stopped at [`RunConfig::timeout`], so not all paths were explored.
+    pub timed_out: bool,
 }
 
 pub fn run(
     path: impl AsRef<Path>,
     function: impl AsRef<str>,
     cfg: &RunConfig,
-) -> Result<Vec<VisualPathResult>, LLVMExecutorError> {
+) -> Result<RunOutput, LLVMExecutorError> {
     // As a temporary measure both the smt context and project get leaked, this is only so I don't
     // have to care about those lifetimes, since they always live for the entire duration of the
     // run anyway.
-    let context = Box::new(DContext::new());
+    let context = Box::new(DContext::with_config(&cfg.solver));
     let context = Box::leak(context);
 
-    let project = Box::new(Project::from_path(path).unwrap());
+    let mut project = Box::new(Project::from_path(path).unwrap());
+    project.set_fmt_hooks(cfg.stub_formatting);
     let project = Box::leak(project);
 
     info!("create VM");
-    let mut vm = VM::new(project, context, function.as_ref())?;
+    let mut vm = match cfg.run_global_ctors {
+        true => VM::new_with_global_ctors(project, context, function.as_ref())?,
+        false => VM::new(project, context, function.as_ref())?,
+    };
+    vm.record_path_conditions = cfg.dot_output.is_some();
+    vm.continue_after_panic = cfg.continue_after_panic;
+    vm.log_constraints = cfg.constraint_log.is_some() || cfg.verbosity == Verbosity::Verbose;
+    vm.uninitialized_memory = cfg.uninitialized_memory;
+    vm.loop_summary = cfg.loop_summary;
+    vm.inline_threshold = cfg.inline_threshold;
+    vm.log_calls = cfg.call_trace;
+    vm.log_block_visits = cfg.loop_counts;
+    vm.detect_non_termination = cfg.detect_infinite_loops;
+    vm.check_alignment = cfg.check_alignment;
+    vm.unknown_function_policy = cfg.unknown_function_policy;
+    vm.max_iter_count = cfg.max_iter_count;
+    vm.max_call_depth = cfg.max_call_depth;
+    match cfg.search_strategy {
+        SearchStrategy::DepthFirst => {}
+        SearchStrategy::BreadthFirst => vm.set_path_selection(Box::new(BFSPathSelection::new())),
+        SearchStrategy::Random { seed } => {
+            vm.set_path_selection(Box::new(RandomPathSelection::new(seed)))
+        }
+    }
+    if cfg.progress {
+        vm.set_progress_callback(PROGRESS_INTERVAL, Box::new(print_progress));
+    }
+    if let Some(dir) = &cfg.constraint_log {
+        if let Err(err) = fs::create_dir_all(dir) {
+            println!(
+                "Failed to create constraint log directory {}: {err}",
+                dir.display()
+            );
+        }
+    }
     info!("run paths");
     let result = run_paths(&mut vm, cfg)?;
+    if cfg.progress {
+        // Leave the last status line in place.
+        eprintln!();
+    }
 
-    println!("Paths: {}, took: {:?}", result.num_paths, result.duration);
+    println!("{}", summary(&result, vm.coverage.percent()));
+    for marker in vm.unreached_markers() {
+        println!("Unreached marker: {marker}");
+    }
+
+    if let Some(dot_path) = &cfg.dot_output {
+        if let Err(err) = fs::write(dot_path, vm.path_tree.to_dot()) {
+            println!("Failed to write path tree to {}: {err}", dot_path.display());
+        }
+    }
     // println!(
     //     "Instructions processed: {}",
     //     vm.stats.instructions_processed
     // );
 
-    Ok(result.results)
+    Ok(RunOutput {
+        results: result.results,
+        timed_out: result.timed_out,
+    })
+}
+
+/// Number of executed instructions between each update of the progress line.
+const PROGRESS_INTERVAL: u64 = 100_000;
+
+/// Overwrite the status line on stderr with the current progress.
+fn print_progress(progress: &Progress) {
+    eprint!(
+        "\r{} paths done, {} pending, {} instructions, {:.1?}",
+        progress.paths_completed, progress.paths_pending, progress.instructions, progress.elapsed
+    );
 }
 
 struct RunnerResult {
     num_paths: usize,
+    num_suppressed: usize,
+    /// Number of paths that diverged in an entry function that never returns.
+    num_diverged: usize,
+    /// Number of paths abandoned at an exploration limit.
+    num_incomplete: usize,
+    /// Number of failed paths for each kind of error.
+    failures: BTreeMap<&'static str, usize>,
     duration: Duration,
+    /// Time spent in the solver, part of `duration`.
+    solver_time: Duration,
+    /// If the run stopped at the timeout before all paths were explored.
+    timed_out: bool,
     results: Vec<VisualPathResult>,
 }
 
+/// Create a single line summary of a run, e.g.
+/// `42 paths (3 failed: 2 IndexOutOfBounds, 1 Panic), 1 suppressed, 87% blocks covered, took 1.2s
+/// (0.8s solving)`.
+///
+/// Paths that diverged in an entry function that never returns are listed separately, e.g.
+/// `2 paths, all diverged, ...`. Paths abandoned at an exploration limit are not counted as failed
+/// but as incomplete, e.g. `5 paths, 1 incomplete, ...`. A run that stopped at the timeout ends
+/// with `, timed out`.
+fn summary(result: &RunnerResult, coverage: f64) -> String {
+    let num_failed: usize = result.failures.values().sum();
+    let failures = match num_failed {
+        0 => String::new(),
+        n => {
+            let kinds = result
+                .failures
+                .iter()
+                .map(|(kind, count)| format!("{count} {kind}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(" ({n} failed: {kinds})")
+        }
+    };
+
+    let diverged = match result.num_diverged {
+        0 => String::new(),
+        n if n == result.num_paths => ", all diverged".to_owned(),
+        n => format!(", {n} diverged"),
+    };
+
+    let incomplete = match result.num_incomplete {
+        0 => String::new(),
+        n => format!(", {n} incomplete"),
+    };
+
+    let timed_out = match result.timed_out {
+        true => ", timed out",
+        false => "",
+    };
+
+    let covered = format!(
+        "{coverage:.0}% blocks covered, took {:.1?} ({:.1?} solving){timed_out}",
+        result.duration, result.solver_time
+    );
+    format!(
+        "{} paths{failures}{diverged}{incomplete}, {} suppressed, {covered}",
+        result.num_paths, result.num_suppressed
+    )
+}
+
+/// The exploration limit an error is caused by, if any.
+fn exploration_limit(error: &AnalysisError) -> Option<Limit> {
+    match error {
+        AnalysisError::IterationCountExceeded { .. } => Some(Limit::IterationCount),
+        AnalysisError::CallDepthExceeded { .. } => Some(Limit::CallDepth),
+        _ => None,
+    }
+}
+
+/// Name of the kind of error, used to group failures in the summary.
+fn error_kind(error: &AnalysisError) -> &'static str {
+    match error {
+        AnalysisError::Panic => "Panic",
+        AnalysisError::Unreachable => "Unreachable",
+        AnalysisError::IndexOutOfBounds { .. } => "IndexOutOfBounds",
+        AnalysisError::InvalidBranchTarget => "InvalidBranchTarget",
+        AnalysisError::OutOfBounds => "OutOfBounds",
+        AnalysisError::PointerOverflow { .. } => "PointerOverflow",
+        AnalysisError::UnsizedType(_) => "UnsizedType",
+        AnalysisError::TooManyAddressResolutions { .. } => "TooManyAddressResolutions",
+        AnalysisError::NonTermination { .. } => "NonTermination",
+        AnalysisError::MisalignedAccess { .. } => "MisalignedAccess",
+        AnalysisError::CallDepthExceeded { .. } => "CallDepthExceeded",
+        AnalysisError::IterationCountExceeded { .. } => "IterationCountExceeded",
+        AnalysisError::UseAfterFree => "UseAfterFree",
+        AnalysisError::InvalidFree => "InvalidFree",
+        AnalysisError::AllocationTooLarge { .. } => "AllocationTooLarge",
+        AnalysisError::AssertionFailed => "AssertionFailed",
+    }
+}
+
+/// SMT-LIB script with the constraints of a path, preceded by comments with the outcome and the
+/// locations of the taken branches in order.
+fn constraint_log(result: &PathResult, state: &LLVMState) -> String {
+    let outcome = match result {
+        PathResult::Failure(error) => error_kind(error),
+        _ => "Success",
+    };
+
+    let mut log = format!("; outcome: {outcome}\n");
+    for decision in state.branch_constraints.iter() {
+        log.push_str(&format!("; branch at {}\n", decision.location));
+    }
+    log.push_str(&state.constraints.to_smt2());
+    log
+}
+
 fn run_paths(vm: &mut VM, cfg: &RunConfig) -> Result<RunnerResult, LLVMExecutorError> {
     // Go through all paths.
 
     let mut results = Vec::new();
     let mut path_num = 0;
+    let mut num_suppressed = 0;
+    let mut num_diverged = 0;
+    let mut num_incomplete = 0;
+    let mut failures = BTreeMap::new();
+
+    let mut solver_time = Duration::ZERO;
 
     let start = Instant::now();
-    while let Some((path_result, mut state)) = vm.run()? {
+    let mut timed_out = false;
+    loop {
+        // Only check between paths, so no path is left half explored.
+        if matches!(cfg.timeout, Some(timeout) if start.elapsed() >= timeout) {
+            timed_out = vm.progress().paths_pending > 0;
+            break;
+        }
+        let Some((path_result, mut state)) = vm.run()? else {
+            break;
+        };
+        // All paths share the solver, so this is the total so far.
+        solver_time = state.constraints.solver_time();
+
         if matches!(path_result, PathResult::Suppress) {
             debug!("Suppressing path");
+            num_suppressed += 1;
             continue;
         }
         if matches!(path_result, PathResult::AssumptionUnsat) {
-            println!("Encountered an unsatisfiable assumption, ignoring this path");
+            if cfg.verbosity > Verbosity::Quiet {
+                println!("Encountered an unsatisfiable assumption, ignoring this path");
+            }
             continue;
         }
 
         path_num += 1;
+        let diverged = vm.is_divergence(&path_result);
+        match &path_result {
+            PathResult::Failure(_) if diverged => num_diverged += 1,
+            PathResult::Failure(error) if exploration_limit(error).is_some() => num_incomplete += 1,
+            PathResult::Failure(error) => *failures.entry(error_kind(error)).or_insert(0) += 1,
+            _ => {}
+        }
+        if let Some(dir) = &cfg.constraint_log {
+            let file = dir.join(format!("path-{path_num}.smt2"));
+            if let Err(err) = fs::write(&file, constraint_log(&path_result, &state)) {
+                println!("Failed to write constraints to {}: {err}", file.display());
+            }
+        }
         // TODO: Cache for solutions.
 
-        if cfg.should_solve(&path_result) {
+        if cfg.should_solve(&path_result, results.len()) {
+            // Count before minimizing, which constrains the values.
+            let solution_counts =
+                count_solutions(vm, &state, &path_result, cfg.max_solutions_exceeded_action)?;
+
+            // The minimized values are only kept while this path's results are solved.
+            let solver = state.constraints.clone();
+            let _minimized = match cfg.minimize_witnesses {
+                true => {
+                    let checkpoint = solver.checkpoint();
+                    for var in vm.inputs.iter().chain(state.marked_symbolic.iter()) {
+                        checkpoint.minimize(&var.value)?;
+                    }
+                    Some(checkpoint)
+                }
+                false => None,
+            };
+
             let inputs = if cfg.solve_inputs {
                 get_values(vm.inputs.iter(), &state)?
             } else {
@@ -124,26 +512,35 @@ fn run_paths(vm: &mut VM, cfg: &RunConfig) -> Result<RunnerResult, LLVMExecutorE
                 vec![]
             };
 
+            let output = match cfg.solve_output {
+                true => vm
+                    .solve_return(&state, &path_result, 1)?
+                    .and_then(|solutions| solutions.into_iter().next()),
+                false => None,
+            };
+
             let result = match path_result {
                 PathResult::Success(value) => {
-                    let value = if let Some(value) = value {
-                        Some(Variable {
+                    let value = match output {
+                        Some(output) => Some(output),
+                        None => value.map(|value| Variable {
                             name: Some("output".to_string()),
-                            value: if cfg.solve_output {
-                                state.constraints.get_value(&value)?
-                            } else {
-                                value
-                            },
+                            value,
                             ty: ExpressionType::Unknown,
-                        })
-                    } else {
-                        None
+                            pointee: None,
+                        }),
                     };
                     PathStatus::Ok(value)
                 }
-                PathResult::Failure(reason) => {
-                    PathStatus::Failed(create_error_reason(&mut state, reason.into()))
+                PathResult::Failure(reason) if diverged => {
+                    PathStatus::Diverged(create_error_reason(&mut state, reason))
                 }
+                PathResult::Failure(reason) => match exploration_limit(&reason) {
+                    Some(limit) => {
+                        PathStatus::Incomplete(limit, create_error_reason(&mut state, reason))
+                    }
+                    None => PathStatus::Failed(create_error_reason(&mut state, reason.into())),
+                },
                 PathResult::Suppress => unreachable!("Suppress is handled above"),
                 PathResult::AssumptionUnsat => unreachable!("AssumptionUnsat is handled above"),
             };
@@ -153,8 +550,21 @@ fn run_paths(vm: &mut VM, cfg: &RunConfig) -> Result<RunnerResult, LLVMExecutorE
                 result,
                 inputs,
                 symbolics,
+                calls: std::mem::take(&mut state.call_log),
+                solution_counts,
+                loops: state.repeated_blocks(),
+                branches: match cfg.verbosity {
+                    Verbosity::Verbose => std::mem::take(&mut state.branch_constraints),
+                    _ => Vec::new(),
+                },
             };
-            println!("{}", path_result);
+            let failed = matches!(path_result.result, PathStatus::Failed(_));
+            if cfg.verbosity > Verbosity::Quiet || failed {
+                match cfg.signed_integers {
+                    true => println!("{}", Signed(&path_result)),
+                    false => println!("{path_result}"),
+                }
+            }
 
             results.push(path_result);
         }
@@ -162,13 +572,103 @@ fn run_paths(vm: &mut VM, cfg: &RunConfig) -> Result<RunnerResult, LLVMExecutorE
 
     Ok(RunnerResult {
         num_paths: path_num,
+        num_suppressed,
+        num_diverged,
+        num_incomplete,
+        failures,
         duration: start.elapsed(),
+        solver_time,
+        timed_out,
         results,
     })
 }
 
+/// Check how many solutions the reported values have, as configured by `action`.
+///
+/// Values with a single solution are not included in the counts.
+fn count_solutions(
+    vm: &VM,
+    state: &LLVMState,
+    result: &PathResult,
+    action: MaxSolutionsExceeded,
+) -> Result<Vec<SolutionCount>, LLVMExecutorError> {
+    let max = match action {
+        MaxSolutionsExceeded::Truncate => return Ok(Vec::new()),
+        MaxSolutionsExceeded::Error => 1,
+        MaxSolutionsExceeded::Count(cap) => cap.max(1),
+    };
+
+    let output = match result {
+        PathResult::Success(Some(value)) => Some(("output".to_owned(), value)),
+        _ => None,
+    };
+    let values = vm
+        .inputs
+        .iter()
+        .chain(state.marked_symbolic.iter())
+        .map(|var| {
+            (
+                var.name.clone().unwrap_or_else(|| "_".to_owned()),
+                &var.value,
+            )
+        })
+        .chain(output);
+
+    let mut counts = Vec::new();
+    for (name, value) in values {
+        let (count, exact) = match state.constraints.count_solutions(value, max)? {
+            Ok(count) => (count, true),
+            Err(count) => (count, false),
+        };
+        if count <= 1 && exact {
+            continue;
+        }
+        if action == MaxSolutionsExceeded::Error {
+            return Err(LLVMExecutorError::TooManySolutions(name));
+        }
+        counts.push(SolutionCount { name, count, exact });
+    }
+
+    Ok(counts)
+}
+
 fn create_error_reason(state: &mut LLVMState, error: AnalysisError) -> ErrorReason {
-    let error_message = format!("{:?}", error);
+    let error_message = match error {
+        AnalysisError::IndexOutOfBounds { index, len } => {
+            format!("Index out of bounds: index = {index}, len = {len}")
+        }
+        AnalysisError::InvalidBranchTarget => {
+            "Indirect branch to an address which is not a listed destination".to_owned()
+        }
+        AnalysisError::PointerOverflow { index } => {
+            format!("Pointer offset overflow: index = {index}")
+        }
+        AnalysisError::UnsizedType(ty) => format!("Size of type {ty} is unknown"),
+        AnalysisError::TooManyAddressResolutions { max } => {
+            format!("Symbolic pointer can point to more than {max} locations")
+        }
+        AnalysisError::NonTermination { function, block } => {
+            format!("Loop at {block} in {function} never terminates")
+        }
+        AnalysisError::MisalignedAccess { align } => {
+            format!("Memory access is not aligned to {align} bytes")
+        }
+        AnalysisError::CallDepthExceeded { max } => {
+            format!("Call depth exceeded the limit of {max}")
+        }
+        AnalysisError::IterationCountExceeded { max } => {
+            format!("Path executed more than {max} instructions")
+        }
+        AnalysisError::UseAfterFree => "Memory is used after it was freed".to_owned(),
+        AnalysisError::InvalidFree => {
+            "Freed a pointer that is not the start of an allocation".to_owned()
+        }
+        AnalysisError::AllocationTooLarge { max } => {
+            format!("Allocation with a symbolic size is always larger than {max} bytes")
+        }
+        AnalysisError::AssertionFailed => "Assertion can fail".to_owned(),
+        error => format!("{:?}", error),
+    };
 
     let error_location = state
         .stack_frames
@@ -190,6 +690,24 @@ fn create_error_reason(state: &mut LLVMState, error: AnalysisError) -> ErrorReas
         let demangled = demangle(&name);
         let function_name = format!("{demangled:#}");
 
+        // Solve the arguments against the model of the failing path. Arguments that cannot be
+        // solved are left out rather than hiding the rest of the trace.
+        let arguments = callstack
+            .function()
+            .parameters()
+            .enumerate()
+            .filter_map(|(n, parameter)| {
+                let value = callstack.get_register(&parameter)?;
+                let value = state.constraints.get_value(value).ok()?;
+                Some(Variable {
+                    name: Some(format!("arg{n}")),
+                    value,
+                    ty: type_to_expr_type(&parameter.ty(), state.project),
+                    pointee: None,
+                })
+            })
+            .collect();
+
         let line = LineTrace {
             function_name,
             line: callstack
@@ -197,6 +715,7 @@ fn create_error_reason(state: &mut LLVMState, error: AnalysisError) -> ErrorReas
                 .map(|instruction| instruction.debug_location())
                 .flatten()
                 .map(|location| format!("{location}")),
+            arguments,
         };
         stack_trace.push(line);
     }
@@ -219,9 +738,64 @@ where
             name: var.name.clone(),
             value: constant,
             ty: var.ty.clone(),
+            pointee: None,
         };
         results.push(var);
     }
 
     Ok(results)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RunConfig {
+        RunConfig {
+            solve_for: SolveFor::All,
+            solve_inputs: true,
+            solve_symbolics: true,
+            solve_output: true,
+            dot_output: None,
+            solver: SolverConfig::default(),
+            run_global_ctors: false,
+            minimize_witnesses: false,
+            continue_after_panic: false,
+            constraint_log: None,
+            uninitialized_memory: UninitializedMemory::default(),
+            stub_formatting: true,
+            loop_summary: false,
+            inline_threshold: 0,
+            call_trace: false,
+            loop_counts: false,
+            detect_infinite_loops: false,
+            check_alignment: false,
+            unknown_function_policy: UnknownFunctionPolicy::default(),
+            progress: false,
+            max_solutions_exceeded_action: MaxSolutionsExceeded::default(),
+            verbosity: Verbosity::Quiet,
+            signed_integers: false,
+            search_strategy: SearchStrategy::default(),
+            max_iter_count: None,
+            max_call_depth: 100,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        let path = "tests/unit_tests/instructions.bc";
+        let output = run(path, "test_reachable", &config()).expect("Failed to run");
+        assert!(!output.timed_out);
+        assert_eq!(output.results.len(), 2);
+
+        // The timeout is reached before the first path, so none of the paths are reported.
+        let cfg = RunConfig {
+            timeout: Some(Duration::ZERO),
+            ..config()
+        };
+        let output = run(path, "test_reachable", &cfg).expect("Failed to run");
+        assert!(output.timed_out);
+        assert!(output.results.is_empty());
+    }
+}
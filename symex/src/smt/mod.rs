@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 pub mod smt_boolector;
 
@@ -6,6 +6,7 @@ pub type DExpr = smt_boolector::BoolectorExpr;
 pub type DSolver = smt_boolector::BoolectorIncrementalSolver;
 pub type DContext = smt_boolector::BoolectorSolverContext;
 pub type DArray = smt_boolector::BoolectorArray;
+pub type DSolverCheckpoint<'a> = smt_boolector::SolverCheckpoint<'a>;
 
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
 pub enum SolverError {
@@ -20,6 +21,10 @@ pub enum SolverError {
     /// Exceeded the passed maximum number of solutions.
     #[error("Exceeded number of solutions")]
     TooManySolutions,
+
+    /// The expression is wider than the query supports. Contains the width in bits.
+    #[error("Expression of {0} bits is too wide")]
+    TooWide(u32),
 }
 
 #[derive(Debug)]
@@ -27,3 +32,71 @@ pub enum Solutions<E> {
     Exactly(Vec<E>),
     AtLeast(Vec<E>),
 }
+
+/// How aggressively the solver should rewrite and simplify expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewriteLevel {
+    /// No rewriting.
+    None,
+
+    /// Term level rewriting.
+    TermLevel,
+
+    /// Term level rewriting and more simplification techniques.
+    More,
+
+    /// Full rewriting and simplification.
+    Full,
+}
+
+/// Tuning options for the SMT solver.
+///
+/// The defaults match the defaults of the underlying solver. All the rewriting and preprocessing
+/// options only affect performance, the results are the same regardless of their values. The
+/// `seed` may change which solution is returned when there are multiple, and a `timeout` can make
+/// queries fail with [`SolverError::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverConfig {
+    /// Rewrite level, can only be set before any expressions are created.
+    pub rewrite_level: RewriteLevel,
+
+    /// Skeleton preprocessing during simplification.
+    pub skeleton_preproc: bool,
+
+    /// Eager addition of Ackermann constraints for function applications.
+    pub ackermann: bool,
+
+    /// Eager elimination of lambda expressions via beta reduction.
+    pub beta_reduce: bool,
+
+    /// Slice elimination on bit vector variables.
+    pub eliminate_slices: bool,
+
+    /// Variable substitution during simplification.
+    pub variable_subst: bool,
+
+    /// Normalization of addition, multiplication and bitwise and.
+    pub normalize: bool,
+
+    /// Seed for the solver's internal random number generator.
+    pub seed: u32,
+
+    /// Maximum time for a single query, `None` for no limit.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            rewrite_level: RewriteLevel::Full,
+            skeleton_preproc: true,
+            ackermann: false,
+            beta_reduce: false,
+            eliminate_slices: true,
+            variable_subst: true,
+            normalize: true,
+            seed: 0,
+            timeout: None,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 #![allow(clippy::len_without_is_empty)]
-use boolector::{Btor, BV};
-use std::{cmp::Ordering, rc::Rc};
+use boolector::BV;
+use std::cmp::Ordering;
 
-use super::BoolectorSolverContext;
+use super::{BoolectorSolverContext, SharedBtor};
+use crate::smt::SolverConfig;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct BoolectorExpr(pub(crate) BV<Rc<Btor>>);
+pub struct BoolectorExpr(pub(crate) BV<SharedBtor>);
 
 impl BoolectorExpr {
     /// Returns the bit width of the [Expression].
@@ -260,8 +261,15 @@ impl BoolectorExpr {
     }
 
     fn get_ctx(&self) -> BoolectorSolverContext {
+        // Only used to create new expressions in the same solver, so the configuration and the
+        // variables are unused. The instance is shared, so fresh names never collide with the ones
+        // of the context.
         let ctx = self.0.get_btor();
-        BoolectorSolverContext { ctx }
+        BoolectorSolverContext {
+            ctx,
+            config: SolverConfig::default(),
+            variables: Default::default(),
+        }
     }
 
     pub fn replace_part(&self, start_idx: u32, replace_with: Self) -> Self {
@@ -357,4 +365,205 @@ impl BoolectorExpr {
             .ite(&is_negative.ite(&min, &max), &result)
             .simplify()
     }
+
+    /// Convert an unsigned integer to an IEEE 754 floating point value.
+    ///
+    /// The floating point format has `exponent_bits` exponent bits and `mantissa_bits` mantissa
+    /// bits, excluding the implicit leading bit. The result is rounded to nearest, ties to even.
+    pub fn uint_to_fp(&self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        self.int_to_fp(false, exponent_bits, mantissa_bits)
+    }
+
+    /// Convert a signed integer to an IEEE 754 floating point value.
+    ///
+    /// See [`Self::uint_to_fp`] for the format of the result.
+    pub fn sint_to_fp(&self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        self.int_to_fp(true, exponent_bits, mantissa_bits)
+    }
+
+    fn int_to_fp(&self, signed: bool, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        let ctx = self.get_ctx();
+        let n = self.len();
+
+        // Split into sign and magnitude, where the magnitude is treated as unsigned.
+        let (sign, magnitude) = if signed {
+            let sign = self.slice(n - 1, n - 1);
+            let negated = ctx.zero(n).sub(self);
+            (sign.clone(), sign.ite(&negated, self))
+        } else {
+            (ctx.zero(1), self.clone())
+        };
+
+        // Make sure there is room for the leading one, the mantissa and a round bit.
+        let width = n.max(mantissa_bits + 2);
+        let magnitude = magnitude.zero_ext(width);
+
+        // Find the position of the most significant set bit.
+        let mut msb = ctx.zero(width);
+        for i in 1..n {
+            let bit = magnitude.slice(i, i);
+            msb = bit.ite(&ctx.from_u64(i as u64, width), &msb);
+        }
+
+        // Normalize so the leading one is the most significant bit, the bits below it make up the
+        // mantissa and the remaining bits are used for rounding.
+        let shift = ctx.from_u64((width - 1) as u64, width).sub(&msb);
+        let normalized = magnitude.sll(&shift);
+
+        let rest_bits = width - 1 - mantissa_bits;
+        let mantissa = normalized.slice(rest_bits, width - 2);
+        let round_bit = normalized.slice(rest_bits - 1, rest_bits - 1);
+        let sticky = if rest_bits >= 2 {
+            normalized
+                .slice(0, rest_bits - 2)
+                ._ne(&ctx.zero(rest_bits - 1))
+        } else {
+            ctx.from_bool(false)
+        };
+        let round_up = round_bit.and(&sticky.or(&mantissa.slice(0, 0)));
+
+        // Combine exponent and mantissa, rounding up may carry over into the exponent which is
+        // exactly what we want. The extra bits are used to detect overflow into infinity.
+        let combined_width = width.max(exponent_bits + mantissa_bits) + 2;
+        let bias = (1u64 << (exponent_bits - 1)) - 1;
+        let exponent = msb
+            .zero_ext(combined_width)
+            .add(&ctx.from_u64(bias, combined_width));
+        let mantissa_shift = ctx.from_u64(mantissa_bits as u64, combined_width);
+        let combined = exponent
+            .sll(&mantissa_shift)
+            .or(&mantissa.zero_ext(combined_width))
+            .add(&round_up.zero_ext(combined_width));
+
+        let infinity = ctx
+            .unsigned_max(exponent_bits)
+            .concat(&ctx.zero(mantissa_bits))
+            .zero_ext(combined_width);
+        let combined = combined.ugte(&infinity).ite(&infinity, &combined);
+        let combined = combined.slice(0, exponent_bits + mantissa_bits - 1);
+
+        // Zero is the only value without a leading one.
+        let is_zero = magnitude._eq(&ctx.zero(width));
+        let combined = is_zero.ite(&ctx.zero(exponent_bits + mantissa_bits), &combined);
+
+        sign.concat(&combined)
+    }
+
+    /// Convert an IEEE 754 floating point value to an unsigned integer of `bits` bits, rounding
+    /// towards zero.
+    ///
+    /// Returns the converted value and a condition which is true when the value does not fit in
+    /// the integer, in that case the converted value should be treated as poison.
+    pub fn fp_to_uint(&self, exponent_bits: u32, mantissa_bits: u32, bits: u32) -> (Self, Self) {
+        self.fp_to_int(false, exponent_bits, mantissa_bits, bits)
+    }
+
+    /// Convert an IEEE 754 floating point value to a signed integer of `bits` bits, rounding
+    /// towards zero.
+    ///
+    /// See [`Self::fp_to_uint`] for the returned values.
+    pub fn fp_to_sint(&self, exponent_bits: u32, mantissa_bits: u32, bits: u32) -> (Self, Self) {
+        self.fp_to_int(true, exponent_bits, mantissa_bits, bits)
+    }
+
+    fn fp_to_int(
+        &self,
+        signed: bool,
+        exponent_bits: u32,
+        mantissa_bits: u32,
+        bits: u32,
+    ) -> (Self, Self) {
+        assert_eq!(self.len(), 1 + exponent_bits + mantissa_bits);
+        let ctx = self.get_ctx();
+
+        let sign = self.slice(exponent_bits + mantissa_bits, exponent_bits + mantissa_bits);
+        let exponent = self.slice(mantissa_bits, exponent_bits + mantissa_bits - 1);
+        let mantissa = self.slice(0, mantissa_bits - 1);
+
+        // Large enough to hold the significand shifted to any position that fits in the integer.
+        let width = (mantissa_bits + 1).max(exponent_bits) + bits + 1;
+        let bias = (1u64 << (exponent_bits - 1)) - 1;
+
+        let nan_or_infinity = exponent._eq(&ctx.unsigned_max(exponent_bits));
+        let exponent = exponent.zero_ext(width);
+
+        // value = 1.mantissa * 2^(exponent - bias), subnormals are always truncated to zero so
+        // the implicit bit does not matter for those.
+        let significand = ctx.one(1).concat(&mantissa).zero_ext(width);
+        let bias_mantissa = ctx.from_u64(bias + mantissa_bits as u64, width);
+        let magnitude = exponent.ugte(&bias_mantissa).ite(
+            &significand.sll(&exponent.sub(&bias_mantissa)),
+            &significand.srl(&bias_mantissa.sub(&exponent)),
+        );
+
+        // The magnitude is only valid when this is false.
+        let too_large = exponent.ugte(&ctx.from_u64(bias + bits as u64, width));
+
+        let (value, out_of_range) = if signed {
+            let limit = ctx.one(width).sll(&ctx.from_u64((bits - 1) as u64, width));
+            let exceeds_limit = sign.ite(&magnitude.ugt(&limit), &magnitude.ugte(&limit));
+            let value = sign.ite(&ctx.zero(width).sub(&magnitude), &magnitude);
+            (value, exceeds_limit)
+        } else {
+            let is_negative = sign.and(&magnitude._ne(&ctx.zero(width)));
+            (magnitude, is_negative)
+        };
+
+        let out_of_range = nan_or_infinity.or(&too_large).or(&out_of_range);
+        (value.slice(0, bits - 1), out_of_range)
+    }
+
+    /// Check if an IEEE 754 floating point value is NaN.
+    pub fn fp_is_nan(&self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        assert_eq!(self.len(), 1 + exponent_bits + mantissa_bits);
+        let ctx = self.get_ctx();
+
+        let exponent = self.slice(mantissa_bits, exponent_bits + mantissa_bits - 1);
+        let mantissa = self.slice(0, mantissa_bits - 1);
+        exponent
+            ._eq(&ctx.unsigned_max(exponent_bits))
+            .and(&mantissa._ne(&ctx.zero(mantissa_bits)))
+    }
+
+    /// Ordered floating point equality, false if either value is NaN and true for `+0 == -0`.
+    pub fn fp_eq(&self, other: &Self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        let ordered = self.fp_is_ordered(other, exponent_bits, mantissa_bits);
+        let equal = self._eq(other).or(&self.fp_both_zero(other));
+        ordered.and(&equal)
+    }
+
+    /// Ordered floating point less than, false if either value is NaN.
+    pub fn fp_lt(&self, other: &Self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        let ordered = self.fp_is_ordered(other, exponent_bits, mantissa_bits);
+        let less = self
+            .fp_ordering_key()
+            .ult(&other.fp_ordering_key())
+            .and(&self.fp_both_zero(other).not());
+        ordered.and(&less)
+    }
+
+    fn fp_is_ordered(&self, other: &Self, exponent_bits: u32, mantissa_bits: u32) -> Self {
+        self.fp_is_nan(exponent_bits, mantissa_bits)
+            .or(&other.fp_is_nan(exponent_bits, mantissa_bits))
+            .not()
+    }
+
+    /// True if both values are zero, regardless of the sign.
+    fn fp_both_zero(&self, other: &Self) -> Self {
+        let ctx = self.get_ctx();
+        let magnitude_bits = self.len() - 1;
+        let zero = ctx.zero(magnitude_bits);
+        let self_zero = self.slice(0, magnitude_bits - 1)._eq(&zero);
+        let other_zero = other.slice(0, magnitude_bits - 1)._eq(&zero);
+        self_zero.and(&other_zero)
+    }
+
+    /// Map a non-NaN floating point value to an integer with the same ordering when compared as
+    /// unsigned. Negative values have all bits flipped and positive values the sign bit set.
+    fn fp_ordering_key(&self) -> Self {
+        let n = self.len();
+        let sign = self.slice(n - 1, n - 1);
+        let sign_bit = self.get_ctx().signed_min(n);
+        sign.ite(&self.not(), &self.or(&sign_bit))
+    }
 }
@@ -1,28 +1,95 @@
 use boolector::{
-    option::{BtorOption, NumberFormat},
+    option::{self, BtorOption, NumberFormat},
     Btor, BV,
 };
-use std::rc::Rc;
+use std::{
+    borrow::Borrow,
+    cell::{Cell, RefCell},
+    ops::Deref,
+    rc::Rc,
+};
+
+use super::{RewriteLevel, SolverConfig};
 
 mod expr;
 mod solver;
 
 // Re-exports.
 pub(super) use expr::BoolectorExpr;
-pub(super) use solver::BoolectorIncrementalSolver;
+pub(super) use solver::{BoolectorIncrementalSolver, SolverCheckpoint};
+
+/// Boolector instance together with the state shared by everything that uses it.
+///
+/// Expressions keep a reference to the instance they were created in, so a context recreated from
+/// an expression still hands out names that are unique in the instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedBtor {
+    btor: Rc<Btor>,
+
+    /// Number of names handed out by [`BoolectorSolverContext::fresh_name`].
+    fresh_names: Rc<Cell<u64>>,
+}
+
+impl SharedBtor {
+    fn new(btor: Btor) -> Self {
+        Self {
+            btor: Rc::new(btor),
+            fresh_names: Rc::new(Cell::new(0)),
+        }
+    }
+}
+
+impl Borrow<Btor> for SharedBtor {
+    fn borrow(&self) -> &Btor {
+        &self.btor
+    }
+}
+
+impl Deref for SharedBtor {
+    type Target = Btor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.btor
+    }
+}
 
 /// SolverContext handles the creation of expressions.
 ///
 /// Keeps track of all the created expressions and the internal SMT state.
 #[derive(Debug, Clone)]
 pub struct BoolectorSolverContext {
-    pub ctx: Rc<Btor>,
+    pub ctx: SharedBtor,
+
+    /// Configuration the solver was created with.
+    pub(crate) config: SolverConfig,
+
+    /// All unconstrained expressions, used to declare them when writing SMT-LIB scripts.
+    pub(super) variables: Rc<RefCell<Vec<BoolectorExpr>>>,
 }
 
 impl BoolectorSolverContext {
     /// Create a new uninitialized expression of size `bits`.
     pub fn unconstrained(&self, bits: u32, name: &str) -> BoolectorExpr {
-        BoolectorExpr(BV::new(self.ctx.clone(), bits, Some(name)))
+        let variable = BoolectorExpr(BV::new(self.ctx.clone(), bits, Some(name)));
+        self.variables.borrow_mut().push(variable.clone());
+        variable
+    }
+
+    /// Create a new uninitialized expression of size `bits` with a unique name, see
+    /// [`Self::fresh_name`].
+    pub fn fresh_unconstrained(&self, bits: u32, prefix: &str) -> BoolectorExpr {
+        self.unconstrained(bits, &self.fresh_name(prefix))
+    }
+
+    /// Get a name starting with `prefix` that is different from all other names returned by this
+    /// context.
+    ///
+    /// Names are numbered in the order they are created, so the same program always gets the
+    /// same names.
+    pub fn fresh_name(&self, prefix: &str) -> String {
+        let id = self.ctx.fresh_names.get();
+        self.ctx.fresh_names.set(id + 1);
+        format!("{prefix}-{id}")
     }
 
     /// Create a new expression set equal to `1` of size `bits.
@@ -52,30 +119,37 @@ impl BoolectorSolverContext {
 
     /// Creates an expression of size `bits` containing the maximum unsigned value.
     pub fn unsigned_max(&self, bits: u32) -> BoolectorExpr {
-        let mut s = String::new();
-        s.reserve_exact(bits as usize);
-        for _ in 0..bits {
-            s.push('1');
-        }
-        self.from_binary_string(&s)
+        self.low_mask(bits, bits)
     }
 
     /// Create an expression of size `bits` containing the maximum signed value.
     pub fn signed_max(&self, bits: u32) -> BoolectorExpr {
         // Maximum value: 0111...1
         assert!(bits > 1);
-        let mut s = String::from("0");
-        s.reserve_exact(bits as usize);
-        for _ in 0..bits - 1 {
-            s.push('1');
-        }
-        self.from_binary_string(&s)
+        self.low_mask(bits - 1, bits)
     }
 
     /// Create an expression of size `bits` containing the minimum signed value.
     pub fn signed_min(&self, bits: u32) -> BoolectorExpr {
         // Minimum value: 1000...0
         assert!(bits > 1);
+        self.sign_bit(bits)
+    }
+
+    /// Create an expression of size `bits` where only the lowest `low_bits` bits are set.
+    pub fn low_mask(&self, low_bits: u32, bits: u32) -> BoolectorExpr {
+        assert!(low_bits <= bits);
+        let mut s = String::new();
+        s.reserve_exact(bits as usize);
+        for i in 0..bits {
+            s.push(if i < bits - low_bits { '0' } else { '1' });
+        }
+        self.from_binary_string(&s)
+    }
+
+    /// Create an expression of size `bits` where only the most significant bit is set.
+    pub fn sign_bit(&self, bits: u32) -> BoolectorExpr {
+        assert!(bits > 0);
         let mut s = String::from("1");
         s.reserve_exact(bits as usize);
         for _ in 0..bits - 1 {
@@ -87,19 +161,50 @@ impl BoolectorSolverContext {
 
 impl BoolectorSolverContext {
     pub fn new() -> Self {
-        let btor = Btor::new();
-        let ctx = Rc::new(btor);
+        Self::with_config(&SolverConfig::default())
+    }
+
+    /// Create a new context with the solver tuned according to `config`.
+    pub fn with_config(config: &SolverConfig) -> Self {
+        let ctx = SharedBtor::new(Btor::new());
         ctx.set_opt(BtorOption::Incremental(true));
         ctx.set_opt(BtorOption::PrettyPrint(true));
         ctx.set_opt(BtorOption::OutputNumberFormat(NumberFormat::Hexadecimal));
 
-        Self { ctx }
+        let rewrite_level = match config.rewrite_level {
+            RewriteLevel::None => option::RewriteLevel::None,
+            RewriteLevel::TermLevel => option::RewriteLevel::TermLevel,
+            RewriteLevel::More => option::RewriteLevel::More,
+            RewriteLevel::Full => option::RewriteLevel::Full,
+        };
+        ctx.set_opt(BtorOption::RewriteLevel(rewrite_level));
+        ctx.set_opt(BtorOption::SkeletonPreproc(config.skeleton_preproc));
+        ctx.set_opt(BtorOption::Ackermann(config.ackermann));
+        ctx.set_opt(BtorOption::BetaReduce(config.beta_reduce));
+        ctx.set_opt(BtorOption::EliminateSlices(config.eliminate_slices));
+        ctx.set_opt(BtorOption::VariableSubst(config.variable_subst));
+        ctx.set_opt(BtorOption::Normalize(config.normalize));
+        ctx.set_opt(BtorOption::Seed(config.seed));
+        ctx.set_opt(BtorOption::SolverTimeout(config.timeout));
+
+        Self {
+            ctx,
+            config: config.clone(),
+            variables: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Create a new empty context, with the same configuration as this one.
+    ///
+    /// No expressions or constraints are shared between the contexts.
+    pub fn fresh(&self) -> Self {
+        Self::with_config(&self.config)
     }
 }
 
 /// Symbolic array where both index and stored values are symbolic.
 #[derive(Debug, Clone)]
-pub struct BoolectorArray(pub(super) boolector::Array<Rc<Btor>>);
+pub struct BoolectorArray(pub(super) boolector::Array<SharedBtor>);
 
 impl BoolectorArray {
     /// Create a new array where index has size `index_size` and each element has size `element_size`.
@@ -129,3 +234,55 @@ impl BoolectorArray {
         self.0 = self.0.write(&index.0, &value.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_mask_works() {
+        let ctx = BoolectorSolverContext::new();
+        assert_eq!(ctx.low_mask(0, 1).get_constant(), Some(0));
+        assert_eq!(ctx.low_mask(1, 1).get_constant(), Some(1));
+        assert_eq!(ctx.low_mask(3, 8).get_constant(), Some(0b111));
+        assert_eq!(ctx.low_mask(16, 32).get_constant(), Some(0xffff));
+        assert_eq!(ctx.low_mask(63, 64).get_constant(), Some(u64::MAX >> 1));
+        assert_eq!(ctx.low_mask(5, 8).len(), 8);
+    }
+
+    #[test]
+    fn sign_bit_works() {
+        let ctx = BoolectorSolverContext::new();
+        assert_eq!(ctx.sign_bit(1).get_constant(), Some(1));
+        assert_eq!(ctx.sign_bit(8).get_constant(), Some(0x80));
+        assert_eq!(ctx.sign_bit(32).get_constant(), Some(0x8000_0000));
+        assert_eq!(ctx.sign_bit(64).get_constant(), Some(1 << 63));
+    }
+
+    #[test]
+    fn unsigned_max_works() {
+        let ctx = BoolectorSolverContext::new();
+        assert_eq!(ctx.unsigned_max(1).get_constant(), Some(1));
+        assert_eq!(ctx.unsigned_max(8).get_constant(), Some(0xff));
+        assert_eq!(ctx.unsigned_max(64).get_constant(), Some(u64::MAX));
+        assert_eq!(ctx.signed_max(8).get_constant(), Some(0x7f));
+        assert_eq!(ctx.signed_min(16).get_constant(), Some(0x8000));
+    }
+
+    #[test]
+    fn fresh_unconstrained_are_independent() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let symbols: Vec<_> = (0..200)
+            .map(|_| ctx.fresh_unconstrained(8, "fresh"))
+            .collect();
+        for (value, symbol) in symbols.iter().enumerate() {
+            solver.assert(&symbol._eq(&ctx.from_u64(value as u64, 8)));
+        }
+        assert_eq!(solver.is_sat(), Ok(true));
+
+        let names: std::collections::HashSet<_> = (0..200).map(|_| ctx.fresh_name("x")).collect();
+        assert_eq!(names.len(), 200);
+    }
+}
@@ -1,24 +1,168 @@
 use boolector::{
     option::{BtorOption, ModelGen},
-    Btor, SolverResult, BV,
+    SolverResult, BV,
 };
-use std::rc::Rc;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    ops::Deref,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+use tracing::trace;
 
-use super::{BoolectorExpr, BoolectorSolverContext};
+use super::{BoolectorExpr, BoolectorSolverContext, SharedBtor};
 use crate::smt::{Solutions, SolverError};
 
+/// Number of results kept in the [`SatCache`] before it is cleared.
+const MAX_CACHED_RESULTS: usize = 10_000;
+
+/// Cache of satisfiability results keyed by the set of constraints that were checked.
+///
+/// Sibling paths share a common prefix of constraints, so the same question is often asked
+/// several times. The asserted constraints are tracked per push scope to know the current set.
+#[derive(Debug)]
+struct SatCache {
+    /// Ids of the asserted constraints, one entry for each scope with the innermost last.
+    scopes: Vec<Vec<i32>>,
+
+    /// Result for each sorted set of constraint ids.
+    results: HashMap<Vec<i32>, bool>,
+
+    /// All constraints that are asserted or part of a cached query.
+    ///
+    /// Keeps the nodes alive so their ids are not reused by boolector for other expressions, which
+    /// would make cached results wrong.
+    nodes: HashMap<i32, BoolectorExpr>,
+
+    /// Number of results to keep before the cache is cleared, see [`MAX_CACHED_RESULTS`].
+    max_results: usize,
+
+    /// Number of queries that were sent to boolector.
+    solver_calls: usize,
+
+    /// Total time spent in boolector for those queries.
+    solver_time: Duration,
+}
+
+impl SatCache {
+    fn new() -> Self {
+        Self {
+            scopes: vec![Vec::new()],
+            results: HashMap::new(),
+            nodes: HashMap::new(),
+            max_results: MAX_CACHED_RESULTS,
+            solver_calls: 0,
+            solver_time: Duration::ZERO,
+        }
+    }
+
+    /// Sorted ids of the currently asserted constraints together with `assumptions`.
+    fn key(&self, assumptions: &[BoolectorExpr]) -> Vec<i32> {
+        let mut ids: Vec<i32> = self
+            .scopes
+            .iter()
+            .flatten()
+            .copied()
+            .chain(assumptions.iter().map(|assumption| assumption.0.get_id()))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Cache the result of checking the asserted constraints together with `assumptions`.
+    ///
+    /// When the cache is full all results are dropped, together with the nodes that are no longer
+    /// asserted, so constraints from finished paths are not kept alive for the whole run.
+    fn insert(&mut self, key: Vec<i32>, assumptions: &[BoolectorExpr], result: bool) {
+        if self.results.len() >= self.max_results {
+            self.results.clear();
+            let asserted: HashSet<i32> = self.scopes.iter().flatten().copied().collect();
+            self.nodes.retain(|id, _| asserted.contains(id));
+        }
+
+        for assumption in assumptions {
+            self.nodes
+                .entry(assumption.0.get_id())
+                .or_insert_with(|| assumption.clone());
+        }
+        self.results.insert(key, result);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BoolectorIncrementalSolver {
-    ctx: Rc<Btor>,
+    ctx: SharedBtor,
+
+    /// Shared between all clones of the solver, since they use the same boolector instance.
+    ///
+    /// A new solver always starts with an empty cache, so results never carry over between
+    /// contexts.
+    cache: Rc<RefCell<SatCache>>,
+
+    /// Unconstrained expressions created by the context, shared with it.
+    variables: Rc<RefCell<Vec<BoolectorExpr>>>,
 }
 
 impl BoolectorIncrementalSolver {
     pub fn new(ctx: &BoolectorSolverContext) -> Self {
         Self {
             ctx: ctx.ctx.clone(),
+            cache: Rc::new(RefCell::new(SatCache::new())),
+            variables: ctx.variables.clone(),
         }
     }
 
+    /// Number of satisfiability queries that were sent to boolector, i.e. were not answered by
+    /// the cache.
+    pub fn solver_calls(&self) -> usize {
+        self.cache.borrow().solver_calls
+    }
+
+    /// Total time spent in boolector, shared by all clones of the solver.
+    pub fn solver_time(&self) -> Duration {
+        self.cache.borrow().solver_time
+    }
+
+    /// Number of constraints asserted in the current and all enclosing scopes.
+    pub fn num_assertions(&self) -> usize {
+        self.cache.borrow().scopes.iter().map(Vec::len).sum()
+    }
+
+    /// Run the solver without looking in the cache, used when a model is needed.
+    fn sat(&self) -> Result<bool, SolverError> {
+        let start = Instant::now();
+        let sat_result = self.ctx.sat();
+
+        let mut cache = self.cache.borrow_mut();
+        cache.solver_calls += 1;
+        cache.solver_time += start.elapsed();
+        drop(cache);
+
+        self.check_sat_result(sat_result)
+    }
+
+    /// Check if the asserted constraints together with `assumptions` are satisfiable, using the
+    /// cached result if the same set of constraints has been checked before.
+    fn cached_sat(&self, assumptions: &[BoolectorExpr]) -> Result<bool, SolverError> {
+        let key = self.cache.borrow().key(assumptions);
+        if let Some(result) = self.cache.borrow().results.get(&key) {
+            trace!("sat cache hit: {result}");
+            return Ok(*result);
+        }
+
+        for assumption in assumptions {
+            // Assume the constraint, will be forgotten after the next call to `sat`.
+            assumption.0.assume();
+        }
+        let result = self.sat()?;
+        self.cache.borrow_mut().insert(key, assumptions, result);
+
+        Ok(result)
+    }
+
     fn check_sat_result(&self, sat_result: SolverResult) -> Result<bool, SolverError> {
         match sat_result {
             SolverResult::Sat => Ok(true),
@@ -36,8 +180,8 @@ impl BoolectorIncrementalSolver {
         self.ctx.set_opt(BtorOption::ModelGen(ModelGen::All));
 
         let result = || {
-            if self.is_sat()? {
-                self.is_sat()?;
+            if self.sat()? {
+                self.sat()?;
                 let solution = expr.0.get_a_solution().disambiguate();
                 let solution = solution.as_01x_str();
 
@@ -56,10 +200,51 @@ impl BoolectorIncrementalSolver {
 
     pub fn push(&self) {
         self.ctx.push(1);
+        self.cache.borrow_mut().scopes.push(Vec::new());
     }
 
     pub fn pop(&self) {
         self.ctx.pop(1);
+        self.cache.borrow_mut().scopes.pop();
+    }
+
+    /// Constraints asserted in the current and all enclosing scopes, the outermost first.
+    pub fn assertions(&self) -> Vec<BoolectorExpr> {
+        let cache = self.cache.borrow();
+        cache
+            .scopes
+            .iter()
+            .flatten()
+            .map(|id| cache.nodes[id].clone())
+            .collect()
+    }
+
+    /// Replace the asserted constraints with `assertions`, as returned by
+    /// [`assertions`](Self::assertions) at some earlier point.
+    ///
+    /// All scopes but the outermost are popped and the constraints after the ones in the outermost
+    /// scope are asserted in a new scope. This makes it possible to resume paths in any order,
+    /// not only in the reverse order they were saved in.
+    pub fn restore(&self, assertions: &[BoolectorExpr]) {
+        let scopes = self.cache.borrow().scopes.len();
+        for _ in 1..scopes {
+            self.pop();
+        }
+
+        let outermost = self.cache.borrow().scopes[0].len();
+        self.push();
+        for constraint in &assertions[outermost..] {
+            self.assert(constraint);
+        }
+    }
+
+    /// Push a new scope that is popped when the returned checkpoint is dropped.
+    ///
+    /// Keeps pushes and pops balanced even on early returns, and is preferred over calling
+    /// [`push`](Self::push) and [`pop`](Self::pop) directly.
+    pub fn checkpoint(&self) -> SolverCheckpoint<'_> {
+        self.push();
+        SolverCheckpoint { solver: self }
     }
 
     /// Solve for the current solver state, and returns if the result is satisfiable.
@@ -67,15 +252,12 @@ impl BoolectorIncrementalSolver {
     /// All asserts and assumes are implicitly combined with a boolean and. Returns true or false,
     /// and [SolverError::Unknown] if the result cannot be determined.
     pub fn is_sat(&self) -> Result<bool, SolverError> {
-        let sat_result = self.ctx.sat();
-        self.check_sat_result(sat_result)
+        self.cached_sat(&[])
     }
 
     /// Solve for the solver state with the assumption of the passed constraint.
     pub fn is_sat_with_constraint(&self, constraint: &BoolectorExpr) -> Result<bool, SolverError> {
-        // Assume the constraint, will be forgotten after the next call to `is_sat`.
-        constraint.0.assume();
-        self.is_sat()
+        self.cached_sat(std::slice::from_ref(constraint))
     }
 
     /// Solve for the solver state with the assumption of the passed constraints.
@@ -83,10 +265,61 @@ impl BoolectorIncrementalSolver {
         &self,
         constraints: &[BoolectorExpr],
     ) -> Result<bool, SolverError> {
+        self.cached_sat(constraints)
+    }
+
+    /// Solve for the solver state with the assumption of the passed constraints, and report which
+    /// of them made it unsatisfiable.
+    ///
+    /// Returns `Ok(())` if the constraints are satisfiable, otherwise the indices of the failed
+    /// assumptions. These are the ones that conflict with the asserted constraints or with each
+    /// other, which is not necessarily a minimal set.
+    pub fn is_sat_assuming(
+        &self,
+        constraints: &[BoolectorExpr],
+    ) -> Result<Result<(), Vec<usize>>, SolverError> {
+        let key = self.cache.borrow().key(constraints);
+        if let Some(true) = self.cache.borrow().results.get(&key) {
+            return Ok(Ok(()));
+        }
+
+        // The failed assumptions are only known right after the query, so this cannot be answered
+        // from the cache when unsat.
         for constraint in constraints {
             constraint.0.assume();
         }
-        self.is_sat()
+        let result = self.sat()?;
+        self.cache.borrow_mut().insert(key, constraints, result);
+        if result {
+            return Ok(Ok(()));
+        }
+
+        let failed = constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, constraint)| constraint.0.is_failed_assumption())
+            .map(|(index, _)| index)
+            .collect();
+        Ok(Err(failed))
+    }
+
+    /// All asserted constraints as an SMT-LIB v2 script, which can be checked by another solver.
+    ///
+    /// Boolector only prints the constraints asserted outside of any scope, so the script is
+    /// built from [`assertions`](Self::assertions) instead.
+    pub fn to_smt2(&self) -> String {
+        let mut script = String::from("(set-logic QF_BV)\n");
+        for variable in self.variables.borrow().iter() {
+            writeln!(script, "{}", format!("{:?}", variable.0).trim()).unwrap();
+        }
+        for assertion in self.assertions() {
+            // Single bit expressions are printed either as booleans or as bit vectors, extending
+            // them makes sure it is always a bit vector.
+            let assertion = assertion.zero_ext(2);
+            writeln!(script, "(assert (= {:?} #b01))", assertion.0).unwrap();
+        }
+        script.push_str("(check-sat)\n(exit)\n");
+        script
     }
 
     /// Add the constraint to the solver.
@@ -95,6 +328,13 @@ impl BoolectorIncrementalSolver {
     /// Asserted constraints cannot be removed.
     pub fn assert(&self, constraint: &BoolectorExpr) {
         constraint.0.assert();
+
+        let mut cache = self.cache.borrow_mut();
+        let id = constraint.0.get_id();
+        cache.nodes.entry(id).or_insert_with(|| constraint.clone());
+        if let Some(scope) = cache.scopes.last_mut() {
+            scope.push(id);
+        }
     }
 
     /// Find solutions to `expr`.
@@ -124,6 +364,100 @@ impl BoolectorIncrementalSolver {
         result
     }
 
+    /// Smallest unsigned value `expr` can take, without constraining `expr`.
+    ///
+    /// Performs a binary search below the value from the current model. Only expressions of at
+    /// most 64 bits are supported, wider ones return [`SolverError::TooWide`].
+    pub fn min_unsigned(&self, expr: &BoolectorExpr) -> Result<u64, SolverError> {
+        let expr = expr.clone().simplify();
+        if expr.len() > 64 {
+            return Err(SolverError::TooWide(expr.len()));
+        }
+        if let Some(value) = expr.get_constant() {
+            return Ok(value);
+        }
+
+        let width = expr.len();
+        let constant = |value| BoolectorExpr(BV::from_u64(self.ctx.clone(), value, width));
+
+        let mut low = 0;
+        let mut high = self.get_value(&expr)?.get_constant().unwrap();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.is_sat_with_constraint(&expr.ulte(&constant(mid)))? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Largest unsigned value `expr` can take, without constraining `expr`.
+    ///
+    /// Performs a binary search above the value from the current model. Only expressions of at
+    /// most 64 bits are supported, wider ones return [`SolverError::TooWide`].
+    pub fn max_unsigned(&self, expr: &BoolectorExpr) -> Result<u64, SolverError> {
+        let expr = expr.clone().simplify();
+        if expr.len() > 64 {
+            return Err(SolverError::TooWide(expr.len()));
+        }
+        if let Some(value) = expr.get_constant() {
+            return Ok(value);
+        }
+
+        let width = expr.len();
+        let constant = |value| BoolectorExpr(BV::from_u64(self.ctx.clone(), value, width));
+
+        let mut low = self.get_value(&expr)?.get_constant().unwrap();
+        let mut high = u64::MAX >> (64 - width);
+        while low < high {
+            // Round up, so the search always makes progress when `low` is feasible.
+            let mid = low + (high - low - 1) / 2 + 1;
+            if self.is_sat_with_constraint(&expr.ugte(&constant(mid)))? {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Constrain `expr` to the value with the smallest absolute value and return that value.
+    ///
+    /// The values `0`, `1` and `-1` are tried first, otherwise the smallest absolute value
+    /// `ite(expr <s 0, -expr, expr)` is found with [`min_unsigned`](Self::min_unsigned).
+    /// Expressions wider than 64 bits are only constrained to the value from the current model. The
+    /// constraint is asserted, so the caller should use [`push`](Self::push) and
+    /// [`pop`](Self::pop) to undo it.
+    pub fn minimize(&self, expr: &BoolectorExpr) -> Result<BoolectorExpr, SolverError> {
+        let expr = expr.clone().simplify();
+        if expr.get_constant().is_some() {
+            return Ok(expr);
+        }
+
+        let width = expr.len();
+        let constant = |value| BoolectorExpr(BV::from_u64(self.ctx.clone(), value, width));
+        let zero = constant(0);
+
+        for candidate in [zero.clone(), constant(1), zero.not()] {
+            if self.can_equal(&expr, &candidate)? {
+                self.assert(&expr._eq(&candidate));
+                return Ok(candidate);
+            }
+        }
+
+        if width <= 64 {
+            let abs = expr.slt(&zero).ite(&zero.sub(&expr), &expr);
+            let min_abs = self.min_unsigned(&abs)?;
+            self.assert(&abs._eq(&constant(min_abs)));
+        }
+
+        let value = self.get_value(&expr)?;
+        self.assert(&expr._eq(&value));
+        Ok(value)
+    }
+
     /// Returns `true` if `lhs` and `rhs` must be equal under the current constraints.
     pub fn must_be_equal(
         &self,
@@ -142,6 +476,50 @@ impl BoolectorIncrementalSolver {
         self.is_sat_with_constraint(&lhs._eq(rhs))
     }
 
+    /// Count the solutions to `expr`, up to `max`.
+    ///
+    /// Returns `Ok(n)` if `expr` has exactly `n` solutions, where `n <= max`, and `Err(max)` if it
+    /// has more than `max`. Solutions are found the same way as in
+    /// [`get_values`](Self::get_values), but are not kept.
+    pub fn count_solutions(
+        &self,
+        expr: &BoolectorExpr,
+        max: usize,
+    ) -> Result<Result<usize, usize>, SolverError> {
+        let expr = expr.clone().simplify();
+        if expr.get_constant().is_some() {
+            return Ok(Ok(1));
+        }
+
+        // Setup before checking for solutions.
+        self.push();
+        self.ctx.set_opt(BtorOption::ModelGen(ModelGen::All));
+
+        let result = || {
+            let mut count = 0;
+            while count < max && self.sat()? {
+                let solution = expr.0.get_a_solution().disambiguate();
+                let solution = BV::from_binary_str(self.ctx.clone(), solution.as_01x_str());
+
+                // Constrain the next value to not be an already found solution.
+                self.assert(&expr._ne(&BoolectorExpr(solution)));
+                count += 1;
+            }
+
+            match count == max && self.sat()? {
+                true => Ok(Err(max)),
+                false => Ok(Ok(count)),
+            }
+        };
+        let result = result();
+
+        // Restore solver to initial state.
+        self.ctx.set_opt(BtorOption::ModelGen(ModelGen::Disabled));
+        self.pop();
+
+        result
+    }
+
     /// Find solutions to `expr`.
     ///
     /// Returns concrete solutions up to a maximum of `upper_bound`. If more solutions are available
@@ -169,7 +547,7 @@ impl BoolectorIncrementalSolver {
         self.ctx.set_opt(BtorOption::ModelGen(ModelGen::All));
 
         let result = || {
-            while solutions.len() < upper_bound && self.is_sat()? {
+            while solutions.len() < upper_bound && self.sat()? {
                 let solution = expr.0.get_a_solution().disambiguate();
                 let solution = solution.as_01x_str();
                 let solution = BoolectorExpr(BV::from_binary_str(self.ctx.clone(), solution));
@@ -180,7 +558,7 @@ impl BoolectorIncrementalSolver {
                 solutions.push(solution);
             }
 
-            let exists_more_solutions = self.is_sat()?;
+            let exists_more_solutions = self.sat()?;
             match exists_more_solutions {
                 false => Ok(Solutions::Exactly(solutions)),
                 true => Ok(Solutions::AtLeast(solutions)),
@@ -193,3 +571,247 @@ impl BoolectorIncrementalSolver {
         result
     }
 }
+
+/// Scope on the solver created by [`BoolectorIncrementalSolver::checkpoint`].
+///
+/// All constraints asserted while the checkpoint is alive are removed when it is dropped.
+#[derive(Debug)]
+pub struct SolverCheckpoint<'a> {
+    solver: &'a BoolectorIncrementalSolver,
+}
+
+impl<'a> Deref for SolverCheckpoint<'a> {
+    type Target = BoolectorIncrementalSolver;
+
+    fn deref(&self) -> &Self::Target {
+        self.solver
+    }
+}
+
+impl<'a> Drop for SolverCheckpoint<'a> {
+    fn drop(&mut self) {
+        self.solver.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimize_finds_smallest_value() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugt(&ctx.from_u64(10, 8)));
+        solver.assert(&x._ne(&ctx.from_u64(11, 8)));
+
+        let value = solver.minimize(&x).unwrap();
+        assert_eq!(value.get_constant(), Some(12));
+        assert!(solver.must_be_equal(&x, &ctx.from_u64(12, 8)).unwrap());
+    }
+
+    #[test]
+    fn minimize_finds_smallest_negative_value() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        // x < -5 && x != -6, so the value closest to zero is -7.
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.slt(&ctx.from_u64(-5i8 as u8 as u64, 8)));
+        solver.assert(&x._ne(&ctx.from_u64(-6i8 as u8 as u64, 8)));
+
+        let value = solver.minimize(&x).unwrap();
+        assert_eq!(value.get_constant(), Some(-7i8 as u8 as u64));
+        assert!(solver
+            .must_be_equal(&x, &ctx.from_u64(-7i8 as u8 as u64, 8))
+            .unwrap());
+    }
+
+    #[test]
+    fn min_and_max_unsigned_find_bounds() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugt(&ctx.from_u64(10, 8)));
+        solver.assert(&x.ult(&ctx.from_u64(200, 8)));
+        assert_eq!(solver.min_unsigned(&x), Ok(11));
+        assert_eq!(solver.max_unsigned(&x), Ok(199));
+
+        // Nothing is asserted, so both bounds can still be reached.
+        assert!(solver.can_equal(&x, &ctx.from_u64(11, 8)).unwrap());
+        assert!(solver.can_equal(&x, &ctx.from_u64(199, 8)).unwrap());
+
+        let y = ctx.unconstrained(64, "y");
+        assert_eq!(solver.min_unsigned(&y), Ok(0));
+        assert_eq!(solver.max_unsigned(&y), Ok(u64::MAX));
+
+        let z = ctx.unconstrained(65, "z");
+        assert_eq!(solver.min_unsigned(&z), Err(SolverError::TooWide(65)));
+    }
+
+    #[test]
+    fn count_solutions_stops_at_max() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugte(&ctx.from_u64(10, 8)));
+        solver.assert(&x.ulte(&ctx.from_u64(14, 8)));
+        assert_eq!(solver.count_solutions(&x, 10), Ok(Ok(5)));
+        assert_eq!(solver.count_solutions(&x, 5), Ok(Ok(5)));
+        assert_eq!(solver.count_solutions(&x, 3), Ok(Err(3)));
+        assert_eq!(solver.count_solutions(&ctx.from_u64(1, 8), 3), Ok(Ok(1)));
+
+        // The solutions that were found are not excluded afterwards.
+        assert!(solver.can_equal(&x, &ctx.from_u64(10, 8)).unwrap());
+    }
+
+    #[test]
+    fn sat_cache_reuses_results() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        let is_small = x.ult(&ctx.from_u64(10, 8));
+        solver.assert(&x.ugt(&ctx.from_u64(4, 8)));
+        assert!(solver.is_sat().unwrap());
+        assert_eq!(solver.solver_calls(), 1);
+
+        // Explore both sides of a branch, and return to the common prefix after each.
+        for constraint in [is_small.clone(), is_small.not()] {
+            for _ in 0..2 {
+                solver.push();
+                solver.assert(&constraint);
+                assert!(solver.is_sat().unwrap());
+                let expected = constraint == is_small;
+                assert_eq!(solver.is_sat_with_constraint(&is_small).unwrap(), expected);
+                solver.pop();
+                assert!(solver.is_sat().unwrap());
+            }
+        }
+        assert_eq!(solver.solver_calls(), 4);
+
+        // The same set of constraints gives the same result, even if asserted in another scope.
+        solver.assert(&is_small);
+        assert!(solver.is_sat().unwrap());
+        assert_eq!(solver.solver_calls(), 4);
+
+        let is_tiny = x.ult(&ctx.from_u64(5, 8));
+        assert!(!solver.is_sat_with_constraint(&is_tiny).unwrap());
+        assert_eq!(solver.solver_calls(), 5);
+    }
+
+    #[test]
+    fn smt2_includes_scoped_assertions() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugt(&ctx.from_u64(4, 8)));
+
+        // Branch constraints are asserted in the scope of the path.
+        solver.push();
+        solver.assert(&x.ult(&ctx.from_u64(10, 8)));
+
+        let script = solver.to_smt2();
+        assert!(script.contains("(declare-fun x () (_ BitVec 8))"));
+        assert_eq!(script.matches("(assert ").count(), 2);
+        assert!(script.contains("#x0a"));
+
+        solver.pop();
+        let script = solver.to_smt2();
+        assert_eq!(script.matches("(assert ").count(), 1);
+        assert!(!script.contains("#x0a"));
+    }
+
+    #[test]
+    fn sat_cache_is_cleared_when_full() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+        solver.cache.borrow_mut().max_results = 2;
+
+        let x = ctx.unconstrained(8, "x");
+        let asserted = x.ugt(&ctx.from_u64(4, 8));
+        solver.assert(&asserted);
+        let assumptions: Vec<_> = (0..3).map(|n| x._ne(&ctx.from_u64(n, 8))).collect();
+        for assumption in &assumptions {
+            assert!(solver.is_sat_with_constraint(assumption).unwrap());
+        }
+        assert_eq!(solver.solver_calls(), 3);
+
+        // Only the last result and the nodes it needs are left, the asserted constraint is kept.
+        let cache = solver.cache.borrow();
+        assert_eq!(cache.results.len(), 1);
+        assert!(cache.nodes.contains_key(&asserted.0.get_id()));
+        assert!(!cache.nodes.contains_key(&assumptions[0].0.get_id()));
+        assert!(cache.nodes.contains_key(&assumptions[2].0.get_id()));
+    }
+
+    #[test]
+    fn sat_assuming_reports_failed_assumptions() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugt(&ctx.from_u64(10, 8)));
+
+        let is_even = x.slice(0, 0)._eq(&ctx.zero(1));
+        let is_small = x.ult(&ctx.from_u64(5, 8));
+        let is_large = x.ugt(&ctx.from_u64(100, 8));
+        let is_medium = x.ult(&ctx.from_u64(50, 8));
+
+        assert_eq!(solver.is_sat_assuming(&[is_even.clone()]).unwrap(), Ok(()));
+
+        // Conflicts with the asserted constraint.
+        let failed = solver
+            .is_sat_assuming(&[is_even.clone(), is_small])
+            .unwrap()
+            .unwrap_err();
+        assert!(failed.contains(&1));
+
+        // Conflict between two assumptions.
+        let failed = solver
+            .is_sat_assuming(&[is_large, is_even, is_medium])
+            .unwrap()
+            .unwrap_err();
+        assert!(failed.contains(&0) && failed.contains(&2));
+
+        // Assumptions are forgotten after the query.
+        assert!(solver.is_sat().unwrap());
+    }
+
+    #[test]
+    fn dropped_checkpoint_restores_state() {
+        let ctx = BoolectorSolverContext::new();
+        let solver = BoolectorIncrementalSolver::new(&ctx);
+
+        let x = ctx.unconstrained(8, "x");
+        solver.assert(&x.ugt(&ctx.from_u64(10, 8)));
+
+        {
+            let checkpoint = solver.checkpoint();
+            checkpoint.assert(&x.ult(&ctx.from_u64(5, 8)));
+            assert!(!checkpoint.is_sat().unwrap());
+        }
+        assert!(solver.is_sat().unwrap());
+
+        // Nested checkpoints are popped in reverse order.
+        let outer = solver.checkpoint();
+        outer.assert(&x.ult(&ctx.from_u64(20, 8)));
+        let inner = outer.checkpoint();
+        inner.assert(&x._eq(&ctx.from_u64(30, 8)));
+        assert!(!inner.is_sat().unwrap());
+        drop(inner);
+        assert!(outer.is_sat().unwrap());
+        assert!(!outer
+            .is_sat_with_constraint(&x._eq(&ctx.from_u64(30, 8)))
+            .unwrap());
+        drop(outer);
+        assert!(solver
+            .is_sat_with_constraint(&x._eq(&ctx.from_u64(30, 8)))
+            .unwrap());
+    }
+}
@@ -2,8 +2,12 @@
 use colored::*;
 use core::fmt::{self, Write};
 use indenter::indented;
+use rustc_demangle::demangle;
 
-use crate::smt::DExpr;
+use crate::{
+    smt::DExpr,
+    vm::{BranchDecision, CallEvent},
+};
 
 /// Result for a single path of execution.
 ///
@@ -27,10 +31,47 @@ pub struct VisualPathResult {
 
     /// Variables explicitly marked as symbolic.
     pub symbolics: Vec<Variable>,
+
+    /// Functions entered and exited on the path, empty unless calls are logged.
+    pub calls: Vec<CallEvent>,
+
+    /// Number of solutions of the values that have more than one, empty unless solutions are
+    /// counted.
+    pub solution_counts: Vec<SolutionCount>,
+
+    /// Basic blocks entered more than once, i.e. loops, empty unless block visits are logged.
+    pub loops: Vec<BlockVisits>,
+
+    /// Symbolic branches taken on the path, empty unless shown with verbose output.
+    pub branches: Vec<BranchDecision>,
+}
+
+/// Shows integers as signed decimal values, e.g. `-1 (i32)` instead of `0xffffffff (32-bits)`,
+/// and single bits as booleans.
+///
+/// LLVM types do not say if an integer is signed, so [`Variable`] and [`VisualPathResult`] show
+/// them as unsigned hexadecimal values by default. Wrap them in this to show them as signed.
+pub struct Signed<'a, T>(pub &'a T);
+
+impl fmt::Display for Signed<'_, VisualPathResult> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_integers(f, true)
+    }
 }
 
 impl fmt::Display for VisualPathResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_integers(f, false)
+    }
+}
+
+impl VisualPathResult {
+    fn fmt_integers(&self, f: &mut fmt::Formatter<'_>, signed: bool) -> fmt::Result {
+        let show = |value: &Variable| match signed {
+            true => Signed(value).to_string(),
+            false => value.to_string(),
+        };
+
         writeln!(
             f,
             "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ PATH {} ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
@@ -42,10 +83,17 @@ impl fmt::Display for VisualPathResult {
                 writeln!(f, "{}: returned void", "Success".green())?;
             }
             PathStatus::Ok(Some(value)) => {
-                writeln!(f, "{}: returned {}", "Success".green(), value)?;
+                writeln!(f, "{}: returned {}", "Success".green(), show(value))?;
             }
-            PathStatus::Failed(err) => {
-                writeln!(f, "{}: {}", "Error".red(), err.error_message)?;
+            PathStatus::Failed(err)
+            | PathStatus::Diverged(err)
+            | PathStatus::Incomplete(_, err) => {
+                let status = match &self.result {
+                    PathStatus::Diverged(_) => "Diverged".yellow(),
+                    PathStatus::Incomplete(..) => "Incomplete".yellow(),
+                    _ => "Error".red(),
+                };
+                writeln!(f, "{status}: {}", err.error_message)?;
                 if let Some(error_location) = &err.error_location {
                     writeln!(indented(f), "at {error_location}\n")?;
                 }
@@ -56,6 +104,10 @@ impl fmt::Display for VisualPathResult {
                     if let Some(line) = &line.line {
                         writeln!(indented(f), "at {line}")?;
                     }
+                    for argument in line.arguments.iter() {
+                        let name = argument.name.as_deref().unwrap_or("_");
+                        writeln!(indented(f), "{name}: {}", show(argument))?;
+                    }
                 }
             }
         }
@@ -68,20 +120,95 @@ impl fmt::Display for VisualPathResult {
                 } else {
                     "_"
                 };
-                writeln!(indented(f), "{name}: {}", value)?;
+                writeln!(indented(f), "{name}: {}", show(value))?;
             }
         }
 
         if !self.inputs.is_empty() {
             writeln!(f, "\nInputs:")?;
             for (n, value) in self.inputs.iter().enumerate() {
-                writeln!(indented(f), "{n}: {}", value)?;
+                writeln!(indented(f), "{n}: {}", show(value))?;
+            }
+        }
+
+        if !self.solution_counts.is_empty() {
+            writeln!(f, "\nSolutions:")?;
+            for count in self.solution_counts.iter() {
+                match count.exact {
+                    true => writeln!(indented(f), "{}: {}", count.name, count.count)?,
+                    false => writeln!(indented(f), "{}: at least {}", count.name, count.count)?,
+                }
+            }
+        }
+
+        if !self.loops.is_empty() {
+            writeln!(f, "\nLoops:")?;
+            for visits in self.loops.iter() {
+                writeln!(
+                    indented(f),
+                    "{:#}: {} executed {} times",
+                    demangle(&visits.function),
+                    visits.block,
+                    visits.count
+                )?;
+            }
+        }
+
+        if !self.branches.is_empty() {
+            writeln!(f, "\nBranches:")?;
+            for branch in self.branches.iter() {
+                writeln!(indented(f), "{}: {:?}", branch.location, branch.condition)?;
+            }
+        }
+
+        if !self.calls.is_empty() {
+            writeln!(f, "\nCalls:")?;
+            let mut depth: usize = 0;
+            for event in self.calls.iter() {
+                let (marker, name) = match event {
+                    CallEvent::Enter(name) => (">", name),
+                    CallEvent::Exit(name) => {
+                        depth = depth.saturating_sub(1);
+                        ("<", name)
+                    }
+                };
+                let indent = "  ".repeat(depth);
+                writeln!(indented(f), "{indent}{marker} {:#}", demangle(name))?;
+                if matches!(event, CallEvent::Enter(_)) {
+                    depth += 1;
+                }
             }
         }
         Ok(())
     }
 }
 
+/// Number of possible values of a reported value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionCount {
+    /// Name of the value, `output` for the return value.
+    pub name: String,
+
+    /// Number of solutions that were found.
+    pub count: usize,
+
+    /// If all solutions were found, otherwise there are at least `count`.
+    pub exact: bool,
+}
+
+/// Number of times a basic block was branched to on a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockVisits {
+    /// Name of the function containing the block.
+    pub function: String,
+
+    /// Name of the block, or its position in the function for unnamed blocks.
+    pub block: String,
+
+    /// Number of times the block was branched to.
+    pub count: usize,
+}
+
 /// Status of the path.
 ///
 /// If the path succeeded the return value (if any) is contained in that variant. Otherwise,
@@ -93,6 +220,24 @@ pub enum PathStatus {
 
     /// The path failed.
     Failed(ErrorReason),
+
+    /// The path never returned from an entry function that cannot return, e.g. a function
+    /// returning `!` that panics. This is the expected outcome for such functions.
+    Diverged(ErrorReason),
+
+    /// The path was abandoned at an exploration limit, so it is neither known to succeed nor to
+    /// fail.
+    Incomplete(Limit, ErrorReason),
+}
+
+/// Exploration limit that a path was abandoned at, see [`PathStatus::Incomplete`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The path executed too many instructions.
+    IterationCount,
+
+    /// The call stack of the path became too deep.
+    CallDepth,
 }
 
 /// Detailed description of why a run failed.
@@ -120,6 +265,9 @@ pub struct LineTrace {
 
     /// Source location if available.
     pub line: Option<String>,
+
+    /// Solved values of the arguments passed to the function.
+    pub arguments: Vec<Variable>,
 }
 
 /// Symbolic variable that should be able to be displayed to an end user.
@@ -138,12 +286,82 @@ pub struct Variable {
 
     /// Simple representation of the variable.
     pub ty: ExpressionType,
+
+    /// Solved value the variable points to, if it is a pointer into a known allocation.
+    pub pointee: Option<Box<Variable>>,
+}
+
+impl Variable {
+    /// Get the value as an unsigned integer.
+    ///
+    /// Returns `None` if the value is not a constant, or if it is not an integer or pointer of at
+    /// most 64 bits.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.ty {
+            ExpressionType::Integer(bits) | ExpressionType::Pointer(bits, _) if bits <= 64 => {
+                self.value.get_constant()
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the value as a signed integer, sign extended from the size of the integer.
+    ///
+    /// Returns `None` if the value is not a constant, or if it is not an integer of at most 64
+    /// bits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.ty {
+            ExpressionType::Integer(bits) if bits > 0 && bits <= 64 => {
+                let value = self.value.get_constant()?;
+                let shift = 64 - bits;
+                Some(((value << shift) as i64) >> shift)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the value as bytes, if it is an array of bytes.
+    ///
+    /// Returns `None` if the value is not a constant, or if it is not an array of 8-bit integers.
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match &self.ty {
+            ExpressionType::Array(ty, num_elements) if **ty == ExpressionType::Integer(8) => {
+                // First element is located in the lowest bits.
+                (0..*num_elements as u32)
+                    .map(|i| {
+                        let byte = self.value.slice(i * 8, i * 8 + 7).get_constant()?;
+                        Some(byte as u8)
+                    })
+                    .collect()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Signed<'_, Variable> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_integers(f, true)
+    }
 }
 
+/// Integers are shown as unsigned hexadecimal values, see [`Signed`] to show them as signed.
 impl fmt::Display for Variable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_integers(f, false)
+    }
+}
+
+impl Variable {
+    fn fmt_integers(&self, f: &mut fmt::Formatter<'_>, signed: bool) -> fmt::Result {
         let raw = self.value.to_binary_string();
-        match self.ty.to_typed_variable(&raw) {
+        if let (ExpressionType::Pointer(..), Some(pointee)) = (&self.ty, &self.pointee) {
+            let address = TypedVariable::Pointer(&raw);
+            write!(f, "{address} -> ")?;
+            return pointee.fmt_integers(f, signed);
+        }
+
+        match self.ty.to_typed_variable(&raw, signed) {
             Some(typed_variable) => {
                 write!(f, "{typed_variable}")
             }
@@ -162,21 +380,37 @@ pub enum ExpressionType {
     /// Floating point of a certain size in bits.
     Float(usize),
 
+    /// Pointer of a certain size in bits to a value of the given type.
+    ///
+    /// The pointed to type is [`ExpressionType::Unknown`] for opaque pointers. Without a solved
+    /// pointee the pointer is shown as an integer.
+    Pointer(usize, Box<ExpressionType>),
+
     /// Array or vector of a certain type with a specific number of values.
     Array(Box<ExpressionType>, usize),
 
     /// Structure
     Struct(Vec<ExpressionType>),
 
+    /// Structure with the source names of the structure and its fields, taken from the debug
+    /// info. Fields without a known name are shown by their index.
+    NamedStruct(String, Vec<(Option<String>, ExpressionType)>),
+
+    /// Field of a structure followed by the given number of padding bits, which are not part of
+    /// the value. The padding comes from the data layout of the structure.
+    Padded(Box<ExpressionType>, usize),
+
     /// Type is unknown.
     Unknown,
 }
 
 impl ExpressionType {
-    fn size_in_bits(&self) -> Option<usize> {
+    /// Size of the type in bits, if it is known.
+    pub fn size_in_bits(&self) -> Option<usize> {
         match self {
             ExpressionType::Integer(bits) => Some(*bits),
             ExpressionType::Float(bits) => Some(*bits),
+            ExpressionType::Pointer(bits, _) => Some(*bits),
             ExpressionType::Array(e, n) => {
                 let element_size = e.size_in_bits()?;
                 Some(*n * element_size)
@@ -188,17 +422,32 @@ impl ExpressionType {
                 }
                 Some(size_in_bits)
             }
+            ExpressionType::NamedStruct(_, fields) => {
+                let mut size_in_bits = 0;
+                for (_, field) in fields.iter() {
+                    size_in_bits += field.size_in_bits()?;
+                }
+                Some(size_in_bits)
+            }
+            ExpressionType::Padded(ty, padding) => Some(ty.size_in_bits()? + padding),
             ExpressionType::Unknown => None,
         }
     }
 
-    fn to_typed_variable<'a>(&self, raw: &'a str) -> Option<TypedVariable<'a>> {
+    fn to_typed_variable<'a>(&'a self, raw: &'a str, signed: bool) -> Option<TypedVariable<'a>> {
         match self {
             ExpressionType::Integer(bits) => {
                 assert!(raw.len() == *bits);
-                Some(TypedVariable::Integer(raw, *bits))
+                match signed {
+                    true => Some(TypedVariable::SignedInteger(raw, *bits)),
+                    false => Some(TypedVariable::Integer(raw, *bits)),
+                }
             }
             ExpressionType::Float(bits) => Some(TypedVariable::Float(raw, *bits)),
+            ExpressionType::Pointer(bits, _) => {
+                assert!(raw.len() == *bits);
+                Some(TypedVariable::Integer(raw, *bits))
+            }
             ExpressionType::Array(ty, num_elements) => {
                 let mut vars = Vec::with_capacity(*num_elements);
                 let size = ty.size_in_bits()?;
@@ -207,49 +456,99 @@ impl ExpressionType {
                 for i in (0..*num_elements).rev() {
                     let start = i * size;
                     let end = (i + 1) * size;
-                    let e = ty.to_typed_variable(&raw[start..end])?;
+                    let e = ty.to_typed_variable(&raw[start..end], signed)?;
                     vars.push(e);
                 }
 
                 Some(TypedVariable::Array(vars))
             }
             ExpressionType::Struct(fields) => {
-                let mut elements = Vec::with_capacity(fields.len());
+                let elements = Self::fields_to_typed_variables(fields.iter(), raw, signed)?;
+                Some(TypedVariable::Struct(elements))
+            }
+            ExpressionType::NamedStruct(name, fields) => {
+                let elements = Self::fields_to_typed_variables(
+                    fields.iter().map(|(_, field)| field),
+                    raw,
+                    signed,
+                )?;
+                let fields = fields
+                    .iter()
+                    .map(|(name, _)| name.as_deref())
+                    .zip(elements)
+                    .collect();
 
-                // First field is located at the end of the raw string.
-                let mut offset = raw.len();
+                Some(TypedVariable::NamedStruct(name, fields))
+            }
+            // The padding is after the field, in the most significant bits.
+            ExpressionType::Padded(ty, padding) => ty.to_typed_variable(&raw[*padding..], signed),
+            ExpressionType::Unknown => None,
+        }
+    }
 
-                for field in fields.iter() {
-                    let size = field.size_in_bits()?;
-                    let (start, end) = (offset - size, offset);
+    fn fields_to_typed_variables<'a>(
+        fields: impl ExactSizeIterator<Item = &'a ExpressionType>,
+        raw: &'a str,
+        signed: bool,
+    ) -> Option<Vec<TypedVariable<'a>>> {
+        let mut elements = Vec::with_capacity(fields.len());
 
-                    let element = field.to_typed_variable(&raw[start..end])?;
-                    elements.push(element);
+        // First field is located at the end of the raw string.
+        let mut offset = raw.len();
 
-                    offset -= size;
-                }
+        for field in fields {
+            let size = field.size_in_bits()?;
+            let (start, end) = (offset - size, offset);
 
-                Some(TypedVariable::Struct(elements))
-            }
-            ExpressionType::Unknown => None,
+            let element = field.to_typed_variable(&raw[start..end], signed)?;
+            elements.push(element);
+
+            offset -= size;
         }
+
+        Some(elements)
     }
 }
 
+/// Convert a string of binary digits to lowercase hexadecimal digits, the most significant first.
+fn binary_to_hex(binary: &str) -> String {
+    let padding = (4 - binary.len() % 4) % 4;
+    let binary = format!("{}{binary}", "0".repeat(padding));
+    binary
+        .as_bytes()
+        .chunks(4)
+        .map(|nibble| {
+            let nibble = nibble
+                .iter()
+                .fold(0, |acc, bit| (acc << 1) | (bit - b'0') as u32);
+            char::from_digit(nibble, 16).unwrap()
+        })
+        .collect()
+}
+
 /// Helper for displaying a [Variable].
 #[derive(Debug, Clone)]
 enum TypedVariable<'a> {
     /// Integer value of a certain size in bits.
     Integer(&'a str, usize),
 
+    /// Integer value of a certain size in bits, shown as a signed decimal value.
+    SignedInteger(&'a str, usize),
+
     /// Floating point of a certain size in bits.
     Float(&'a str, usize),
 
+    /// Address of a pointer.
+    Pointer(&'a str),
+
     /// Array or vector of a certain type with a specific number of values.
     Array(Vec<TypedVariable<'a>>),
 
     /// Structure
     Struct(Vec<TypedVariable<'a>>),
+
+    /// Structure with a name and optionally named fields.
+    NamedStruct(&'a str, Vec<(Option<&'a str>, TypedVariable<'a>)>),
 }
 
 impl<'a> fmt::Display for TypedVariable<'a> {
@@ -259,23 +558,43 @@ impl<'a> fmt::Display for TypedVariable<'a> {
         match self {
             Integer(value, bits) => {
                 let bits_str = if *bits == 1 { "bit" } else { "bits" };
-                let value = u128::from_str_radix(value, 2).unwrap();
 
+                // The digits are converted directly, so values of any width can be shown, e.g.
+                // `i256` which does not fit in any primitive.
                 const BITS_IN_BYTES: usize = 8;
                 const BITS_PER_HEX_CHAR: usize = 4;
                 match *bits % BITS_IN_BYTES {
                     0 => {
-                        // Get number of hex chars and add two for "0x" start.
-                        let width = *bits / BITS_PER_HEX_CHAR + 2;
-                        write!(f, "{value:#0width$x} ({bits}-{bits_str})")
+                        let value = binary_to_hex(value);
+                        let width = *bits / BITS_PER_HEX_CHAR;
+                        write!(f, "0x{value:0>width$} ({bits}-{bits_str})")
                     }
                     _ => {
-                        // Add two to number of bits for "0b" start.
-                        let width = *bits + 2;
-                        write!(f, "{value:#0width$b} ({bits}-{bits_str})")
+                        let width = *bits;
+                        write!(f, "0b{value:0>width$} ({bits}-{bits_str})")
                     }
                 }
             }
+            SignedInteger(value, bits) => {
+                if *bits == 0 || *bits > 128 {
+                    return write!(f, "{}", Integer(value, *bits));
+                }
+                if *bits == 1 {
+                    return write!(f, "{} (i1)", *value == "1");
+                }
+
+                // Sign extend from the size of the integer.
+                let shift = 128 - *bits;
+                let value = u128::from_str_radix(value, 2).unwrap();
+                let value = ((value << shift) as i128) >> shift;
+                write!(f, "{value} (i{bits})")
+            }
+            Pointer(value) => {
+                // Get number of hex chars and add two for "0x" start.
+                let width = value.len() / 4 + 2;
+                let value = u128::from_str_radix(value, 2).unwrap();
+                write!(f, "{value:#0width$x}")
+            }
             Float(value, bits) => match bits {
                 32 => {
                     let value = u32::from_str_radix(value, 2).unwrap();
@@ -314,13 +633,29 @@ impl<'a> fmt::Display for TypedVariable<'a> {
                     write!(f, "}}")
                 }
             },
+            NamedStruct(name, fields) => match fields.len() {
+                0 => {
+                    write!(f, "{name} {{}}")
+                }
+                _ => {
+                    writeln!(f, "{name} {{")?;
+                    for (i, (field_name, element)) in fields.iter().enumerate() {
+                        match field_name {
+                            Some(field_name) => writeln!(indented(f), "{field_name}: {element}")?,
+                            None => writeln!(indented(f), "{i}: {element}")?,
+                        }
+                    }
+                    write!(f, "}}")
+                }
+            },
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TypedVariable;
+    use super::{ExpressionType, Signed, TypedVariable, Variable};
+    use crate::smt::DContext;
 
     #[test]
     fn i64_works() {
@@ -330,10 +665,90 @@ mod tests {
         assert_eq!(s, "0x000000000001e240 (64-bits)");
     }
 
+    #[test]
+    fn wide_integers_work() {
+        let typed_variable = TypedVariable::Integer(&"1".repeat(128), 128);
+        let s = format!("{typed_variable}");
+        assert_eq!(s, format!("0x{} (128-bits)", "f".repeat(32)));
+
+        let value = format!("1{}1", "0".repeat(254));
+        let typed_variable = TypedVariable::Integer(&value, 256);
+        let s = format!("{typed_variable}");
+        assert_eq!(s, format!("0x8{}1 (256-bits)", "0".repeat(62)));
+
+        let value = format!("1{}", "0".repeat(129));
+        let typed_variable = TypedVariable::Integer(&value, 130);
+        let s = format!("{typed_variable}");
+        assert_eq!(s, format!("0b{value} (130-bits)"));
+    }
+
+    #[test]
+    fn signed_integers_work() {
+        let typed_variable = TypedVariable::SignedInteger(&"1".repeat(32), 32);
+        assert_eq!(format!("{typed_variable}"), "-1 (i32)");
+
+        let typed_variable = TypedVariable::SignedInteger("0101", 4);
+        assert_eq!(format!("{typed_variable}"), "5 (i4)");
+
+        let ctx = DContext::new();
+        let variable = Variable {
+            name: None,
+            value: ctx.from_u64(0xfe, 8),
+            ty: ExpressionType::Array(Box::new(ExpressionType::Integer(8)), 1),
+            pointee: None,
+        };
+        assert_eq!(format!("{variable}"), "[0xfe (8-bits)]");
+        assert_eq!(format!("{}", Signed(&variable)), "[-2 (i8)]");
+
+        // Single bits are booleans.
+        let typed_variable = TypedVariable::SignedInteger("1", 1);
+        assert_eq!(format!("{typed_variable}"), "true (i1)");
+    }
+
     #[test]
     fn i1_works() {
         let typed_variable = TypedVariable::Integer("1", 1);
         let s = format!("{typed_variable}");
         assert_eq!(s, "0b1 (1-bit)");
     }
+
+    #[test]
+    fn named_struct_works() {
+        let typed_variable = TypedVariable::NamedStruct(
+            "Point",
+            vec![
+                (Some("x"), TypedVariable::Integer("00000001", 8)),
+                (None, TypedVariable::Integer("00000010", 8)),
+            ],
+        );
+        let s = format!("{typed_variable}");
+        assert_eq!(s, "Point {\n    x: 0x01 (8-bits)\n    1: 0x02 (8-bits)\n}");
+    }
+
+    #[test]
+    fn variable_accessors_work() {
+        let ctx = DContext::new();
+        let variable = |value, ty| Variable {
+            name: None,
+            value,
+            ty,
+            pointee: None,
+        };
+
+        let negative = variable(ctx.from_u64(0xfe, 8), ExpressionType::Integer(8));
+        assert_eq!(negative.as_u64(), Some(0xfe));
+        assert_eq!(negative.as_i64(), Some(-2));
+        assert_eq!(negative.as_bytes(), None);
+
+        let bytes = variable(
+            ctx.from_u64(0x030201, 24),
+            ExpressionType::Array(Box::new(ExpressionType::Integer(8)), 3),
+        );
+        assert_eq!(bytes.as_bytes(), Some(vec![1, 2, 3]));
+        assert_eq!(bytes.as_u64(), None);
+
+        let symbolic = variable(ctx.unconstrained(32, "x"), ExpressionType::Integer(32));
+        assert_eq!(symbolic.as_u64(), None);
+        assert_eq!(symbolic.as_i64(), None);
+    }
 }
@@ -1,18 +1,25 @@
+use std::collections::HashSet;
+
 use llvm_ir::{
-    instruction::{self, BasicBlock, Instruction, LLVMAtomicRMWBinOp, LLVMIntPredicate},
-    Function, Type, Value,
+    instruction::{
+        self, BasicBlock, Instruction, LLVMAtomicRMWBinOp, LLVMIntPredicate, LLVMRealPredicate,
+    },
+    DataLayout, Function, Global, GlobalValue, StructureType, Type, Value,
 };
 use tracing::{debug, trace, warn};
 
 use crate::{
-    memory::to_bytes_u32,
-    smt::{DContext, DExpr, SolverError},
+    memory::{to_bytes_u32, MemoryError, UninitializedMemory, BITS_IN_BYTE},
+    smt::{DContext, DExpr, Solutions, SolverError},
+    util::{ExpressionType, Variable},
     vm::{Overriden, StackFrame},
 };
 
 use super::{
-    project::Project, state::LLVMState, vm::VM, AnalysisError, Hook, Intrinsic, LLVMExecutorError,
-    Path, Result,
+    project::{type_to_expr_type, Project},
+    state::{block_label, zero_sized_placeholder, LLVMState},
+    vm::{BranchDecision, CallEvent, UnknownFunctionPolicy, VM},
+    AnalysisError, Hook, Intrinsic, LLVMExecutorError, Path, Result,
 };
 
 pub struct LLVMExecutor<'vm> {
@@ -40,6 +47,7 @@ pub enum CallResult {
     CallFn(CallFn),
     Return(Option<DExpr>),
     AnalysisError(AnalysisError),
+    Suppress,
 }
 
 pub enum BlockResult {
@@ -47,6 +55,7 @@ pub enum BlockResult {
     CallFn(CallFn),
     Return(Option<DExpr>),
     AnalysisError(AnalysisError),
+    Suppress,
 }
 
 pub enum InstructionResult {
@@ -56,6 +65,7 @@ pub enum InstructionResult {
     CallFn(CallFn),
     Return(Option<DExpr>),
     AnalysisError(AnalysisError),
+    Suppress,
 }
 
 pub enum ResolvedFunction {
@@ -85,29 +95,37 @@ impl<'vm> LLVMExecutor<'vm> {
                 // done. Otherwise, we continue execution from the previous stack frame.
                 CallResult::Return(value) => {
                     if self.state.stack_frames.is_empty() {
-                        return Ok(PathResult::Success(value));
-                    }
-
-                    // Assign return values from functions.
-                    if let Some(result) = value {
-                        let current_instruction =
-                            self.state.current_frame()?.current_instruction().cloned().expect(
-                                "Basic block should not be empty. Should have a terminator instruction",
-                            );
+                        // Global constructors and destructors do not return anything, so any
+                        // value is from the entry function.
+                        if value.is_some() {
+                            self.state.entry_return = value.clone();
+                        }
 
-                        let register = Value::Instruction(current_instruction);
-                        self.assign_result(register, result)?;
+                        match self.state.pending_functions.pop_front() {
+                            Some(function) => {
+                                self.log_call(CallEvent::Enter, &function);
+                                let stack_frame = StackFrame::new(function)?;
+                                self.state.stack_frames.push(stack_frame);
+                                continue;
+                            }
+                            None => {
+                                let value = value.or_else(|| self.state.entry_return.take());
+                                return Ok(PathResult::Success(value));
+                            }
+                        }
                     }
 
-                    // Resume execution on the next instruction.
-                    self.state.current_frame_mut()?.increase_pc();
+                    self.return_to_caller(value)?;
                 }
 
                 // We are calling another function. This will push a new stack frame and resume
                 // execution from the first basic block in that function.
                 CallResult::CallFn(call) => {
-                    if self.state.stack_frames.len() >= 100 {
-                        panic!("Call depth exceeded");
+                    let max = self.vm.max_call_depth;
+                    if self.state.stack_frames.len() >= max {
+                        return Ok(PathResult::Failure(AnalysisError::CallDepthExceeded {
+                            max,
+                        }));
                     }
 
                     // The received function *may* be a symbolic address, and can reference multiple
@@ -115,16 +133,34 @@ impl<'vm> LLVMExecutor<'vm> {
                     // new paths for all but one.
                     let function = self.resolve_function(call.function)?;
                     match function {
+                        ResolvedFunction::Function(function) if function.is_declaration() => {
+                            let value = self.call_unknown_function(&function, &call.arguments)?;
+                            self.return_to_caller(value)?;
+                        }
                         ResolvedFunction::Function(function) => {
                             // Create arguments to put on the new stack frame.
-                            let arguments = call
+                            let mut arguments = call
                                 .arguments
-                                .into_iter()
-                                .map(|arg| self.state.get_expr(&arg))
+                                .iter()
+                                .map(|arg| self.state.get_expr(arg))
                                 .collect::<Result<Vec<_>>>()?;
-
-                            let stack_frame = StackFrame::new_from_function(function, &arguments)?;
-                            self.state.stack_frames.push(stack_frame);
+                            self.copy_byval_arguments(&function, &mut arguments)?;
+
+                            if self.is_inlinable(&function) {
+                                match self.execute_inline(function, &arguments)? {
+                                    CallResult::Return(value) => self.return_to_caller(value)?,
+                                    CallResult::AnalysisError(error) => {
+                                        return Ok(PathResult::Failure(error))
+                                    }
+                                    CallResult::Suppress => return Ok(PathResult::Suppress),
+                                    CallResult::CallFn(_) => unreachable!(),
+                                }
+                            } else {
+                                self.log_call(CallEvent::Enter, &function);
+                                let stack_frame =
+                                    StackFrame::new_from_function(function, &arguments)?;
+                                self.state.stack_frames.push(stack_frame);
+                            }
                         }
                         ResolvedFunction::Instrinic(_) | ResolvedFunction::Hook(_) => {
                             // For these we perform the entire function call at once, and handle the
@@ -133,34 +169,170 @@ impl<'vm> LLVMExecutor<'vm> {
                                 ResolvedFunction::Function(_) => unreachable!(),
                                 ResolvedFunction::Instrinic(i) => i(self, &call.arguments),
                                 ResolvedFunction::Hook(i) => i(self, &call.arguments),
-                            }?;
+                            };
+                            let result = match result {
+                                Ok(result) => result,
+                                Err(error) => PathResult::Failure(path_failure(error)?),
+                            };
+
+                            if let PathResult::Failure(error) = &result {
+                                if error.is_panic() && self.vm.continue_after_panic {
+                                    self.save_unwind_path()?;
+                                }
+                            }
 
                             let PathResult::Success(value) = result else {
                                 return Ok(result);
                             };
-
-                            if let Some(value) = value {
-                                let current_instruction = self
-                                    .state
-                                    .current_frame()?
-                                    .current_instruction()
-                                    .cloned()
-                                    .expect(
-                                        "Basic block should not be empty. Should have a terminator instruction",
-                                    );
-
-                                let register = Value::Instruction(current_instruction);
-                                self.assign_result(register, value)?;
-                            }
-                            self.state.current_frame_mut()?.increase_pc();
+                            self.return_to_caller(value)?;
                         }
                     }
                 }
 
                 // End execution.
-                CallResult::AnalysisError(error) => return Ok(PathResult::Failure(error)),
+                CallResult::AnalysisError(error) => {
+                    if error.is_panic() && self.vm.continue_after_panic {
+                        self.save_unwind_path()?;
+                    }
+                    return Ok(PathResult::Failure(error));
+                }
+                CallResult::Suppress => return Ok(PathResult::Suppress),
+            }
+        }
+    }
+
+    /// Call `function`, which is only declared, according to [`VM::unknown_function_policy`].
+    fn call_unknown_function(
+        &mut self,
+        function: &Function,
+        arguments: &[Value],
+    ) -> Result<Option<DExpr>> {
+        let name = function.name().to_string_lossy().into_owned();
+        let policy = self.vm.unknown_function_policy;
+        if policy == UnknownFunctionPolicy::Error {
+            return Err(LLVMExecutorError::FunctionNotFound(name));
+        }
+        debug!("Calling undefined function {name} with policy {policy:?}");
+        self.log_call(CallEvent::Enter, function);
+
+        if policy == UnknownFunctionPolicy::HavocArgsAndReturnSymbolic {
+            for argument in arguments {
+                if !matches!(argument.ty(), Type::Pointer(_)) {
+                    continue;
+                }
+
+                // The size of the pointed to value is not known, so havoc the rest of the
+                // allocation. Pointers outside of all allocations, e.g. null, are left alone.
+                let address = self.state.get_expr(argument)?;
+                let address = self.resolve_address(address)?;
+                let Some(start) = address.get_constant() else {
+                    continue;
+                };
+                if let Some((base, bits)) = self.state.memory.allocation_containing(start) {
+                    let bytes = base + bits / BITS_IN_BYTE as u64 - start;
+                    self.havoc_region(&address, bytes)?;
+                }
+            }
+        }
+
+        let return_type = match function.value_type() {
+            Type::Function(ty) => ty.return_type(),
+            _ => Type::Void,
+        };
+        let value = match self.project.bit_size(&return_type)? {
+            0 => None,
+            bits => {
+                let name = self.state.ctx.fresh_name(&format!("{name}-return"));
+                let value = self.state.ctx.unconstrained(bits, &name);
+                self.mark_symbolic(Variable {
+                    name: Some(name),
+                    value: value.clone(),
+                    ty: type_to_expr_type(&return_type, self.project),
+                    pointee: None,
+                });
+                Some(value)
+            }
+        };
+
+        self.log_call(CallEvent::Exit, function);
+        Ok(value)
+    }
+
+    /// Check if `function` can be executed in the caller's stack frame, see
+    /// [`VM::inline_threshold`].
+    fn is_inlinable(&self, function: &Function) -> bool {
+        let threshold = self.vm.inline_threshold;
+        if threshold == 0 {
+            return false;
+        }
+
+        // The registers of a recursive call would overwrite the caller's.
+        match self.state.current_frame() {
+            Ok(frame) if frame.function() != function => {}
+            _ => return false,
+        }
+
+        fits_inline_threshold(function, threshold)
+    }
+
+    /// Execute `function` directly in the caller's stack frame, without pushing a new one.
+    ///
+    /// Values are unique to the function they are defined in, so the parameters and results of
+    /// the inlined function cannot collide with the caller's registers. They are removed again
+    /// when the function is done.
+    fn execute_inline(&mut self, function: Function, arguments: &[DExpr]) -> Result<CallResult> {
+        let block = function
+            .first_basic_block()
+            .expect("Function has no basic blocks");
+        self.vm.coverage.visit(&function, &block);
+        self.log_call(CallEvent::Enter, &function);
+
+        let frame = self.state.current_frame_mut()?;
+        for (parameter, argument) in function.parameters().zip(arguments) {
+            frame.set_register(parameter, argument.clone());
+        }
+
+        let mut result = CallResult::Return(None);
+        for instruction in block.instructions() {
+            if is_debug_intrinsic(&instruction) {
+                continue;
             }
+            if let Some(max) = self.count_instruction() {
+                result = CallResult::AnalysisError(AnalysisError::IterationCountExceeded { max });
+                break;
+            }
+            let instruction_result = match self.execute_instruction(&instruction) {
+                Ok(result) => result,
+                Err(error) => InstructionResult::AnalysisError(path_failure(error)?),
+            };
+            result = match instruction_result {
+                InstructionResult::Continue => continue,
+                InstructionResult::Assign(value) => {
+                    self.assign_result(Value::Instruction(instruction), value)?;
+                    continue;
+                }
+                InstructionResult::Return(value) => CallResult::Return(value),
+                InstructionResult::AnalysisError(error) => CallResult::AnalysisError(error),
+                InstructionResult::Suppress => CallResult::Suppress,
+                InstructionResult::Branch(_) | InstructionResult::CallFn(_) => {
+                    unreachable!("Inlined functions neither branch nor call")
+                }
+            };
+            break;
+        }
+
+        let frame = self.state.current_frame_mut()?;
+        for parameter in function.parameters() {
+            frame.remove_register(&parameter);
+        }
+        for instruction in block.instructions() {
+            frame.remove_register(&Value::Instruction(instruction));
+        }
+
+        if matches!(result, CallResult::Return(_)) {
+            self.log_call(CallEvent::Exit, &function);
         }
+        Ok(result)
     }
 
     /// Execute a single function.
@@ -175,18 +347,35 @@ impl<'vm> LLVMExecutor<'vm> {
                 //
                 // Keep the execution going until we leave the function.
                 BlockResult::Branch(target) => {
+                    if self.vm.is_known_error(&target) {
+                        return Ok(CallResult::Suppress);
+                    }
+                    if self.vm.log_block_visits {
+                        *self.state.block_visits.entry(target.clone()).or_default() += 1;
+                    }
+                    if self.vm.detect_non_termination && self.state.repeats_loop_state(&target)? {
+                        let function = target.parent().name().to_string_lossy().into_owned();
+                        let block = block_label(&target);
+                        return Ok(CallResult::AnalysisError(AnalysisError::NonTermination {
+                            function,
+                            block,
+                        }));
+                    }
                     self.state.current_frame_mut()?.set_basic_block(target)?
                 }
 
                 // Both of these will leave the current function, so return control to the caller.
                 BlockResult::Return(value) => {
-                    self.state.stack_frames.pop();
+                    if let Some(frame) = self.state.stack_frames.pop() {
+                        self.log_call(CallEvent::Exit, frame.function());
+                    }
                     return Ok(CallResult::Return(value));
                 }
                 BlockResult::CallFn(call_fn) => {
                     return Ok(CallResult::CallFn(call_fn));
                 }
                 BlockResult::AnalysisError(error) => return Ok(CallResult::AnalysisError(error)),
+                BlockResult::Suppress => return Ok(CallResult::Suppress),
             }
         }
     }
@@ -197,6 +386,11 @@ impl<'vm> LLVMExecutor<'vm> {
     /// hits a terminator. This can either be a value, or a variant denoting a branch has occurred
     /// and that the callee should call this function again to resume execution in that basic block.
     fn execute_basic_block(&mut self) -> Result<BlockResult> {
+        let frame = self.state.current_frame()?;
+        self.vm
+            .coverage
+            .visit(frame.function(), frame.current_block());
+
         loop {
             let instruction = self
                 .state
@@ -205,7 +399,15 @@ impl<'vm> LLVMExecutor<'vm> {
                 .cloned()
                 .expect("Basic block should not be empty. Should have a terminator instruction");
 
-            let result = self.execute_instruction(&instruction)?;
+            if let Some(max) = self.count_instruction() {
+                return Ok(BlockResult::AnalysisError(
+                    AnalysisError::IterationCountExceeded { max },
+                ));
+            }
+            let result = match self.execute_instruction(&instruction) {
+                Ok(result) => result,
+                Err(error) => InstructionResult::AnalysisError(path_failure(error)?),
+            };
             match result {
                 // Continue execution in the same basic block.
                 InstructionResult::Continue => {}
@@ -225,12 +427,25 @@ impl<'vm> LLVMExecutor<'vm> {
                 InstructionResult::AnalysisError(error) => {
                     return Ok(BlockResult::AnalysisError(error))
                 }
+                InstructionResult::Suppress => return Ok(BlockResult::Suppress),
             }
 
             self.state.current_frame_mut()?.increase_pc();
         }
     }
 
+    /// Count an instruction executed on this path.
+    ///
+    /// Returns the limit if the path has executed more instructions than
+    /// [`VM::max_iter_count`].
+    fn count_instruction(&mut self) -> Option<u64> {
+        self.vm.count_instruction();
+        self.state.instructions += 1;
+        self.vm
+            .max_iter_count
+            .filter(|max| self.state.instructions > *max)
+    }
+
     fn assign_result(&mut self, value: Value, result: DExpr) -> Result<()> {
         self.state.current_frame_mut()?.set_register(value, result);
         Ok(())
@@ -252,6 +467,11 @@ impl<'vm> LLVMExecutor<'vm> {
         // Fast path for non-address values.
         match called_value {
             Value::Function(function) => return Ok(fn_lookup(function)),
+            Value::Global(Global::Alias(ref alias)) => {
+                if let Some(function) = self.project.resolve_alias(alias) {
+                    return Ok(fn_lookup(function));
+                }
+            }
             Value::Metadata => todo!("Cannot call metadata"),
             Value::InlineAsm => todo!("Inline asm is not supported"),
 
@@ -286,6 +506,66 @@ impl<'vm> LLVMExecutor<'vm> {
         }
     }
 
+    /// Read `bits` bits from memory at `address`.
+    ///
+    /// Meant for hooks and intrinsics. A symbolic address is resolved the same way as for a
+    /// `load`, creating new paths for all but one of its possible values.
+    pub fn read_memory(&mut self, address: &DExpr, bits: u32) -> Result<DExpr> {
+        let address = self.resolve_address(address.clone())?;
+        Ok(self.state.memory.read(&address, bits)?)
+    }
+
+    /// Write `value` to memory at `address`.
+    ///
+    /// Meant for hooks and intrinsics. A symbolic address is resolved the same way as in
+    /// [`read_memory`](Self::read_memory).
+    pub fn write_memory(&mut self, address: &DExpr, value: DExpr) -> Result<()> {
+        let address = self.resolve_address(address.clone())?;
+        Ok(self.state.memory.write(&address, value)?)
+    }
+
+    /// Free the allocation starting at `address`.
+    ///
+    /// Meant for hooks. A symbolic address is resolved the same way as in
+    /// [`read_memory`](Self::read_memory).
+    pub fn free_memory(&mut self, address: &DExpr) -> Result<()> {
+        let address = self.resolve_address(address.clone())?;
+        Ok(self.state.memory.deallocate(&address)?)
+    }
+
+    /// Allocate `count` elements of `element_bits` each, where the count is symbolic.
+    ///
+    /// Meant for hooks. The largest size the allocation can take is allocated, up to
+    /// [`VM::max_symbolic_allocation`], and accesses are checked against the symbolic size. Counts
+    /// where the size in bits overflows are excluded from the path. Returns
+    /// [`LLVMExecutorError::AllocationTooLarge`] if the size never fits, which only fails this
+    /// path.
+    pub fn allocate_symbolic(
+        &mut self,
+        count: &DExpr,
+        element_bits: u64,
+        align: u64,
+        uninitialized: UninitializedMemory,
+    ) -> Result<u64> {
+        let count = count.resize_unsigned(64);
+        let element_bits = self.state.ctx.from_u64(element_bits, 64);
+        let no_overflow = count.umulo(&element_bits).not();
+        let bits = count.mul(&element_bits);
+
+        let max = self.vm.max_symbolic_allocation;
+        let max_bits = max.saturating_mul(BITS_IN_BYTE as u64);
+        let fits = no_overflow.and(&bits.ulte(&self.state.ctx.from_u64(max_bits, 64)));
+        if !self.state.constraints.is_sat_with_constraint(&fits)? {
+            return Err(LLVMExecutorError::AllocationTooLarge(max));
+        }
+        self.state.constraints.assert(&no_overflow);
+
+        Ok(self
+            .state
+            .memory
+            .allocate_symbolic(&bits, align, max_bits, uninitialized)?)
+    }
+
     /// Resolve an address expression to a single value.
     ///
     /// If the address contain more than one possible address, then we create new paths for all
@@ -296,9 +576,13 @@ impl<'vm> LLVMExecutor<'vm> {
         }
 
         // Create new paths for all but one of the addresses.
-        let mut addresses = self.state.memory.resolve_addresses(&address, 50)?;
-        for address in addresses.iter().skip(1) {
-            let constraint = address._eq(&address);
+        let max = self.vm.max_memory_access_resolutions;
+        let mut addresses = match self.state.memory.resolve_addresses(&address, max)? {
+            Solutions::Exactly(addresses) => addresses,
+            Solutions::AtLeast(_) => return Err(LLVMExecutorError::TooManyAddressResolutions(max)),
+        };
+        for possible_address in addresses.iter().skip(1) {
+            let constraint = address._eq(possible_address);
             self.fork(constraint)?;
         }
 
@@ -314,13 +598,212 @@ impl<'vm> LLVMExecutor<'vm> {
         }
     }
 
+    /// Check if `address` can be misaligned for an access with an alignment of `align` bytes, when
+    /// [`VM::check_alignment`] is set.
+    ///
+    /// If the address can also be aligned, a new path is created for the misaligned case and this
+    /// path continues with the address constrained to be aligned.
+    fn is_misaligned(&mut self, address: &DExpr, align: u32) -> Result<bool> {
+        if !self.vm.check_alignment || align <= 1 {
+            return Ok(false);
+        }
+
+        let bits = address.len();
+        let mask = self.state.ctx.from_u64(align as u64 - 1, bits);
+        let misaligned = address.and(&mask)._ne(&self.state.ctx.zero(bits));
+        if !self.state.constraints.is_sat_with_constraint(&misaligned)? {
+            return Ok(false);
+        }
+
+        let aligned = misaligned.not();
+        if self.state.constraints.is_sat_with_constraint(&aligned)? {
+            self.fork(misaligned)?;
+            self.state.constraints.assert(&aligned);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Replace the arguments for parameters with the `byval` attribute with a pointer to a copy of
+    /// the value they point to.
+    ///
+    /// The callee semantically gets the value, not the pointer, so it must not be able to modify
+    /// the caller's value through it.
+    fn copy_byval_arguments(&mut self, function: &Function, arguments: &mut [DExpr]) -> Result<()> {
+        for (index, argument) in arguments.iter_mut().enumerate() {
+            let Some(ty) = function.byval_type(index as u32) else {
+                continue;
+            };
+
+            let bytes = byte_size(&ty, self.project)? as u64;
+            let align = self.project.ptr_size as u64 / BITS_IN_BYTE as u64;
+            let copy = self
+                .state
+                .memory
+                .allocate(bytes.max(1) * BITS_IN_BYTE as u64, align)?;
+            let copy = self.state.ctx.from_u64(copy, self.project.ptr_size);
+            self.state.memory.copy(&copy, argument, bytes)?;
+            *argument = copy;
+        }
+        Ok(())
+    }
+
+    /// Check if `bits` bits at the concrete `address` are inside a single allocation.
+    ///
+    /// For an allocation with a symbolic size the access can be in bounds for only some of its
+    /// sizes. The path then continues with those, and a new path is created for the rest.
+    fn is_in_allocation(&mut self, address: &DExpr, bits: u32) -> Result<bool> {
+        let Some(address) = address.get_constant() else {
+            return Ok(false);
+        };
+        let Some((base, size)) = self.state.memory.allocation_containing(address) else {
+            return Ok(false);
+        };
+        let end = (address - base) * BITS_IN_BYTE as u64 + bits as u64;
+        if end > size {
+            return Ok(false);
+        }
+
+        let object = self.state.memory.get_object(base);
+        let Some(size) = object.and_then(|object| object.symbolic_size()).cloned() else {
+            return Ok(true);
+        };
+        let in_bounds = self.state.ctx.from_u64(end, size.len()).ulte(&size);
+        if !self.state.constraints.is_sat_with_constraint(&in_bounds)? {
+            return Ok(false);
+        }
+        if self
+            .state
+            .constraints
+            .is_sat_with_constraint(&in_bounds.not())?
+        {
+            self.fork(in_bounds.not())?;
+        }
+        self.state.constraints.assert(&in_bounds);
+        Ok(true)
+    }
+
+    /// Assign the value returned from a call and continue execution in the caller.
+    ///
+    /// Execution continues on the next instruction after a `call`, and in the normal destination
+    /// after an `invoke`.
+    fn return_to_caller(&mut self, value: Option<DExpr>) -> Result<()> {
+        let current_instruction = self
+            .state
+            .current_frame()?
+            .current_instruction()
+            .cloned()
+            .expect("Basic block should not be empty. Should have a terminator instruction");
+
+        // Assign return values from functions.
+        if let Some(value) = value {
+            let register = Value::Instruction(current_instruction.clone());
+            self.assign_result(register, value)?;
+        }
+
+        let frame = self.state.current_frame_mut()?;
+        match current_instruction {
+            Instruction::Invoke(invoke) => frame.set_basic_block(invoke.normal_destination())?,
+            // Resume execution on the next instruction.
+            _ => frame.increase_pc(),
+        }
+        Ok(())
+    }
+
+    /// Record a call event for `function` if calls are logged, see [`VM::log_calls`].
+    fn log_call(&mut self, event: fn(String) -> CallEvent, function: &Function) {
+        if self.vm.log_calls {
+            let name = function.name().to_string_lossy().into_owned();
+            self.state.call_log.push(event(name));
+        }
+    }
+
+    /// Pop stack frames until the top frame is in an `invoke`, returns the unwind destination of
+    /// the `invoke`. Returns `None`, and leaves the stack as is, if no frame can catch the unwind.
+    ///
+    /// The popped frames are recorded as exited if `log_calls` is set.
+    fn unwind(state: &mut LLVMState, log_calls: bool) -> Option<BasicBlock> {
+        let (index, landing_pad) =
+            state
+                .stack_frames
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(index, frame)| match frame.current_instruction() {
+                    Some(Instruction::Invoke(invoke)) => Some((index, invoke.unwind_destination())),
+                    _ => None,
+                })?;
+
+        if log_calls {
+            for frame in state.stack_frames[index + 1..].iter().rev() {
+                let name = frame.function().name().to_string_lossy().into_owned();
+                state.call_log.push(CallEvent::Exit(name));
+            }
+        }
+        state.stack_frames.truncate(index + 1);
+        Some(landing_pad)
+    }
+
+    /// Save a path which continues from the landing pad that would catch a panic at the current
+    /// location, if there is one.
+    fn save_unwind_path(&mut self) -> Result<()> {
+        let mut state = self.state.clone();
+        match Self::unwind(&mut state, self.vm.log_calls) {
+            Some(landing_pad) => {
+                debug!("Continuing after panic in landing pad {landing_pad:?}");
+                state.current_frame_mut()?.set_basic_block(landing_pad)?;
+                self.save_path(state, None)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Overwrite `bytes` bytes of memory starting at `address` with fresh unconstrained values.
+    ///
+    /// This is meant for hooks modeling external functions that mutate memory in unknown ways,
+    /// e.g. `recv(buf, len)` would havoc `len` bytes of `buf`. The new values are marked as
+    /// symbolic so they are reported together with the other symbolic variables.
+    pub fn havoc_region(&mut self, address: &DExpr, bytes: u64) -> Result<()> {
+        if bytes == 0 {
+            return Ok(());
+        }
+
+        let address = self.resolve_address(address.clone())?;
+        let bits = bytes * BITS_IN_BYTE as u64;
+
+        let name = self.state.ctx.fresh_name("havoc");
+        let value = self.state.ctx.unconstrained(bits as u32, &name);
+        self.state.memory.write(&address, value.clone())?;
+
+        self.mark_symbolic(Variable {
+            name: Some(name),
+            value,
+            ty: ExpressionType::Unknown,
+            pointee: None,
+        });
+        Ok(())
+    }
+
+    /// Mark a variable as symbolic so it is reported in the results.
+    ///
+    /// When running with concrete inputs the variable is constrained to the next input.
+    pub fn mark_symbolic(&mut self, variable: Variable) {
+        if let Some(concrete) = &mut self.vm.concrete {
+            match concrete.inputs.pop_front() {
+                Some(input) => {
+                    let value = self.state.ctx.from_u64(input, variable.value.len());
+                    self.state.constraints.assert(&variable.value._eq(&value));
+                }
+                None => warn!("No concrete input left, {:?} stays symbolic", variable.name),
+            }
+        }
+        self.state.marked_symbolic.push(variable);
+    }
+
     pub fn fork(&mut self, constraint: DExpr) -> Result<()> {
         trace!("Save backtracking path: constraint={:?}", constraint);
         let forked_state = self.state.clone();
-        let path = Path::new(forked_state, Some(constraint));
-
-        self.vm.paths.save_path(path);
-        Ok(())
+        self.save_path(forked_state, Some(constraint))
     }
 
     fn fork_and_branch(&mut self, bb: BasicBlock, constraint: Option<DExpr>) -> Result<()> {
@@ -331,13 +814,73 @@ impl<'vm> LLVMExecutor<'vm> {
         );
 
         let mut state = self.state.clone();
+        if self.vm.log_block_visits {
+            *state.block_visits.entry(bb.clone()).or_default() += 1;
+        }
         state.current_frame_mut()?.set_basic_block(bb)?;
+        self.save_path(state, constraint)
+    }
+
+    /// Save a path to be explored later and record it in the exploration tree.
+    ///
+    /// When running with concrete inputs only a single path is explored, so nothing is saved.
+    fn save_path(&mut self, mut state: LLVMState, constraint: Option<DExpr>) -> Result<()> {
+        if self.vm.concrete.is_some() {
+            return Ok(());
+        }
+
+        let location = self.current_location()?;
+        if let (true, Some(constraint)) = (self.vm.log_constraints, &constraint) {
+            state.branch_constraints.push(BranchDecision {
+                location: location.clone(),
+                condition: constraint.clone(),
+            });
+        }
+
+        let condition = match self.vm.record_path_conditions {
+            true => constraint.as_ref().map(|c| format!("{c:?}")),
+            false => None,
+        };
+        let id = self
+            .vm
+            .path_tree
+            .add_path(self.vm.current_path, location, condition);
 
-        let path = Path::new(state, constraint);
+        let mut path = Path::new(state, constraint);
+        path.id = id;
         self.vm.paths.save_path(path);
         Ok(())
     }
 
+    /// Record the condition of a taken symbolic branch when running with concrete inputs, or
+    /// when logging constraints.
+    fn record_decision(&mut self, condition: &DExpr) -> Result<()> {
+        if self.vm.concrete.is_none() && !self.vm.log_constraints {
+            return Ok(());
+        }
+
+        let decision = BranchDecision {
+            location: self.current_location()?,
+            condition: condition.clone(),
+        };
+        if self.vm.log_constraints {
+            self.state.branch_constraints.push(decision.clone());
+        }
+        if let Some(concrete) = &mut self.vm.concrete {
+            concrete.decisions.push(decision);
+        }
+        Ok(())
+    }
+
+    /// Human readable description of the instruction currently being executed.
+    fn current_location(&self) -> Result<String> {
+        let frame = self.state.current_frame()?;
+        Ok(instruction_location(
+            frame.function(),
+            frame.current_instruction(),
+        ))
+    }
+
     fn execute_instruction(&mut self, i: &Instruction) -> Result<InstructionResult> {
         match i {
             Instruction::Load(i) => self.load(i),
@@ -412,10 +955,25 @@ impl<'vm> LLVMExecutor<'vm> {
     /// Load reads a value from memory.
     fn load(&mut self, i: &instruction::Load) -> Result<InstructionResult> {
         debug!("{i}");
+        let size = self.project.bit_size(&i.result_type())?;
+        if size == 0 {
+            return Ok(InstructionResult::Assign(zero_sized_placeholder(
+                self.state.ctx,
+            )));
+        }
+
         let address = self.state.get_expr(&i.address())?;
+        if self.is_misaligned(&address, i.alignment())? {
+            let align = i.alignment() as u64;
+            return Ok(InstructionResult::AnalysisError(
+                AnalysisError::MisalignedAccess { align },
+            ));
+        }
         let address = self.resolve_address(address)?;
+        if !self.is_in_allocation(&address, size)? {
+            return Ok(InstructionResult::AnalysisError(AnalysisError::OutOfBounds));
+        }
 
-        let size = bit_size(&i.result_type(), self.project.ptr_size).unwrap();
         let value = self.state.memory.read(&address, size)?;
         Ok(InstructionResult::Assign(value))
     }
@@ -423,24 +981,83 @@ impl<'vm> LLVMExecutor<'vm> {
     /// Write [`DExpr`] to memory.
     fn store(&mut self, i: &instruction::Store) -> Result<InstructionResult> {
         debug!("{i}");
+        // Zero sized values take up no memory, so there is nothing to write.
+        if self.project.bit_size(&i.value().ty())? == 0 {
+            return Ok(InstructionResult::Continue);
+        }
+
         let value = self.state.get_expr(&i.value())?;
         let address = self.state.get_expr(&i.address())?;
-        let address = self.resolve_address(address)?;
+        if self.is_misaligned(&address, i.alignment())? {
+            let align = i.alignment() as u64;
+            return Ok(InstructionResult::AnalysisError(
+                AnalysisError::MisalignedAccess { align },
+            ));
+        }
+        if address.get_constant().is_some() {
+            if !self.is_in_allocation(&address, value.len())? {
+                return Ok(InstructionResult::AnalysisError(AnalysisError::OutOfBounds));
+            }
+            self.state.memory.write(&address, value)?;
+            return Ok(InstructionResult::Continue);
+        }
+
+        // A symbolic address can point into several allocations, or outside all of them. Create
+        // new paths for all but one of the allocations, and one for the access being out of bounds.
+        let bytes = value.len().div_ceil(BITS_IN_BYTE) as u64;
+        let max = self.vm.max_memory_access_resolutions;
+        let mut allocations = match self
+            .state
+            .memory
+            .resolve_allocations(&address, bytes, max)?
+        {
+            Solutions::Exactly(allocations) => allocations,
+            Solutions::AtLeast(_) => return Err(LLVMExecutorError::TooManyAddressResolutions(max)),
+        };
 
-        self.state.memory.write(&address, value)?;
+        let in_bounds = allocations
+            .iter()
+            .map(|(_, constraint)| constraint.clone())
+            .reduce(|lhs, rhs| lhs.or(&rhs));
+        let Some(in_bounds) = in_bounds else {
+            return Ok(InstructionResult::AnalysisError(AnalysisError::OutOfBounds));
+        };
+        if self
+            .state
+            .constraints
+            .is_sat_with_constraint(&in_bounds.not())?
+        {
+            self.fork(in_bounds.not())?;
+        }
+        for (_, constraint) in allocations.iter().skip(1) {
+            self.fork(constraint.clone())?;
+        }
+
+        let (base, constraint) = allocations.swap_remove(0);
+        self.state.constraints.assert(&constraint);
+        self.state
+            .memory
+            .write_in_allocation(base, &address, value)?;
         Ok(InstructionResult::Continue)
     }
 
     fn alloca(&mut self, i: &instruction::Alloca) -> Result<InstructionResult> {
         debug!("{i}");
-        let num_elements = i.num_elements();
-        let num_elements = match self.state.get_expr(&num_elements)?.get_constant() {
-            Some(c) => c,
-            None => todo!("Handle non-constant alloca"),
+        let allocated_type = i.allocated_type();
+        let allocated_size = self.project.bit_size(&allocated_type)? as u64;
+
+        let num_elements = self.state.get_expr(&i.num_elements())?;
+        let Some(num_elements) = num_elements.get_constant() else {
+            let address = self.allocate_symbolic(
+                &num_elements,
+                allocated_size,
+                i.alignment() as u64,
+                self.vm.uninitialized_memory,
+            )?;
+            let address = self.state.ctx.from_u64(address, self.project.ptr_size);
+            return Ok(InstructionResult::Assign(address));
         };
 
-        let allocated_type = i.allocated_type();
-        let allocated_size = bit_size(&allocated_type, self.project.ptr_size).unwrap() as u64;
         let allocated_size = match allocated_size * num_elements {
             0 => {
                 warn!("Zero-sized alloca");
@@ -451,7 +1068,11 @@ impl<'vm> LLVMExecutor<'vm> {
         };
 
         let alignment = i.alignment() as u64;
-        let address = self.state.memory.allocate(allocated_size, alignment)?;
+        let address = self.state.memory.allocate_with(
+            allocated_size,
+            alignment,
+            self.vm.uninitialized_memory,
+        )?;
         let address = self.state.ctx.from_u64(address, self.project.ptr_size);
 
         Ok(InstructionResult::Assign(address))
@@ -567,14 +1188,48 @@ impl<'vm> LLVMExecutor<'vm> {
         todo!()
     }
 
-    fn shuffle_vector(&mut self, _i: &instruction::ShuffleVector) -> Result<InstructionResult> {
-        todo!()
+    /// Pick elements from the concatenation of two vectors, the mask gives the index of each
+    /// element in the result.
+    ///
+    /// Undefined mask elements mean the value of the element does not matter, these become
+    /// unconstrained so all possible values are checked.
+    fn shuffle_vector(&mut self, i: &instruction::ShuffleVector) -> Result<InstructionResult> {
+        debug!("{i}");
+        let Type::Vector(ty) = i.lhs().ty() else {
+            return Err(LLVMExecutorError::MalformedInstruction);
+        };
+        let element_size = self.project.bit_size(&ty.element_type())?;
+        let Some(mask) = i.mask() else {
+            return Err(LLVMExecutorError::UnsupportedInstruction(format!("{i}")));
+        };
+
+        let lhs = self.state.get_expr(&i.lhs())?;
+        let rhs = self.state.get_expr(&i.rhs())?;
+        let vector = rhs.concat(&lhs);
+
+        let result = mask
+            .into_iter()
+            .map(|index| match index {
+                Some(index) => {
+                    let low = index * element_size;
+                    vector.slice(low, low + element_size - 1)
+                }
+                None => self.state.ctx.fresh_unconstrained(element_size, "undef"),
+            })
+            .reduce(|acc, element| element.concat(&acc))
+            .ok_or(LLVMExecutorError::MalformedInstruction)?;
+        Ok(InstructionResult::Assign(result))
     }
 
     fn extract_value(&mut self, i: &instruction::ExtractValue) -> Result<InstructionResult> {
         debug!("{i}");
         let aggregate = i.aggregate();
         let (lower, upper) = get_element_offset(&self.state, aggregate.ty(), i.indices())?;
+        if lower == upper {
+            return Ok(InstructionResult::Assign(zero_sized_placeholder(
+                self.state.ctx,
+            )));
+        }
 
         let aggregate = self.state.get_expr(&aggregate)?;
         assert!(aggregate.len() >= upper && upper > 0);
@@ -587,17 +1242,24 @@ impl<'vm> LLVMExecutor<'vm> {
         debug!("{i}");
         let aggregate = i.aggregate();
         let (lower, upper) = get_element_offset(&self.state, aggregate.ty(), i.indices())?;
+        let aggregate = self.state.get_expr(&aggregate)?;
+        if lower == upper {
+            // Inserting a zero sized element leaves the aggregate as is.
+            return Ok(InstructionResult::Assign(aggregate));
+        }
 
         let element = self.state.get_expr(&i.element())?;
         assert_eq!(upper - lower, element.len());
 
-        let aggregate = self.state.get_expr(&aggregate)?;
         let new_aggregate = aggregate.replace_part(lower, element);
         Ok(InstructionResult::Assign(new_aggregate))
     }
 
-    fn fence(&mut self, _i: &instruction::Fence) -> Result<InstructionResult> {
-        todo!()
+    /// Fences only order memory accesses between threads, and a single thread is executed, so
+    /// they do nothing.
+    fn fence(&mut self, i: &instruction::Fence) -> Result<InstructionResult> {
+        debug!("{i}");
+        Ok(InstructionResult::Continue)
     }
 
     fn cmp_xchg(&mut self, i: &instruction::CmpXchg) -> Result<InstructionResult> {
@@ -615,7 +1277,12 @@ impl<'vm> LLVMExecutor<'vm> {
         self.state.memory.write(&address, result.clone())?;
 
         // The instructions returns a struct of { result, condition i1 }.
-        let result = condition.concat(&result); // TODO: Is this correct?
+        let result = struct_from_fields(
+            &self.state,
+            &i.result_type(),
+            [Some(result), Some(condition)],
+        )?
+        .ok_or(LLVMExecutorError::MalformedInstruction)?;
         Ok(InstructionResult::Assign(result))
     }
 
@@ -679,9 +1346,15 @@ impl<'vm> LLVMExecutor<'vm> {
         let source_element_type = i.source_element_type();
         let indices = i.indices();
 
+        // Offsets from symbolic indices can overflow the pointer width, which silently wraps the
+        // address around and can make it alias another allocation. Collect the conditions for
+        // that to happen.
+        let mut overflows = Vec::new();
+        let mut symbolic_index = None;
+
         // The first argument is *always* a pointer, so the first index always determine
         // "which element" we access, e.g., if we get an array.
-        let mut address = {
+        let mut total_offset = {
             let Some(index) = indices.first() else {
                 panic!("getelementptr should always have at least one index");
             };
@@ -689,23 +1362,64 @@ impl<'vm> LLVMExecutor<'vm> {
             let index = self.state.get_expr(index)?;
             let index = index.zero_ext(ptr_size).simplify();
 
-            let size_bytes = byte_size(&source_element_type, ptr_size)?;
+            let size_bytes = byte_size(&source_element_type, self.project)?;
             let size_bytes = self.state.ctx.from_u64(size_bytes.into(), ptr_size);
             let offset = index.mul(&size_bytes);
-            address.add(&offset)
+            if index.get_constant().is_none() {
+                overflows.push(index.smulo(&size_bytes));
+                symbolic_index.get_or_insert(index);
+            }
+            offset
         };
 
         let mut curr_ty = source_element_type;
         for index in indices.iter().skip(1) {
             let index = self.state.get_expr(index)?;
             let index = index.zero_ext(ptr_size).simplify();
-            let (offset, ty) = byte_offset(&curr_ty, &index, ptr_size, &self.state.ctx)?;
+            let (offset, ty) = byte_offset(&curr_ty, &index, self.project, self.state.ctx)?;
+
+            // Only arrays and vectors can be indexed by a symbolic index.
+            if index.get_constant().is_none() {
+                let size_bytes = byte_size(&ty, self.project)?;
+                let size_bytes = self.state.ctx.from_u64(size_bytes.into(), ptr_size);
+                overflows.push(index.smulo(&size_bytes));
+                overflows.push(total_offset.saddo(&offset));
+                symbolic_index.get_or_insert(index);
+            }
 
-            address = address.add(&offset);
+            total_offset = total_offset.add(&offset);
             curr_ty = ty;
         }
 
-        Ok(InstructionResult::Assign(address))
+        let result = address.add(&total_offset);
+        if let Some(index) = symbolic_index {
+            // The offset is signed, so the address wraps if it moves in the wrong direction.
+            let is_negative = total_offset.slt(&self.state.ctx.zero(ptr_size));
+            overflows.push(is_negative.ite(&result.ugt(&address), &result.ult(&address)));
+
+            let overflow = overflows
+                .into_iter()
+                .reduce(|lhs, rhs| lhs.or(&rhs))
+                .unwrap();
+            if self.state.constraints.is_sat_with_constraint(&overflow)? {
+                if self
+                    .state
+                    .constraints
+                    .is_sat_with_constraint(&overflow.not())?
+                {
+                    self.fork(overflow.not())?;
+                }
+
+                self.state.constraints.assert(&overflow);
+                let index = self.state.constraints.get_value(&index)?;
+                let index = index.get_constant().unwrap();
+                return Ok(InstructionResult::AnalysisError(
+                    AnalysisError::PointerOverflow { index },
+                ));
+            }
+        }
+
+        Ok(InstructionResult::Assign(result))
     }
 
     fn trunc(&mut self, i: &instruction::Trunc) -> Result<InstructionResult> {
@@ -737,20 +1451,28 @@ impl<'vm> LLVMExecutor<'vm> {
         todo!()
     }
 
-    fn fp_to_ui(&mut self, _i: &instruction::FPToUI) -> Result<InstructionResult> {
-        todo!()
+    fn fp_to_ui(&mut self, i: &instruction::FPToUI) -> Result<InstructionResult> {
+        debug!("{i}");
+        let result = fp_to_int(&mut self.state, i.value(), &i.to_type(), false)?;
+        Ok(InstructionResult::Assign(result))
     }
 
-    fn fp_to_si(&mut self, _i: &instruction::FPToSI) -> Result<InstructionResult> {
-        todo!()
+    fn fp_to_si(&mut self, i: &instruction::FPToSI) -> Result<InstructionResult> {
+        debug!("{i}");
+        let result = fp_to_int(&mut self.state, i.value(), &i.to_type(), true)?;
+        Ok(InstructionResult::Assign(result))
     }
 
-    fn ui_to_fp(&mut self, _i: &instruction::UIToFP) -> Result<InstructionResult> {
-        todo!()
+    fn ui_to_fp(&mut self, i: &instruction::UIToFP) -> Result<InstructionResult> {
+        debug!("{i}");
+        let result = int_to_fp(&mut self.state, i.value(), &i.to_type(), false)?;
+        Ok(InstructionResult::Assign(result))
     }
 
-    fn si_to_fp(&mut self, _i: &instruction::SIToFP) -> Result<InstructionResult> {
-        todo!()
+    fn si_to_fp(&mut self, i: &instruction::SIToFP) -> Result<InstructionResult> {
+        debug!("{i}");
+        let result = int_to_fp(&mut self.state, i.value(), &i.to_type(), true)?;
+        Ok(InstructionResult::Assign(result))
     }
 
     fn ptr_to_int(&mut self, i: &instruction::PtrToInt) -> Result<InstructionResult> {
@@ -760,10 +1482,20 @@ impl<'vm> LLVMExecutor<'vm> {
         Ok(InstructionResult::Assign(result))
     }
 
+    /// Convert an integer to a pointer.
+    ///
+    /// This works the same as for any other value, but an integer that was never a pointer does
+    /// not point into any allocation. Dereferencing it fails the path, so warn about it early.
     fn int_to_ptr(&mut self, i: &instruction::IntToPtr) -> Result<InstructionResult> {
         debug!("{i}");
         let f = |value: DExpr, target_size: u32| value.resize_unsigned(target_size);
         let result = convert_to_map(&mut self.state, i.value(), &i.to_type(), f)?;
+
+        if let (Type::Pointer(_), Some(address)) = (i.to_type(), result.get_constant()) {
+            if address != 0 && self.state.memory.allocation_containing(address).is_none() {
+                warn!("Integer {address:#x} cast to a pointer is not inside any allocation");
+            }
+        }
         Ok(InstructionResult::Assign(result))
     }
 
@@ -797,10 +1529,46 @@ impl<'vm> LLVMExecutor<'vm> {
         Ok(InstructionResult::Assign(result))
     }
 
-    fn fcmp(&mut self, _i: &instruction::FCmp) -> Result<InstructionResult> {
-        todo!()
+    fn fcmp(&mut self, i: &instruction::FCmp) -> Result<InstructionResult> {
+        debug!("{i}");
+        let element_type = match i.lhs().ty() {
+            Type::Vector(t) => t.element_type(),
+            ty => ty,
+        };
+        let (e, m) = float_layout(&element_type)?;
+        let ctx = self.state.ctx;
+
+        let f = |lhs: &DExpr, rhs: &DExpr| {
+            // The ordered comparisons are false if either operand is NaN, the unordered true.
+            let unordered = lhs.fp_is_nan(e, m).or(&rhs.fp_is_nan(e, m));
+            let eq = || lhs.fp_eq(rhs, e, m);
+            let lt = || lhs.fp_lt(rhs, e, m);
+            let gt = || rhs.fp_lt(lhs, e, m);
+            match i.predicate() {
+                LLVMRealPredicate::LLVMRealPredicateFalse => ctx.from_bool(false),
+                LLVMRealPredicate::LLVMRealOEQ => eq(),
+                LLVMRealPredicate::LLVMRealOGT => gt(),
+                LLVMRealPredicate::LLVMRealOGE => gt().or(&eq()),
+                LLVMRealPredicate::LLVMRealOLT => lt(),
+                LLVMRealPredicate::LLVMRealOLE => lt().or(&eq()),
+                LLVMRealPredicate::LLVMRealONE => lt().or(&gt()),
+                LLVMRealPredicate::LLVMRealORD => unordered.not(),
+                LLVMRealPredicate::LLVMRealUNO => unordered,
+                LLVMRealPredicate::LLVMRealUEQ => unordered.or(&eq()),
+                LLVMRealPredicate::LLVMRealUGT => unordered.or(&gt()),
+                LLVMRealPredicate::LLVMRealUGE => unordered.or(&gt()).or(&eq()),
+                LLVMRealPredicate::LLVMRealULT => unordered.or(&lt()),
+                LLVMRealPredicate::LLVMRealULE => unordered.or(&lt()).or(&eq()),
+                LLVMRealPredicate::LLVMRealUNE => eq().not(),
+                LLVMRealPredicate::LLVMRealPredicateTrue => ctx.from_bool(true),
+            }
+        };
+        let result = binop(&mut self.state, &i.lhs(), &i.rhs(), f)?;
+        Ok(InstructionResult::Assign(result))
     }
 
+    /// Pick the incoming value from the previous block, this works for any first class type
+    /// including aggregates.
     fn phi(&mut self, i: &instruction::Phi) -> Result<InstructionResult> {
         debug!("{i}");
 
@@ -814,15 +1582,82 @@ impl<'vm> LLVMExecutor<'vm> {
             }
 
             let value = self.state.get_expr(&value)?;
+            let value = self.summarize_phi(i, value)?;
             return Ok(InstructionResult::Assign(value));
         }
         panic!("Phi instruction without matching incoming value for previous block");
     }
 
-    fn select(&mut self, i: &instruction::Select) -> Result<InstructionResult> {
-        debug!("{i}");
-        let condition = i.condition();
-        let true_value = i.true_value();
+    /// Replace a loop induction variable with a fresh symbol when loops are summarized.
+    ///
+    /// A phi that already has a value in the current frame means its block is visited again, i.e.
+    /// it is a loop header. If the value changed since the last visit the phi is treated as an
+    /// induction variable, and the loop is marked to be exited at the header terminator, see
+    /// [`Self::exit_summarized_loop`].
+    fn summarize_phi(&mut self, i: &instruction::Phi, value: DExpr) -> Result<DExpr> {
+        if !self.vm.loop_summary {
+            return Ok(value);
+        }
+
+        let frame = self.state.current_frame()?;
+        let header = frame.current_block().clone();
+        let register = Value::Instruction(Instruction::Phi(i.clone()));
+        let Some(previous) = frame.get_register(&register).cloned() else {
+            return Ok(value);
+        };
+        if loop_exit(&header).is_none()
+            || self.state.constraints.must_be_equal(&previous, &value)?
+        {
+            return Ok(value);
+        }
+
+        debug!("Summarizing loop, replacing induction variable {i}");
+        self.state
+            .current_frame_mut()?
+            .set_summarized_loop(Some(header));
+        let value = self
+            .state
+            .ctx
+            .fresh_unconstrained(value.len(), "loop_summary");
+        Ok(value)
+    }
+
+    /// Leave the loop being summarized through the exit of its header.
+    ///
+    /// The exit condition is asserted on the fresh induction variables. If the loop cannot be
+    /// exited the path is suppressed, since it would never terminate.
+    fn exit_summarized_loop(&mut self, condition: &DExpr) -> Result<Option<InstructionResult>> {
+        let frame = self.state.current_frame_mut()?;
+        let header = frame.current_block().clone();
+        if frame.summarized_loop() != Some(&header) {
+            return Ok(None);
+        }
+        frame.set_summarized_loop(None);
+
+        let Some((exit, exit_on_true)) = loop_exit(&header) else {
+            return Ok(None);
+        };
+        let condition = match exit_on_true {
+            true => condition.clone(),
+            false => condition.not(),
+        };
+
+        if !self.state.constraints.is_sat_with_constraint(&condition)? {
+            return Ok(Some(InstructionResult::Suppress));
+        }
+        self.state.constraints.assert(&condition);
+        self.record_decision(&condition)?;
+        Ok(Some(InstructionResult::Branch(exit)))
+    }
+
+    /// Select one of two values depending on a condition.
+    ///
+    /// Aggregates (structs and arrays) are represented as a single concatenated expression, so for
+    /// scalar conditions the selection is performed on the full width of the value.
+    fn select(&mut self, i: &instruction::Select) -> Result<InstructionResult> {
+        debug!("{i}");
+        let condition = i.condition();
+        let true_value = i.true_value();
         let false_value = i.false_value();
 
         let condition_expr = self.state.get_expr(&condition)?;
@@ -842,7 +1677,7 @@ impl<'vm> LLVMExecutor<'vm> {
                     (Type::Vector(lhs), Type::Vector(rhs)) => {
                         assert_eq!(lhs.num_elements(), rhs.num_elements());
                         assert_eq!(lhs.element_type(), rhs.element_type());
-                        bit_size(&lhs.element_type(), self.state.project.ptr_size)?
+                        self.state.project.bit_size(&lhs.element_type())?
                     }
                     _ => panic!("Select true and false value must be vectors"),
                 };
@@ -881,8 +1716,13 @@ impl<'vm> LLVMExecutor<'vm> {
         todo!()
     }
 
-    fn landing_pad(&mut self, _i: &instruction::LandingPad) -> Result<InstructionResult> {
-        todo!()
+    fn landing_pad(&mut self, i: &instruction::LandingPad) -> Result<InstructionResult> {
+        debug!("{i}");
+        // The exception object and selector are not modeled, so leave them unconstrained.
+        let size = self.project.bit_size(&i.result_type())?;
+        Ok(InstructionResult::Assign(
+            self.state.ctx.fresh_unconstrained(size, "landingpad"),
+        ))
     }
 
     fn catch_pad(&mut self, _i: &instruction::CatchPad) -> Result<InstructionResult> {
@@ -912,6 +1752,10 @@ impl<'vm> LLVMExecutor<'vm> {
         debug!("{i}");
         let c = self.state.get_expr(&i.condition())?.simplify();
 
+        if let Some(result) = self.exit_summarized_loop(&c)? {
+            return Ok(result);
+        }
+
         // Fast path: skip checking satisfiability if the condition is constant.
         if let Some(target) = c.get_constant_bool() {
             let target = if target {
@@ -930,10 +1774,17 @@ impl<'vm> LLVMExecutor<'vm> {
                 self.fork_and_branch(i.false_destination(), Some(c.not()))?;
 
                 self.state.constraints.assert(&c);
+                self.record_decision(&c)?;
                 Ok(i.true_destination())
             }
-            (true, false) => Ok(i.true_destination()),
-            (false, true) => Ok(i.false_destination()),
+            (true, false) => {
+                self.record_decision(&c)?;
+                Ok(i.true_destination())
+            }
+            (false, true) => {
+                self.record_decision(&c.not())?;
+                Ok(i.false_destination())
+            }
             (false, false) => Err(SolverError::Unsat),
         }?;
         Ok(InstructionResult::Branch(target))
@@ -984,19 +1835,84 @@ impl<'vm> LLVMExecutor<'vm> {
         }
 
         self.state.constraints.assert(&constraint);
+        self.record_decision(&constraint)?;
         Ok(InstructionResult::Branch(target))
     }
 
-    fn indirect_br(&mut self, _i: &instruction::IndirectBr) -> Result<InstructionResult> {
-        todo!("indirect_br")
+    fn indirect_br(&mut self, i: &instruction::IndirectBr) -> Result<InstructionResult> {
+        debug!("{i}");
+        let address = self.state.get_expr(&i.address())?.simplify();
+
+        // Fast path: a concrete address maps directly to a block.
+        if let Some(address) = address.get_constant() {
+            let target = self
+                .state
+                .block_at_address(address)
+                .filter(|block| i.destinations().contains(*block));
+            return match target {
+                Some(target) => Ok(InstructionResult::Branch(target.clone())),
+                None => Ok(InstructionResult::AnalysisError(
+                    AnalysisError::InvalidBranchTarget,
+                )),
+            };
+        }
+
+        // The address must be one of the listed destinations, so check which of them it can be.
+        let mut possible_paths = Vec::new();
+        let mut is_destination = Vec::new();
+        for bb in i.destinations() {
+            let constraint = address._eq(&self.state.block_address(&bb)?);
+            if self.state.constraints.is_sat_with_constraint(&constraint)? {
+                debug!("{i}: path {:?} possible", bb);
+                possible_paths.push((bb, constraint.clone()));
+            }
+            is_destination.push(constraint);
+        }
+
+        let Some((target, constraint)) = possible_paths.pop() else {
+            return Ok(InstructionResult::AnalysisError(
+                AnalysisError::InvalidBranchTarget,
+            ));
+        };
+
+        // Jumping anywhere else is an error, the forked path finds no possible destinations.
+        let invalid = is_destination
+            .into_iter()
+            .reduce(|lhs, rhs| lhs.or(&rhs))
+            .map(|valid| valid.not());
+        if let Some(invalid) = invalid {
+            if self.state.constraints.is_sat_with_constraint(&invalid)? {
+                self.fork(invalid)?;
+            }
+        }
+
+        // Save all other paths.
+        for (bb, constraint) in possible_paths {
+            self.fork_and_branch(bb, Some(constraint))?;
+        }
+
+        self.state.constraints.assert(&constraint);
+        self.record_decision(&constraint)?;
+        Ok(InstructionResult::Branch(target))
     }
 
-    fn invoke(&mut self, _i: &instruction::Invoke) -> Result<InstructionResult> {
-        todo!()
+    fn invoke(&mut self, i: &instruction::Invoke) -> Result<InstructionResult> {
+        debug!("{i}");
+        // Works the same as a call, the normal destination is taken when the callee returns.
+        let call_fn = CallFn {
+            function: i.called_value(),
+            arguments: i.arguments(),
+        };
+        Ok(InstructionResult::CallFn(call_fn))
     }
 
-    fn resume(&mut self, _i: &instruction::Resume) -> Result<InstructionResult> {
-        todo!()
+    fn resume(&mut self, i: &instruction::Resume) -> Result<InstructionResult> {
+        debug!("{i}");
+        // Continue unwinding in the callers, the unwind is only reported once it reaches the top.
+        match Self::unwind(&mut self.state, self.vm.log_calls) {
+            Some(landing_pad) => Ok(InstructionResult::Branch(landing_pad)),
+            None => Ok(InstructionResult::AnalysisError(AnalysisError::Panic)),
+        }
     }
 
     fn unreachable(&mut self, _i: &instruction::Unreachable) -> Result<InstructionResult> {
@@ -1016,8 +1932,29 @@ impl<'vm> LLVMExecutor<'vm> {
         todo!()
     }
 
-    fn call_br(&mut self, _i: &instruction::CallBr) -> Result<InstructionResult> {
-        todo!()
+    /// Inline assembly that may jump to one of several labels, i.e. `asm goto`.
+    ///
+    /// The assembly itself is not modeled, so any output is unconstrained and all destinations are
+    /// explored. The default destination is taken first and the others are saved as new paths.
+    fn call_br(&mut self, i: &instruction::CallBr) -> Result<InstructionResult> {
+        debug!("{i}");
+        if !matches!(i.called_value(), Value::InlineAsm) {
+            return Err(LLVMExecutorError::UnsupportedInstruction(
+                "callbr to a function".to_owned(),
+            ));
+        }
+
+        let size = self.project.bit_size(&i.result_type())?;
+        if size > 0 {
+            let output = self.state.ctx.fresh_unconstrained(size, "asm");
+            let register = Value::Instruction(Instruction::CallBr(i.clone()));
+            self.assign_result(register, output)?;
+        }
+
+        for destination in i.indirect_destinations() {
+            self.fork_and_branch(destination, None)?;
+        }
+        Ok(InstructionResult::Branch(i.default_destination()))
     }
 }
 
@@ -1046,16 +1983,19 @@ where
         // Some may support pointer operands, such as icmp, which work the same as integer ones.
         (Type::Pointer(_), Type::Pointer(_)) => Ok(operation(&lhs, &rhs)),
 
+        // Floating point values are bit vectors in IEEE 754 format, the operation interprets them.
+        (Type::Float(_), Type::Float(_)) => Ok(operation(&lhs, &rhs)),
+
         // The docs do not really specify how the vector operations should work. But I'll assume it
         // is the operation on a per element basis.
         (Type::Vector(t0), Type::Vector(t1)) if !(t0.is_scalable() || t1.is_scalable()) => {
             let element_type = t0.element_type();
             let num_elements = t0.num_elements();
-            let bits = bit_size(&element_type, state.project.ptr_size).unwrap();
+            let bits = state.project.bit_size(&element_type)?;
 
             let rhs_element_type = t1.element_type();
             let rhs_num_elements = t1.num_elements();
-            let rhs_bits = bit_size(&rhs_element_type, state.project.ptr_size).unwrap();
+            let rhs_bits = state.project.bit_size(&rhs_element_type)?;
 
             assert_eq!(element_type, rhs_element_type);
             assert_eq!(num_elements, rhs_num_elements);
@@ -1079,20 +2019,29 @@ where
     }
 }
 
-pub(crate) fn bit_size(ty: &Type, ptr_size: u32) -> Result<u32> {
+/// Calculates the size of the type in bits.
+///
+/// Structures include the padding between their fields and at the end, as given by `layout`.
+pub(crate) fn bit_size(ty: &Type, ptr_size: u32, layout: &DataLayout) -> Result<u32> {
     match ty {
         Type::Void => Ok(0),
         Type::Integer(t) => Ok(t.bits()),
         Type::Float(t) => Ok(t.bits()),
         Type::Pointer(_) => Ok(ptr_size),
         Type::Vector(t) => {
-            bit_size(&t.element_type(), ptr_size).map(|size| size * t.num_elements())
+            bit_size(&t.element_type(), ptr_size, layout).map(|size| size * t.num_elements())
         }
         Type::Array(t) => {
-            bit_size(&t.element_type(), ptr_size).map(|size| size * t.num_elements() as u32)
+            bit_size(&t.element_type(), ptr_size, layout).map(|size| size * t.num_elements() as u32)
         }
-        Type::Structure(t) => t.fields().into_iter().map(|f| bit_size(&f, ptr_size)).sum(),
-        Type::OpaqueStructure => todo!("Cannot get size of opaque structure"),
+        Type::Structure(t) => {
+            // The layout is only known for sized structures, so check the fields first.
+            for field in t.fields() {
+                bit_size(&field, ptr_size, layout)?;
+            }
+            Ok(t.abi_size(layout) as u32 * BITS_IN_BYTE)
+        }
+        Type::OpaqueStructure(t) => Err(no_size(t)),
 
         // TODO: How are these sized?
         Type::Function(_)
@@ -1105,9 +2054,126 @@ pub(crate) fn bit_size(ty: &Type, ptr_size: u32) -> Result<u32> {
     }
 }
 
+/// Describe the location of `instruction` in `function`, using the source location if there is
+/// debug info.
+pub(crate) fn instruction_location(
+    function: &Function,
+    instruction: Option<&Instruction>,
+) -> String {
+    let name = function.name().to_string_lossy();
+    let function_name = format!("{:#}", rustc_demangle::demangle(&name));
+
+    match instruction {
+        Some(instruction) => match instruction.debug_location() {
+            Some(location) => format!("{function_name} ({location})"),
+            None => format!("{function_name}: {instruction}"),
+        },
+        None => function_name,
+    }
+}
+
+/// Check if `function` is a single basic block of at most `threshold` instructions, without
+/// calls or memory writes. Calls to the `llvm.dbg.*` intrinsics only carry debug info, so they
+/// are not counted.
+fn fits_inline_threshold(function: &Function, threshold: usize) -> bool {
+    let mut blocks = function.basic_blocks();
+    let (Some(block), None) = (blocks.next(), blocks.next()) else {
+        return false;
+    };
+
+    for (count, instruction) in block
+        .instructions()
+        .filter(|instruction| !is_debug_intrinsic(instruction))
+        .enumerate()
+    {
+        if count >= threshold {
+            return false;
+        }
+        match instruction {
+            Instruction::Ret(_) => return true,
+            Instruction::Call(_)
+            | Instruction::Invoke(_)
+            | Instruction::CallBr(_)
+            | Instruction::Store(_)
+            | Instruction::Alloca(_)
+            | Instruction::AtomicRMW(_)
+            | Instruction::CmpXchg(_)
+            | Instruction::Fence(_)
+            | Instruction::VAArg(_) => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Returns `true` for calls to the `llvm.dbg.*` intrinsics, which do nothing when executed.
+fn is_debug_intrinsic(instruction: &Instruction) -> bool {
+    let Instruction::Call(call) = instruction else {
+        return false;
+    };
+    matches!(
+        call.called_value(),
+        Value::Function(function) if function.name().to_bytes().starts_with(b"llvm.dbg.")
+    )
+}
+
+/// Check if `target` can be reached from `from` by following the branches in a function.
+fn reaches(from: &BasicBlock, target: &BasicBlock) -> bool {
+    let mut visited = HashSet::new();
+    let mut queue = vec![from.clone()];
+    while let Some(block) = queue.pop() {
+        if &block == target {
+            return true;
+        }
+        if visited.insert(block.clone()) {
+            queue.extend(block.successors());
+        }
+    }
+    false
+}
+
+/// Get the exit of the loop with `header`, if the loop is left from a conditional branch in it.
+///
+/// Returns the block outside the loop and the value of the condition that branches to it.
+fn loop_exit(header: &BasicBlock) -> Option<(BasicBlock, bool)> {
+    let Some(Instruction::CondBr(br)) = header.terminator() else {
+        return None;
+    };
+    let true_in_loop = reaches(&br.true_destination(), header);
+    let false_in_loop = reaches(&br.false_destination(), header);
+    match (true_in_loop, false_in_loop) {
+        (false, true) => Some((br.true_destination(), true)),
+        (true, false) => Some((br.false_destination(), false)),
+        _ => None,
+    }
+}
+
+/// Error for an opaque structure that has no size.
+fn no_size(ty: &StructureType) -> LLVMExecutorError {
+    let name = ty.name().map(|name| name.to_string_lossy().into_owned());
+    LLVMExecutorError::NoSize(name.unwrap_or_else(|| "opaque structure".to_owned()))
+}
+
+/// Convert errors which are caused by the analyzed program into a failure of the current path.
+///
+/// Types without a size, e.g. opaque structures used for FFI, freed memory being accessed or freed
+/// again, and symbolic allocations that never fit only fail the path. All other errors stop the
+/// analysis.
+fn path_failure(error: LLVMExecutorError) -> Result<AnalysisError> {
+    match error {
+        LLVMExecutorError::NoSize(ty) => Ok(AnalysisError::UnsizedType(ty)),
+        LLVMExecutorError::MemoryError(MemoryError::UseAfterFree) => {
+            Ok(AnalysisError::UseAfterFree)
+        }
+        LLVMExecutorError::MemoryError(MemoryError::InvalidFree) => Ok(AnalysisError::InvalidFree),
+        LLVMExecutorError::AllocationTooLarge(max) => Ok(AnalysisError::AllocationTooLarge { max }),
+        error => Err(error),
+    }
+}
+
 /// Calculates the size of the type in bytes.
-pub(crate) fn byte_size(ty: &Type, ptr_size: u32) -> Result<u32> {
-    let bit_size = bit_size(ty, ptr_size)?;
+pub(crate) fn byte_size(ty: &Type, project: &Project) -> Result<u32> {
+    let bit_size = project.bit_size(ty)?;
     let byte_size = to_bytes_u32(bit_size)?;
     Ok(byte_size)
 }
@@ -1128,53 +2194,81 @@ pub(crate) fn get_element_offset(
         aggregate_type = inner_ty;
     }
 
-    let ptr_size = state.project.ptr_size;
-    let element_size = bit_size(&aggregate_type, ptr_size)?;
+    let element_size = state.project.bit_size(&aggregate_type)?;
     let upper_bound = lower_bound + element_size;
 
     Ok((lower_bound, upper_bound))
 }
 
+/// Combine the fields of a structure of type `ty` into a single value, with each field at its
+/// offset in the data layout. The padding between the fields and at the end is zero.
+///
+/// Zero sized fields have no value and are given as `None`. Returns `None` if the structure has
+/// no size.
+pub(crate) fn struct_from_fields(
+    state: &LLVMState,
+    ty: &Type,
+    fields: impl IntoIterator<Item = Option<DExpr>>,
+) -> Result<Option<DExpr>> {
+    let Type::Structure(t) = ty else {
+        return Err(LLVMExecutorError::MalformedInstruction);
+    };
+    let offsets = state.project.field_offsets(t)?;
+
+    // Collect the fields and the padding from the least significant bits.
+    let mut parts = Vec::new();
+    let mut end = 0;
+    for (offset, field) in offsets.into_iter().zip(fields) {
+        let Some(field) = field else {
+            continue;
+        };
+        if offset > end {
+            parts.push(state.ctx.zero(offset - end));
+        }
+        end = offset + field.len();
+        parts.push(field);
+    }
+    let size = state.project.bit_size(ty)?;
+    if size > end {
+        parts.push(state.ctx.zero(size - end));
+    }
+
+    Ok(parts.into_iter().reduce(|acc, part| part.concat(&acc)))
+}
+
 /// Calculate the offset in bits from a concrete index.
 pub(crate) fn get_bit_offset_concrete(
     ty: &Type,
     index: u32,
     project: &Project,
 ) -> Result<(u32, Type)> {
-    let ptr_size = project.ptr_size;
-
     match ty {
         // Can this work with opaque pointers? Don't think so.
         Type::Pointer(_) => todo!(),
 
         Type::Vector(t) => {
             let element_type = t.element_type();
-            let element_size = bit_size(&element_type, ptr_size).unwrap();
+            let element_size = project.bit_size(&element_type)?;
             Ok((element_size * index, element_type))
         }
 
         Type::Array(t) => {
             let element_type = t.element_type();
-            let element_size = bit_size(&element_type, ptr_size).unwrap();
+            let element_size = project.bit_size(&element_type)?;
             Ok((element_size * index, element_type))
         }
 
         Type::Structure(t) => {
-            let fields = t.fields();
-            let offset = fields
-                .iter()
-                .take(index as usize)
-                .map(|f| bit_size(f, ptr_size))
-                .sum::<Result<u32>>()?;
-
-            let inner_ty = fields
+            let offset = project
+                .field_offsets(t)?
                 .get(index as usize)
-                .cloned()
+                .copied()
                 .ok_or(LLVMExecutorError::MalformedInstruction)?;
+            let inner_ty = t.fields().swap_remove(index as usize);
 
             Ok((offset, inner_ty))
         }
-        Type::OpaqueStructure => Err(LLVMExecutorError::NoSize),
+        Type::OpaqueStructure(t) => Err(no_size(t)),
 
         // We cannot index into these types.
         Type::X86Amx
@@ -1197,166 +2291,1536 @@ pub(crate) fn get_bit_offset_concrete(
 pub(crate) fn byte_offset(
     ty: &Type,
     index: &DExpr,
-    ptr_size: u32,
+    project: &Project,
     ctx: &DContext,
 ) -> Result<(DExpr, Type)> {
     match ty {
         Type::Pointer(_) => todo!(),
 
-        Type::Vector(t) => {
-            let element_type = t.element_type();
-            let element_size = byte_size(&element_type, ptr_size)?;
-            let element_size = ctx.from_u64(element_size.into(), index.len());
-            Ok((element_size.mul(index), element_type))
-        }
+        Type::Vector(t) => {
+            let element_type = t.element_type();
+            let element_size = byte_size(&element_type, project)?;
+            let element_size = ctx.from_u64(element_size.into(), index.len());
+            Ok((element_size.mul(index), element_type))
+        }
+
+        Type::Array(t) => {
+            let element_type = t.element_type();
+            let element_size = byte_size(&element_type, project)?;
+            let element_size = ctx.from_u64(element_size.into(), index.len());
+            Ok((element_size.mul(index), element_type))
+        }
+
+        // For structs the offset of the member is taken from the data layout. Not supported for
+        // non-constant indexes.
+        //
+        // With a symbol as an index we cannot index into structs, not without having to try
+        // solutions and fork the state. So these are not supported.
+        Type::Structure(t) => {
+            let index_len = index.len();
+            let Some(index) = index.get_constant() else {
+                panic!("Cannot index into struct with non-constant index");
+            };
+
+            let offset = project
+                .field_offsets(t)?
+                .get(index as usize)
+                .copied()
+                .ok_or(LLVMExecutorError::MalformedInstruction)?;
+            let inner_ty = t.fields().swap_remove(index as usize);
+
+            let offset = ctx.from_u64((offset / BITS_IN_BYTE).into(), index_len);
+            Ok((offset, inner_ty))
+        }
+        Type::OpaqueStructure(t) => Err(no_size(t)),
+
+        // We cannot index into these types.
+        Type::Void => todo!(),
+        Type::Integer(_) => todo!(),
+        Type::Float(_) => todo!(),
+        Type::Function(_) => todo!(),
+        Type::Label => todo!(),
+        Type::Token => todo!(),
+        Type::Metadata => todo!(),
+        Type::X86Amx => todo!(),
+        Type::X86Mmx => todo!(),
+        Type::TargetExtension(_) => todo!(),
+    }
+}
+
+/// Converts integers, pointers, or vectors.
+///
+/// Performs a conversion on either (int,int), (ptr,int), (int,ptr), or (vector,vector) with the
+/// passed mapping function.
+///
+/// No type checking is done, if this is of interest they have to be checked before calling this
+/// function.
+pub(crate) fn convert_to_map<F>(
+    state: &mut LLVMState,
+    value: Value,
+    to_type: &Type,
+    map: F,
+) -> Result<DExpr>
+where
+    F: Fn(DExpr, u32) -> DExpr,
+{
+    let value_type = value.ty();
+    let value = state.get_expr(&value)?;
+
+    match (value_type, to_type) {
+        // Integer to integer conversion are done by trunc, zext, and sext. While the ptr->int,
+        // int->ptr are done by ptrtoint and inttoptr. All these should be supported.
+        (Type::Integer(_), Type::Integer(_))
+        | (Type::Integer(_), Type::Pointer(_))
+        | (Type::Pointer(_), Type::Integer(_)) => {
+            let target_bits = state.project.bit_size(to_type)?;
+            Ok(map(value, target_bits))
+        }
+
+        // Vectors are a bit more annoying, in that the elements have to be processed one by one.
+        (Type::Vector(source), Type::Vector(target))
+            if !(source.is_scalable() || target.is_scalable()) =>
+        {
+            let source_bits = state.project.bit_size(&source.element_type())?;
+            let target_bits = state.project.bit_size(&target.element_type())?;
+
+            let num_elements = source.num_elements();
+            assert!(source_bits * num_elements == value.len());
+
+            // Process each element one by one and concatenate the result.
+            (0..num_elements)
+                .map(|i| {
+                    let low = i * source_bits;
+                    let high = (i + 1) * source_bits - 1;
+                    let symbol = value.slice(low, high);
+                    map(symbol, target_bits)
+                })
+                .reduce(|acc, v| v.concat(&acc))
+                .ok_or(LLVMExecutorError::MalformedInstruction)
+        }
+
+        // The other types should not appear for this instruction.
+        _ => Err(LLVMExecutorError::MalformedInstruction),
+    }
+}
+
+/// Converts a value element by element.
+///
+/// Works like [`convert_to_map`] but passes the source and target element types to the mapping
+/// function, which is required for conversions between integers and floating point values.
+pub(crate) fn convert_elements<F>(
+    state: &mut LLVMState,
+    value: Value,
+    to_type: &Type,
+    mut map: F,
+) -> Result<DExpr>
+where
+    F: FnMut(DExpr, &Type, &Type) -> Result<DExpr>,
+{
+    let value_type = value.ty();
+    let value = state.get_expr(&value)?;
+
+    match (&value_type, to_type) {
+        (Type::Vector(source), Type::Vector(target))
+            if !(source.is_scalable() || target.is_scalable()) =>
+        {
+            let source_type = source.element_type();
+            let target_type = target.element_type();
+            let source_bits = state.project.bit_size(&source_type)?;
+
+            let num_elements = source.num_elements();
+            assert!(source_bits * num_elements == value.len());
+
+            // Process each element one by one and concatenate the result.
+            let mut result: Option<DExpr> = None;
+            for i in 0..num_elements {
+                let low = i * source_bits;
+                let high = (i + 1) * source_bits - 1;
+                let element = map(value.slice(low, high), &source_type, &target_type)?;
+                result = Some(match result {
+                    Some(acc) => element.concat(&acc),
+                    None => element,
+                });
+            }
+            result.ok_or(LLVMExecutorError::MalformedInstruction)
+        }
+
+        // Vectors can only be converted to vectors.
+        (Type::Vector(_), _) | (_, Type::Vector(_)) => Err(LLVMExecutorError::MalformedInstruction),
+
+        (source, target) => map(value, source, target),
+    }
+}
+
+/// Returns the number of exponent and mantissa bits of a floating point type.
+fn float_layout(ty: &Type) -> Result<(u32, u32)> {
+    match ty {
+        Type::Float(t) => t.exponent_and_mantissa_bits().ok_or_else(|| {
+            LLVMExecutorError::UnsupportedInstruction(format!("conversion with {t}"))
+        }),
+        _ => Err(LLVMExecutorError::MalformedInstruction),
+    }
+}
+
+/// Converts integers (or vectors of integers) to floating point values.
+pub(crate) fn int_to_fp(
+    state: &mut LLVMState,
+    value: Value,
+    to_type: &Type,
+    signed: bool,
+) -> Result<DExpr> {
+    convert_elements(state, value, to_type, |value, _, target| {
+        let (exponent_bits, mantissa_bits) = float_layout(target)?;
+        Ok(match signed {
+            true => value.sint_to_fp(exponent_bits, mantissa_bits),
+            false => value.uint_to_fp(exponent_bits, mantissa_bits),
+        })
+    })
+}
+
+/// Converts floating point values (or vectors of) to integers, rounding towards zero.
+///
+/// If the value does not fit in the integer type the result is poison, which is represented as
+/// an unconstrained value.
+pub(crate) fn fp_to_int(
+    state: &mut LLVMState,
+    value: Value,
+    to_type: &Type,
+    signed: bool,
+) -> Result<DExpr> {
+    let ctx = state.ctx;
+    let project = state.project;
+
+    let mut out_of_range = Vec::new();
+    let result = convert_elements(state, value, to_type, |value, source, target| {
+        let (exponent_bits, mantissa_bits) = float_layout(source)?;
+        let bits = project.bit_size(target)?;
+
+        let (result, poison) = match signed {
+            true => value.fp_to_sint(exponent_bits, mantissa_bits, bits),
+            false => value.fp_to_uint(exponent_bits, mantissa_bits, bits),
+        };
+        let result = poison.ite(&ctx.fresh_unconstrained(bits, "poison"), &result);
+
+        out_of_range.push(poison);
+        Ok(result)
+    })?;
+
+    if let Some(out_of_range) = out_of_range.into_iter().reduce(|acc, c| acc.or(&c)) {
+        if state.constraints.is_sat_with_constraint(&out_of_range)? {
+            warn!(
+                "Floating point value may be out of range for the integer type, result is poison"
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::BlockVisits;
+
+    const INSTRUCTIONS: &str = "tests/unit_tests/instructions.bc";
+
+    /// Create a VM for the function `fn_name` in the module at `file`.
+    fn vm_for(file: &str, fn_name: &str) -> VM {
+        let project = Box::new(Project::from_path(file).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        VM::new(project, context, fn_name).expect("Failed to create VM")
+    }
+
+    fn run(fn_name: &str) -> Vec<Option<i64>> {
+        // let subscriber = tracing_subscriber::FmtSubscriber::builder()
+        //     .with_max_level(tracing::Level::TRACE)
+        //     .finish();
+        // tracing::subscriber::set_global_default(subscriber)
+        //     .expect("setting default subscriber failed");
+
+        let path = format!("tests/unit_tests/instructions.bc");
+        let project = Box::new(Project::from_path(&path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, fn_name).expect("Failed to create VM");
+        run_vm(&mut vm)
+    }
+
+    /// Run a function returning a single value wider than 64 bits.
+    fn run_wide(fn_name: &str) -> u128 {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, fn_name).expect("Failed to create VM");
+
+        let (result, state) = vm.run().unwrap().expect("expected a path");
+        let PathResult::Success(Some(value)) = result else {
+            panic!("expected a return value, got {result:?}");
+        };
+        let value = state.constraints.get_value(&value).unwrap();
+        u128::from_str_radix(&value.to_binary_string(), 2).unwrap()
+    }
+
+    fn run_vm(vm: &mut VM) -> Vec<Option<i64>> {
+        let mut path_results = Vec::new();
+        while let Some((path_result, state)) = vm.run().expect("Failed to run path") {
+            let result = match path_result {
+                PathResult::Success(Some(value)) => {
+                    let value = state
+                        .constraints
+                        .get_value(&value)
+                        .expect("Failed to get concrete value");
+                    let binary_str = value.to_binary_string();
+                    Some(u128::from_str_radix(&binary_str, 2).unwrap() as i64)
+                }
+                PathResult::Success(None) => None,
+                PathResult::Failure(error) => {
+                    panic!("Did not expect any paths to fail, reason: {error:?}")
+                }
+                PathResult::Suppress => panic!("Did not expect any paths to be suppressed"),
+                PathResult::AssumptionUnsat => panic!("Did not expect any paths to be unsat"),
+            };
+            path_results.push(result);
+        }
+
+        eprintln!("{path_results:x?}");
+        path_results
+    }
+
+    #[test]
+    fn test_indirectbr() {
+        let res = run("test_indirectbr");
+        assert_eq!(res, vec![Some(3), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_indirectbr_no_destination() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_indirectbr_no_destination");
+
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(
+            result,
+            PathResult::Failure(AnalysisError::InvalidBranchTarget)
+        );
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_indirectbr_invalid_destination() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_indirectbr_invalid_destination");
+
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert!(matches!(result, PathResult::Success(Some(_))));
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(
+            result,
+            PathResult::Failure(AnalysisError::InvalidBranchTarget)
+        );
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_bfs_path_selection() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_indirectbr").expect("Failed to create VM");
+        vm.set_path_selection(Box::new(crate::vm::BFSPathSelection::new()));
+
+        // The saved paths are resumed in the order they were saved, instead of the reverse.
+        let res = run_vm(&mut vm);
+        assert_eq!(res, vec![Some(3), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_random_path_selection() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project: &'static Project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context: &'static DContext = Box::leak(context);
+        let run_with_seed = |seed| {
+            let mut vm = VM::new(project, context, "test_indirectbr").expect("Failed to create VM");
+            vm.set_path_selection(Box::new(crate::vm::RandomPathSelection::new(seed)));
+            run_vm(&mut vm)
+        };
+
+        // All paths are explored, in the same order for the same seed.
+        let res = run_with_seed(7);
+        assert_eq!(res, run_with_seed(7));
+        let mut sorted = res.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_log_constraints() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_indirectbr").expect("Failed to create VM");
+        vm.log_constraints = true;
+
+        // Every path takes a single symbolic branch, which should be the only one recorded.
+        let mut num_paths = 0;
+        while let Some((_, state)) = vm.run().expect("Failed to run path") {
+            num_paths += 1;
+            assert_eq!(state.branch_constraints.len(), 1);
+            assert!(state.branch_constraints[0]
+                .location
+                .contains("test_indirectbr"));
+            let script = state.constraints.to_smt2();
+            assert_eq!(
+                script.matches("(assert ").count(),
+                state.constraints.assertions().len()
+            );
+        }
+        assert_eq!(num_paths, 3);
+    }
+
+    #[test]
+    fn test_log_calls() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_call").expect("Failed to create VM");
+        vm.log_calls = true;
+
+        let (_, state) = vm.run().unwrap().expect("expected a path");
+        let enter = |name: &str| CallEvent::Enter(name.to_owned());
+        let exit = |name: &str| CallEvent::Exit(name.to_owned());
+        assert_eq!(
+            state.call_log,
+            vec![
+                enter("test_call"),
+                enter("test_call_called_function"),
+                exit("test_call_called_function"),
+                exit("test_call"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_callbr() {
+        let res = run("test_callbr");
+        assert_eq!(res, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_indirectbr_stored_address() {
+        let res = run("test_indirectbr_stored_address");
+        assert_eq!(res, vec![Some(2)]);
+    }
+
+    #[test]
+    fn test_indirectbr_unlisted_destination() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_indirectbr_unlisted_destination");
+
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(
+            result,
+            PathResult::Failure(AnalysisError::InvalidBranchTarget)
+        );
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_reset_to() {
+        let path = format!("tests/unit_tests/instructions.bc");
+        let project = Box::new(Project::from_path(&path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_indirectbr").expect("Failed to create VM");
+
+        // Leave the other paths unexplored, they should not be visible after the reset.
+        vm.run()
+            .expect("Failed to run path")
+            .expect("Expected a path");
+
+        vm.reset_to("test_call").expect("Failed to reset VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(0xabcd)]);
+
+        vm.reset_to("test_indirectbr").expect("Failed to reset VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(3), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_entry_by_raw_name() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        // Unmangled functions keep their name, so the module prefix added by cargo-symex has to
+        // be ignored.
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "unit_tests::test_call").expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(0xabcd)]);
+    }
+
+    #[test]
+    fn test_uninitialized_memory() {
+        use crate::memory::UninitializedMemory;
+
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_uninitialized_read").expect("Failed to create VM");
+        vm.uninitialized_memory = UninitializedMemory::Byte(0xab);
+        assert_eq!(run_vm(&mut vm), vec![Some(0xabababab)]);
+    }
+
+    #[test]
+    fn test_loop_summary() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_loop_summary").expect("Failed to create VM");
+        vm.loop_summary = true;
+
+        // One path skips the loop, the other runs one iteration and is then summarized.
+        assert_eq!(run_vm(&mut vm), vec![Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn test_loop_summary_two_loops() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_loop_summary_two_loops");
+        vm.loop_summary = true;
+
+        // Each loop is either skipped or summarized, the summaries use different symbols.
+        assert_eq!(run_vm(&mut vm), vec![Some(1); 4]);
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_zero_sized_param").expect("Failed to create VM");
+
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let recorded = reports.clone();
+        vm.set_progress_callback(1, Box::new(move |p| recorded.borrow_mut().push(p.clone())));
+        assert_eq!(run_vm(&mut vm), vec![Some(1), Some(2)]);
+
+        // Called for every instruction, and the second path is pending while the first runs.
+        let reports = reports.borrow();
+        let progress = vm.progress();
+        assert_eq!(reports.len() as u64, progress.instructions);
+        assert_eq!(progress.paths_completed, 2);
+        assert_eq!(progress.paths_pending, 0);
+        assert!(reports
+            .iter()
+            .any(|p| p.paths_completed == 0 && p.paths_pending == 1));
+    }
+
+    #[test]
+    fn test_option_niche() {
+        // Some is explored first since the switch takes the last possible case.
+        let res = run("test_option_niche");
+        assert_eq!(res, vec![Some(1), Some(0)]);
+
+        // None, then Some with both fields read back unchanged.
+        let res = run("test_option_niche_aggregate");
+        assert_eq!(res, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_entry_function_in_module() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+
+        match project.find_entry_function("dup::f") {
+            Err(LLVMExecutorError::AmbiguousFunction { name, candidates }) => {
+                assert_eq!(name, "dup::f");
+                assert_eq!(candidates.len(), 2);
+                assert!(candidates[0].contains("dup-1.0.0/src/lib.rs"));
+                assert!(candidates[1].contains("dup-2.0.0/src/lib.rs"));
+            }
+            other => panic!("Expected an ambiguous function, got {other:?}"),
+        }
+
+        let mut vm = VM::new_in_module(project, context, "dup::f", "dup-2.0.0/src/lib.rs")
+            .expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(2)]);
+
+        // The full symbol is always unique.
+        let mut vm = VM::new(project, context, "_ZN3dup1f17h0123456789abcdefE")
+            .expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(1)]);
+    }
+
+    #[test]
+    fn test_shuffle_vector() {
+        let res = run("test_shuffle_vector");
+        assert_eq!(res, vec![Some(0x0002_0003_0001_0004)]);
+
+        let res = run("test_shuffle_vector_undef");
+        assert_eq!(
+            res,
+            vec![Some(0x0003_0001_0000_0004), Some(0x0003_0001_0000_0004)]
+        );
+    }
+
+    #[test]
+    fn test_diverging_entry() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_diverges").expect("Failed to create VM");
+
+        let mut num_paths = 0;
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            assert_eq!(result, PathResult::Failure(AnalysisError::Panic));
+            assert!(vm.is_divergence(&result));
+            num_paths += 1;
+        }
+        assert_eq!(num_paths, 2);
+
+        // Panics are still errors in functions that can return.
+        vm.reset_to("test_reachable_errors").unwrap();
+        let (result, _) = vm
+            .find_reachable_errors()
+            .unwrap()
+            .into_iter()
+            .next()
+            .expect("Expected an error");
+        assert_eq!(result, PathResult::Failure(AnalysisError::Panic));
+        assert!(!vm.is_divergence(&result));
+    }
+
+    #[test]
+    fn test_zero_sized_param() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_zero_sized_param").expect("Failed to create VM");
+
+        // The unit parameter is a no-op, only `x` is symbolic.
+        assert_eq!(run_vm(&mut vm), vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_reachable_markers() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_reachable").expect("Failed to create VM");
+        // The marker in the function that is never called is not included.
+        assert_eq!(vm.unreached_markers().len(), 3);
+
+        run_vm(&mut vm);
+        let unreached = vm.unreached_markers();
+        assert_eq!(unreached.len(), 1);
+        assert!(unreached[0].contains("test_reachable"));
+    }
+
+    #[test]
+    fn test_symbolic_named() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_symbolic_named").expect("Failed to create VM");
+        let (_, state) = vm.run().expect("Failed to run path").expect("No path");
+
+        let names: Vec<_> = state
+            .marked_symbolic
+            .iter()
+            .filter_map(|variable| variable.name.as_deref())
+            .collect();
+        assert!(names.contains(&"input"), "{names:?}");
+    }
+
+    #[test]
+    fn test_concretize() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_concretize");
+
+        // The argument is constrained to the value it was concretized to.
+        let mut results = Vec::new();
+        while let Some((result, state)) = vm.run().expect("Failed to run path") {
+            let PathResult::Success(Some(value)) = result else {
+                panic!("Unexpected result {result:?}");
+            };
+            let x = &state.marked_symbolic[0].value;
+            let x = state.constraints.get_value(x).unwrap();
+            assert_eq!(value.get_constant(), x.get_constant());
+            results.push(value);
+        }
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_callers_of() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Project::from_path(path).expect("Failed to created project");
+        let names = |functions: &[llvm_ir::Function]| -> Vec<String> {
+            functions
+                .iter()
+                .map(|f| f.name().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let callers = project.callers_of("core::panicking::panic");
+        let direct = names(&callers.direct);
+        assert!(direct.contains(&"test_reachable_errors".to_owned()));
+        assert!(direct.contains(&"test_diverges".to_owned()));
+        assert!(direct.contains(&"panic_with_cleanup".to_owned()));
+
+        // The call through the alias resolves, the call through the loaded pointer does not.
+        let callers = project.callers_of("test_call_called_function");
+        assert!(names(&callers.direct).contains(&"test_call_through_alias".to_owned()));
+        assert!(names(&callers.unresolved).contains(&"test_call_through_alias".to_owned()));
+    }
+
+    #[test]
+    fn test_global_addresses_deterministic() {
+        let addresses = || {
+            let path = "tests/unit_tests/instructions.bc";
+            let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+            let project = Box::leak(project);
+
+            let context = Box::new(DContext::new());
+            let context = Box::leak(context);
+            let mut vm =
+                VM::new(project, context, "test_zeroed_global").expect("Failed to create VM");
+            let (_, state) = vm.run().expect("Failed to run path").expect("No path");
+
+            let global = project.find_global("zeroed").expect("Global not found");
+            let function = project
+                .find_entry_function("test_call_called_function")
+                .expect("Function not found");
+            (
+                state.global_lookup[&Value::Global(Global::Variable(global))],
+                state.global_lookup[&Value::Function(function)],
+            )
+        };
+
+        assert_eq!(addresses(), addresses());
+    }
+
+    #[test]
+    fn test_inline_threshold() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_inline_loop").expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(0x39a5)]);
+        let called = vm.progress().instructions;
+
+        let mut vm = VM::new(project, context, "test_inline_loop").expect("Failed to create VM");
+        vm.inline_threshold = 3;
+        vm.log_calls = true;
+        let (result, state) = vm.run().expect("Failed to run path").expect("No path");
+        let PathResult::Success(Some(value)) = result else {
+            panic!("Expected a return value, got {result:?}");
+        };
+        let value = state
+            .constraints
+            .get_value(&value)
+            .expect("Failed to get value");
+        assert_eq!(value.get_constant(), Some(0x39a5));
+
+        // The same instructions are executed and the ten calls are still logged, only the frames
+        // differ.
+        assert_eq!(vm.progress().instructions, called);
+        assert_eq!(state.call_log.len(), 2 + 20);
+    }
+
+    #[test]
+    fn test_inline_debug_info() {
+        let project = Project::from_path(INSTRUCTIONS).expect("Failed to created project");
+        let helper = project.find_entry_function("inline_helper_dbg").unwrap();
+
+        // The call to `llvm.dbg.value` is not counted.
+        assert!(fits_inline_threshold(&helper, 3));
+        assert!(!fits_inline_threshold(&helper, 2));
+
+        let mut vm = vm_for(INSTRUCTIONS, "test_inline_loop_dbg");
+        vm.inline_threshold = 3;
+        assert_eq!(run_vm(&mut vm), vec![Some(0x39a5)]);
+    }
+
+    #[test]
+    fn test_log_block_visits() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_inline_loop").expect("Failed to create VM");
+        vm.log_block_visits = true;
+
+        let (_, state) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(
+            state.repeated_blocks(),
+            vec![BlockVisits {
+                function: "test_inline_loop".to_owned(),
+                block: "%loop".to_owned(),
+                count: 10,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_byval() {
+        let res = run("test_byval");
+        assert_eq!(res, vec![Some(0x21)]);
+
+        // As the entry function the parameter points to a symbolic copy of the structure.
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_byval_callee").expect("Failed to create VM");
+
+        let (result, state) = vm.run().expect("Failed to run path").expect("No path");
+        assert!(matches!(result, PathResult::Success(Some(_))));
+        assert_eq!(state.marked_symbolic.len(), 1);
+        assert_eq!(state.marked_symbolic[0].value.len(), 64);
+    }
+
+    #[test]
+    fn test_max_iter_count() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_infinite_loop").expect("Failed to create VM");
+        vm.max_iter_count = Some(50);
+
+        let mut results = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            results.push(result);
+        }
+        assert_eq!(results.len(), 2);
+        assert!(results.contains(&PathResult::Failure(
+            AnalysisError::IterationCountExceeded { max: 50 }
+        )));
+    }
+
+    #[test]
+    fn test_max_call_depth() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_unbounded_recursion").expect("Failed to create VM");
+        vm.max_call_depth = 5;
+
+        // The recursion ends within the limit for `n` from 0 to 4.
+        let mut returned = 0;
+        let mut failures = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            match result {
+                PathResult::Success(_) => returned += 1,
+                PathResult::Failure(error) => failures.push(error),
+                result => panic!("Unexpected result {result:?}"),
+            }
+        }
+        assert_eq!(returned, 5);
+        assert_eq!(failures, vec![AnalysisError::CallDepthExceeded { max: 5 }]);
+    }
+
+    #[test]
+    fn test_unknown_function_policy() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_unknown_function").expect("Failed to create VM");
+        assert_eq!(
+            vm.run().err(),
+            Some(LLVMExecutorError::FunctionNotFound(
+                "external_read".to_owned()
+            ))
+        );
+
+        let mut vm =
+            VM::new(project, context, "test_unknown_function").expect("Failed to create VM");
+        vm.unknown_function_policy = UnknownFunctionPolicy::ReturnSymbolic;
+        assert_eq!(run_vm(&mut vm), vec![Some(1)]);
+
+        let mut vm =
+            VM::new(project, context, "test_unknown_function").expect("Failed to create VM");
+        vm.unknown_function_policy = UnknownFunctionPolicy::HavocArgsAndReturnSymbolic;
+        let mut results = run_vm(&mut vm);
+        results.sort();
+        assert_eq!(results, vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_symbolic_alloca() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_symbolic_alloca");
+
+        let mut results = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            results.push(result);
+        }
+
+        // Only an allocation of all 4 elements fits the write, smaller ones are out of bounds.
+        assert_eq!(results.len(), 3);
+        assert!(results.contains(&PathResult::Failure(AnalysisError::OutOfBounds)));
+        for expected in [0, 9] {
+            assert!(results.iter().any(|result| match result {
+                PathResult::Success(Some(value)) => value.get_constant() == Some(expected),
+                _ => false,
+            }));
+        }
+    }
+
+    #[test]
+    fn test_symbolic_alloca_overflow() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_symbolic_alloca_overflow");
+
+        // The wrapped size would be small enough to allocate, but the real size never fits. Only
+        // the path fails, the analysis continues.
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(
+            result,
+            PathResult::Failure(AnalysisError::AllocationTooLarge { max: 4096 })
+        );
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_add_hook() {
+        let path = "tests/unit_tests/instructions.bc";
+        let mut project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        project.add_hook("external_read", |vm, _| {
+            Ok(PathResult::Success(Some(vm.state.ctx.from_u64(0, 32))))
+        });
+        let project = Box::leak(project);
+
+        // The hook replaces the declared function, so the call no longer fails.
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_unknown_function").expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm), vec![Some(1)]);
+    }
+
+    #[test]
+    fn test_use_after_free() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_use_after_free").expect("Failed to create VM");
+
+        let mut results = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            results.push(result);
+        }
+        assert_eq!(results.len(), 2);
+        assert!(results.contains(&PathResult::Failure(AnalysisError::UseAfterFree)));
+        assert!(results.iter().any(|result| match result {
+            PathResult::Success(Some(value)) => value.get_constant() == Some(7),
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_realloc_after_free() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_realloc_after_free");
+
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(result, PathResult::Failure(AnalysisError::UseAfterFree));
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_memcpy_after_free() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_memcpy_after_free");
+
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(result, PathResult::Failure(AnalysisError::UseAfterFree));
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
+
+    #[test]
+    fn test_assert() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_assert");
+
+        let mut results = Vec::new();
+        while let Some((result, state)) = vm.run().expect("Failed to run path") {
+            let x = &state.marked_symbolic[0].value;
+            let x = state.constraints.get_value(x).unwrap();
+            results.push((result, x.get_constant().unwrap() as i32));
+        }
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|(result, x)| match result {
+            PathResult::Failure(AnalysisError::AssertionFailed) => *x <= 0,
+            _ => false,
+        }));
+        assert!(results.iter().any(|(result, x)| match result {
+            PathResult::Success(Some(_)) => *x > 0,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_detect_non_termination() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_infinite_loop").expect("Failed to create VM");
+        vm.detect_non_termination = true;
+
+        let mut results = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            results.push(result);
+        }
+        let non_termination = AnalysisError::NonTermination {
+            function: "test_infinite_loop".to_owned(),
+            block: "%loop".to_owned(),
+        };
+        assert_eq!(results.len(), 2);
+        assert!(results.contains(&PathResult::Failure(non_termination)));
+
+        // Loops that change the state on each iteration are not reported.
+        let mut vm = VM::new(project, context, "test_inline_loop").expect("Failed to create VM");
+        vm.detect_non_termination = true;
+        assert_eq!(run_vm(&mut vm), vec![Some(0x39a5)]);
+    }
+
+    #[test]
+    fn test_dump_smtlib() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_reachable");
+
+        let mut scripts = Vec::new();
+        while let Some((_, state)) = vm.run().expect("Failed to run path") {
+            // The branch constraints are asserted in the scope of the path, and are included.
+            let script = state.dump_smtlib();
+            let assertions = state.constraints.assertions();
+            assert!(!assertions.is_empty());
+            assert_eq!(script.matches("(assert ").count(), assertions.len());
+            assert!(script.contains("(declare-fun "));
+            scripts.push(script);
+        }
+
+        // Each path has its own branch constraints, from the scope of the resumed path.
+        assert_eq!(scripts.len(), 2);
+        assert_ne!(scripts[0], scripts[1]);
+    }
+
+    #[test]
+    fn test_fmt_hooks() {
+        let res = run("test_fmt_write");
+        assert_eq!(res, vec![Some(0)]);
+
+        let path = "tests/unit_tests/instructions.bc";
+        let mut project = Project::from_path(path).expect("Failed to created project");
+        project.set_fmt_hooks(false);
+        assert!(project
+            .get_hook("_ZN4core3fmt5write17h0123456789abcdefE")
+            .is_none());
+    }
+
+    #[test]
+    fn test_call_through_alias() {
+        let res = run("test_call_through_alias");
+        assert_eq!(res, vec![Some(0x1579a)]);
+
+        // Aliases can also be used as the entry function.
+        let res = run("test_call_alias");
+        assert_eq!(res, vec![Some(0xabcd)]);
+    }
+
+    #[test]
+    fn test_solve_return_typed() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_select_struct").expect("Failed to create VM");
+
+        let (result, state) = vm.run().unwrap().expect("expected a path");
+        let solutions = vm.solve_return(&state, &result, 2).unwrap();
+        let solutions = solutions.expect("expected a return value");
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(
+            solutions[0].ty,
+            ExpressionType::Struct(vec![
+                ExpressionType::Integer(32),
+                ExpressionType::Integer(32)
+            ])
+        );
+        assert_eq!(solutions[0].value.get_constant(), Some(0x0000000400000003));
+    }
+
+    #[test]
+    fn test_solve_return_named_struct() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_return_named_struct").expect("Failed to create VM");
+
+        let (result, state) = vm.run().unwrap().expect("expected a path");
+        let solutions = vm.solve_return(&state, &result, 1).unwrap();
+        let output = &solutions.expect("expected a return value")[0];
+        assert_eq!(
+            output.ty,
+            ExpressionType::NamedStruct(
+                "Point".to_owned(),
+                vec![
+                    (Some("x".to_owned()), ExpressionType::Integer(32)),
+                    (Some("y".to_owned()), ExpressionType::Integer(32)),
+                ]
+            )
+        );
+        assert_eq!(
+            format!("{output}"),
+            "Point {\n    x: 0x00000001 (32-bits)\n    y: 0x00000002 (32-bits)\n}"
+        );
+    }
+
+    #[test]
+    fn test_solve_return_padded_struct() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_return_padded_struct").expect("Failed to create VM");
+
+        let (result, state) = vm.run().unwrap().expect("expected a path");
+        let solutions = vm.solve_return(&state, &result, 1).unwrap();
+        let output = &solutions.expect("expected a return value")[0];
+        assert_eq!(
+            output.ty,
+            ExpressionType::NamedStruct(
+                "Padded".to_owned(),
+                vec![
+                    (
+                        Some("a".to_owned()),
+                        ExpressionType::Padded(Box::new(ExpressionType::Integer(8)), 24)
+                    ),
+                    (Some("b".to_owned()), ExpressionType::Integer(32)),
+                ]
+            )
+        );
+        assert_eq!(output.ty.size_in_bits(), Some(64));
+    }
+
+    #[test]
+    fn test_solve_global() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_write_global").expect("Failed to create VM");
+
+        let (_, mut state) = vm.run().unwrap().expect("expected a path");
+        let counter = vm.solve_global(&mut state, "counter", 2).unwrap();
+        let counter = counter.expect("expected the global to exist");
+        assert_eq!(counter.len(), 1);
+        assert_eq!(counter[0].ty, ExpressionType::Integer(32));
+        assert_eq!(counter[0].as_u64(), Some(42));
+
+        let missing = vm.solve_global(&mut state, "missing", 1).unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_read_cstring() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_write_cstring").expect("Failed to create VM");
+
+        let (result, mut state) = vm.run().unwrap().expect("expected a path");
+        let PathResult::Success(Some(message)) = result else {
+            panic!("expected the address of the message");
+        };
+        let message = vm.read_cstring(&state, &message).unwrap();
+        assert_eq!(message.as_deref(), Some("hello"));
+
+        // The first byte can be anything, so the string is not known.
+        let global = project.find_global("symbolic_message").unwrap();
+        let address = state
+            .get_expr(&Value::Global(Global::Variable(global)))
+            .unwrap();
+        assert_eq!(vm.read_cstring(&state, &address).unwrap(), None);
+    }
+
+    #[test]
+    fn test_opaque_struct_size() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_opaque_global").expect("Failed to create VM");
+
+        // The size of the foreign struct is unknown, so it cannot be read.
+        let (_, mut state) = vm.run().unwrap().expect("expected a path");
+        let result = vm.solve_global(&mut state, "ffi_state", 1);
+        assert_eq!(
+            result,
+            Err(LLVMExecutorError::NoSize("struct.FfiState".to_owned()))
+        );
+
+        // Initializing a global of the foreign struct only fails the path that uses it.
+        let mut vm = vm_for(INSTRUCTIONS, "test_opaque_global_init");
+        let (result, _) = vm.run().unwrap().expect("expected a path");
+        assert!(matches!(
+            result,
+            PathResult::Failure(AnalysisError::UnsizedType(ty)) if ty == "struct.FfiState"
+        ));
+    }
+
+    #[test]
+    fn test_solve_return_pointer() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_return_pointer").expect("Failed to create VM");
+
+        let (result, state) = vm.run().unwrap().expect("expected a path");
+        let solutions = vm.solve_return(&state, &result, 1).unwrap();
+        let output = &solutions.expect("expected a return value")[0];
+        assert_eq!(
+            output.ty,
+            ExpressionType::Pointer(64, Box::new(ExpressionType::Unknown))
+        );
+
+        // The pointee type is opaque, so the global is shown as bytes.
+        let pointee = output.pointee.as_ref().expect("expected a pointee");
+        assert_eq!(pointee.value.get_constant(), Some(0x1234));
+        assert!(format!("{output}")
+            .ends_with(" -> [0x34 (8-bits), 0x12 (8-bits), 0x00 (8-bits), 0x00 (8-bits)]"));
+    }
+
+    #[test]
+    fn test_invoke() {
+        let res = run("test_invoke");
+        assert_eq!(res, vec![Some(0xabcd)]);
+    }
+
+    #[test]
+    fn test_continue_after_panic() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm = VM::new(project, context, "test_resume").expect("Failed to create VM");
+        vm.continue_after_panic = true;
+
+        // First the panic is reported, then the path continues through both landing pads.
+        let (result, _) = vm.run().unwrap().expect("expected a panicking path");
+        assert_eq!(result, PathResult::Failure(AnalysisError::Panic));
+
+        let (result, state) = vm.run().unwrap().expect("expected a continued path");
+        let PathResult::Success(Some(value)) = result else {
+            panic!("expected a return value, got {result:?}");
+        };
+        assert_eq!(
+            state.constraints.get_value(&value).unwrap().get_constant(),
+            Some(3)
+        );
+        assert!(vm.run().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_continue_after_index_out_of_bounds() {
+        let mut vm = vm_for(INSTRUCTIONS, "test_resume_bounds_check");
+        vm.continue_after_panic = true;
+
+        let (result, _) = vm.run().unwrap().expect("expected a panicking path");
+        assert_eq!(
+            result,
+            PathResult::Failure(AnalysisError::IndexOutOfBounds { index: 5, len: 3 })
+        );
+
+        let (result, state) = vm.run().unwrap().expect("expected a continued path");
+        let PathResult::Success(Some(value)) = result else {
+            panic!("expected a return value, got {result:?}");
+        };
+        assert_eq!(
+            state.constraints.get_value(&value).unwrap().get_constant(),
+            Some(3)
+        );
+        assert!(vm.run().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_store_symbolic_pointer() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
 
-        Type::Array(t) => {
-            let element_type = t.element_type();
-            let element_size = byte_size(&element_type, ptr_size)?;
-            let element_size = ctx.from_u64(element_size.into(), index.len());
-            Ok((element_size.mul(index), element_type))
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_store_symbolic_pointer").expect("Failed to create VM");
+
+        // One path for each buffer and one for writing past the end of the last one.
+        let mut stores = 0;
+        let mut failures = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            match result {
+                PathResult::Success(_) => stores += 1,
+                PathResult::Failure(error) => failures.push(error),
+                result => panic!("Unexpected result {result:?}"),
+            }
         }
+        assert_eq!(stores, 3);
+        assert_eq!(failures, vec![AnalysisError::OutOfBounds]);
+    }
 
-        // For structs we have to collect the size of all the members until the index. Not supported
-        // for non-constant indexes.
-        //
-        // With a symbol as an index we cannot index into structs, not without having to try
-        // solutions and fork the state. So these are not supported.
-        Type::Structure(t) => {
-            let index_len = index.len();
-            let Some(index) = index.get_constant() else {
-                panic!("Cannot index into struct with non-constant index");
-            };
+    #[test]
+    fn test_priority_path_selection() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
 
-            let fields = t.fields();
-            let offset = fields
-                .iter()
-                .take(index as usize)
-                .map(|f| byte_size(f, ptr_size))
-                .sum::<Result<u32>>()?;
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_store_symbolic_pointer").expect("Failed to create VM");
+
+        // Prefer the paths with the fewest constraints, so paths are not resumed in the reverse
+        // order they were saved in.
+        vm.set_path_selection(Box::new(crate::vm::PriorityPathSelection::new(|state| {
+            -(state.constraints.num_assertions() as i64)
+        })));
+
+        let mut stores = 0;
+        let mut failures = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            match result {
+                PathResult::Success(_) => stores += 1,
+                PathResult::Failure(error) => failures.push(error),
+                result => panic!("Unexpected result {result:?}"),
+            }
+        }
+        assert_eq!(stores, 3);
+        assert_eq!(failures, vec![AnalysisError::OutOfBounds]);
+    }
 
-            let inner_ty = fields
-                .get(index as usize)
-                .cloned()
-                .ok_or(LLVMExecutorError::MalformedInstruction)?;
+    #[test]
+    fn test_check_alignment() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
 
-            let offset = ctx.from_u64(offset.into(), index_len);
-            Ok((offset, inner_ty))
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_misaligned_load").expect("Failed to create VM");
+        vm.check_alignment = true;
+
+        // The aligned offset loads the stored value, the other offsets are a single failed path.
+        let mut results = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            results.push(result);
         }
-        Type::OpaqueStructure => Err(LLVMExecutorError::NoSize),
+        assert_eq!(results.len(), 2);
+        assert!(
+            results.contains(&PathResult::Failure(AnalysisError::MisalignedAccess {
+                align: 4
+            }))
+        );
 
-        // We cannot index into these types.
-        Type::Void => todo!(),
-        Type::Integer(_) => todo!(),
-        Type::Float(_) => todo!(),
-        Type::Function(_) => todo!(),
-        Type::Label => todo!(),
-        Type::Token => todo!(),
-        Type::Metadata => todo!(),
-        Type::X86Amx => todo!(),
-        Type::X86Mmx => todo!(),
-        Type::TargetExtension(_) => todo!(),
+        // Without the check each offset is a valid load.
+        let mut vm =
+            VM::new(project, context, "test_misaligned_load").expect("Failed to create VM");
+        assert_eq!(run_vm(&mut vm).len(), 4);
     }
-}
 
-/// Converts integers, pointers, or vectors.
-///
-/// Performs a conversion on either (int,int), (ptr,int), (int,ptr), or (vector,vector) with the
-/// passed mapping function.
-///
-/// No type checking is done, if this is of interest they have to be checked before calling this
-/// function.
-pub(crate) fn convert_to_map<F>(
-    state: &mut LLVMState,
-    value: Value,
-    to_type: &Type,
-    map: F,
-) -> Result<DExpr>
-where
-    F: Fn(DExpr, u32) -> DExpr,
-{
-    let value_type = value.ty();
-    let value = state.get_expr(&value)?;
+    #[test]
+    fn test_ptrtoint_roundtrip() {
+        let res = run("test_ptrtoint_roundtrip");
+        assert_eq!(res, vec![Some(42)]);
+    }
 
-    match (value_type, to_type) {
-        // Integer to integer conversion are done by trunc, zext, and sext. While the ptr->int,
-        // int->ptr are done by ptrtoint and inttoptr. All these should be supported.
-        (Type::Integer(_), Type::Integer(_))
-        | (Type::Integer(_), Type::Pointer(_))
-        | (Type::Pointer(_), Type::Integer(_)) => {
-            let target_bits = bit_size(to_type, state.project.ptr_size)?;
-            Ok(map(value, target_bits))
-        }
+    #[test]
+    fn test_inttoptr_dangling() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
 
-        // Vectors are a bit more annoying, in that the elements have to be processed one by one.
-        (Type::Vector(source), Type::Vector(target))
-            if !(source.is_scalable() || target.is_scalable()) =>
-        {
-            let source_bits = bit_size(&source.element_type(), state.project.ptr_size)?;
-            let target_bits = bit_size(&target.element_type(), state.project.ptr_size)?;
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_inttoptr_dangling").expect("Failed to create VM");
 
-            let num_elements = source.num_elements();
-            assert!(source_bits * num_elements == value.len());
+        let (result, _) = vm.run().expect("Failed to run path").expect("No path");
+        assert_eq!(result, PathResult::Failure(AnalysisError::OutOfBounds));
+        assert!(vm.run().expect("Failed to run path").is_none());
+    }
 
-            // Process each element one by one and concatenate the result.
-            (0..num_elements)
-                .map(|i| {
-                    let low = i * source_bits;
-                    let high = (i + 1) * source_bits - 1;
-                    let symbol = value.slice(low, high);
-                    map(symbol, target_bits)
-                })
-                .reduce(|acc, v| v.concat(&acc))
-                .ok_or(LLVMExecutorError::MalformedInstruction)
-        }
+    #[test]
+    fn test_max_memory_access_resolutions() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
 
-        // The other types should not appear for this instruction.
-        _ => Err(LLVMExecutorError::MalformedInstruction),
-    }
-}
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_address_resolutions").expect("Failed to create VM");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Within the limit there is one path for each possible address, and one for skipping.
+        assert_eq!(run_vm(&mut vm).len(), 17);
 
-    fn run(fn_name: &str) -> Vec<Option<i64>> {
-        // let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        //     .with_max_level(tracing::Level::TRACE)
-        //     .finish();
-        // tracing::subscriber::set_global_default(subscriber)
-        //     .expect("setting default subscriber failed");
+        vm.reset_to("test_address_resolutions").unwrap();
+        vm.max_memory_access_resolutions = 4;
+        let mut successes = 0;
+        let mut failures = Vec::new();
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            match result {
+                PathResult::Success(_) => successes += 1,
+                PathResult::Failure(error) => failures.push(error),
+                result => panic!("Unexpected result {result:?}"),
+            }
+        }
+        assert_eq!(successes, 1);
+        assert_eq!(
+            failures,
+            vec![AnalysisError::TooManyAddressResolutions { max: 4 }]
+        );
+    }
 
-        let path = format!("tests/unit_tests/instructions.bc");
-        let project = Box::new(Project::from_path(&path).expect("Failed to created project"));
+    #[test]
+    fn test_gep_overflow() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
         let project = Box::leak(project);
 
         let context = Box::new(DContext::new());
         let context = Box::leak(context);
-        let mut vm = VM::new(project, context, fn_name).expect("Failed to create VM");
+        let mut vm = VM::new(project, context, "test_gep_overflow").expect("Failed to create VM");
 
-        let mut path_results = Vec::new();
-        while let Some((path_result, state)) = vm.run().expect("Failed to run path") {
-            let result = match path_result {
-                PathResult::Success(Some(value)) => {
-                    let value = state
-                        .constraints
-                        .get_value(&value)
-                        .expect("Failed to get concrete value");
-                    let binary_str = value.to_binary_string();
-                    Some(u128::from_str_radix(&binary_str, 2).unwrap() as i64)
-                }
-                PathResult::Success(None) => None,
-                PathResult::Failure(error) => {
-                    panic!("Did not expect any paths to fail, reason: {error:?}")
+        let mut successes = 0;
+        let mut overflow_index = None;
+        while let Some((result, _)) = vm.run().expect("Failed to run path") {
+            match result {
+                PathResult::Success(_) => successes += 1,
+                PathResult::Failure(AnalysisError::PointerOverflow { index }) => {
+                    overflow_index = Some(index)
                 }
-                PathResult::Suppress => panic!("Did not expect any paths to be suppressed"),
-                PathResult::AssumptionUnsat => panic!("Did not expect any paths to be unsat"),
-            };
-            path_results.push(result);
+                result => panic!("Unexpected result {result:?}"),
+            }
         }
+        assert_eq!(successes, 1);
 
-        eprintln!("{path_results:x?}");
-        path_results
+        // A positive index can only overflow in the multiplication by the element size, a negative
+        // one can also wrap the address below zero.
+        let index = overflow_index.expect("Expected an overflow") as i64;
+        assert!(index.checked_mul(8).is_none() || index < 0);
+    }
+
+    #[test]
+    fn test_find_reachable_errors() {
+        let path = "tests/unit_tests/instructions.bc";
+        let project = Box::new(Project::from_path(path).expect("Failed to created project"));
+        let project = Box::leak(project);
+
+        let context = Box::new(DContext::new());
+        let context = Box::leak(context);
+        let mut vm =
+            VM::new(project, context, "test_reachable_errors").expect("Failed to create VM");
+
+        let errors = vm.find_reachable_errors().unwrap();
+        let results: Vec<_> = errors.into_iter().map(|(result, _)| result).collect();
+        assert_eq!(
+            results,
+            vec![
+                PathResult::Failure(AnalysisError::Panic),
+                PathResult::Failure(AnalysisError::Panic)
+            ]
+        );
+        assert!(vm.run().unwrap().is_none());
     }
 
     #[test]
@@ -1612,6 +4076,13 @@ mod tests {
         assert_eq!(res[0], Some(0x56));
     }
 
+    #[test]
+    fn test_atomic_swap() {
+        let res = run("test_atomic_swap");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0100));
+    }
+
     #[test]
     fn test_gep1() {
         let res = run("test_gep1");
@@ -1668,6 +4139,61 @@ mod tests {
         assert_eq!(res[0], Some(2));
     }
 
+    #[test]
+    fn test_null_global() {
+        let res = run("test_null_global");
+        assert_eq!(res, vec![Some(1)]);
+    }
+
+    #[test]
+    fn test_global_pointer_table() {
+        let res = run("test_global_pointer_table");
+        assert_eq!(res, vec![Some(50)]);
+    }
+
+    #[test]
+    fn test_global_constant_expression() {
+        let res = run("test_global_constant_expression");
+        assert_eq!(res, vec![Some(0)]);
+    }
+
+    #[test]
+    fn test_static_array() {
+        let res = run("test_static_array");
+        assert_eq!(res, vec![Some(3)]);
+    }
+
+    #[test]
+    fn test_static_struct() {
+        let res = run("test_static_struct");
+        assert_eq!(res, vec![Some(12)]);
+    }
+
+    #[test]
+    fn test_large_zeroed_global() {
+        let res = run("test_large_zeroed_global");
+        assert_eq!(res, vec![Some(0)]);
+    }
+
+    #[test]
+    fn test_undef_global() {
+        let mut res = run("test_undef_global");
+        res.sort();
+        assert_eq!(res, vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_zeroed_global() {
+        let res = run("test_zeroed_global");
+        assert_eq!(res, vec![Some(7)]);
+    }
+
+    #[test]
+    fn test_store_aggregate_zero() {
+        let res = run("test_store_aggregate_zero");
+        assert_eq!(res, vec![Some(0)]);
+    }
+
     #[test]
     fn test_bitcast1() {
         let res = run("test_bitcast1");
@@ -1703,6 +4229,24 @@ mod tests {
         assert_eq!(res[0], Some(0x000F00FF));
     }
 
+    #[test]
+    fn test_zext_vec4() {
+        let res = run_wide("test_zext_vec4");
+        assert_eq!(res, 0x0000007F_000000FF_00000080_00000001);
+    }
+
+    #[test]
+    fn test_sext_vec4() {
+        let res = run_wide("test_sext_vec4");
+        assert_eq!(res, 0x0000007F_FFFFFFFF_FFFFFF80_00000001);
+    }
+
+    #[test]
+    fn test_trunc_vec4() {
+        let res = run("test_trunc_vec4");
+        assert_eq!(res, vec![Some(0x0F00CD34)]);
+    }
+
     #[test]
     fn test_sext() {
         let res = run("test_sext");
@@ -1759,6 +4303,41 @@ mod tests {
         assert_eq!(res[0], Some(0x1111222233334444));
     }
 
+    #[test]
+    fn test_sitofp_fptosi() {
+        let res = run("test_sitofp_fptosi");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0xffffffd6));
+    }
+
+    #[test]
+    fn test_uitofp() {
+        let res = run("test_uitofp");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x4008000000000000));
+    }
+
+    #[test]
+    fn test_uitofp_round() {
+        let res = run("test_uitofp_round");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x4b800000));
+    }
+
+    #[test]
+    fn test_fptoui() {
+        let res = run("test_fptoui");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(7));
+    }
+
+    #[test]
+    fn test_fptosi_negative() {
+        let res = run("test_fptosi_negative");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0xfe));
+    }
+
     #[test]
     fn test_icmp_eq_false() {
         let res = run("test_icmp_eq_false");
@@ -1906,6 +4485,41 @@ mod tests {
         assert_eq!(res[0], Some(0x5));
     }
 
+    #[test]
+    fn test_fcmp_oeq_signed_zero() {
+        let res = run("test_fcmp_oeq_signed_zero");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x1));
+    }
+
+    #[test]
+    fn test_fcmp_olt_nan() {
+        let res = run("test_fcmp_olt_nan");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0));
+    }
+
+    #[test]
+    fn test_fcmp_ult_nan() {
+        let res = run("test_fcmp_ult_nan");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x1));
+    }
+
+    #[test]
+    fn test_fcmp_ogt_vec() {
+        let res = run("test_fcmp_ogt_vec");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x1));
+    }
+
+    #[test]
+    fn test_fcmp_assume() {
+        // Only the path where the assumption holds is explored.
+        let res = run("test_fcmp_assume");
+        assert_eq!(res, vec![Some(1)]);
+    }
+
     #[test]
     fn test_phi1() {
         let res = run("test_phi1");
@@ -1920,6 +4534,13 @@ mod tests {
         assert_eq!(res[0], Some(0xcd));
     }
 
+    #[test]
+    fn test_phi_struct() {
+        let res = run("test_phi_struct");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0xab000000cd));
+    }
+
     #[test]
     fn test_select1() {
         let res = run("test_select1");
@@ -1948,6 +4569,20 @@ mod tests {
         assert_eq!(res[0], Some(0x000000dd000000aa));
     }
 
+    #[test]
+    fn test_select_struct() {
+        let res = run("test_select_struct");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0000000400000003));
+    }
+
+    #[test]
+    fn test_select_array() {
+        let res = run("test_select_array");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x00020001));
+    }
+
     #[test]
     fn test_call() {
         let res = run("test_call");
@@ -10,7 +10,8 @@ use tracing::{debug, trace};
 // This would require a general purpose project as well though.
 
 use crate::{
-    memory::BITS_IN_BYTE,
+    memory::{MemoryError, UninitializedMemory, BITS_IN_BYTE},
+    smt::{DContext, DExpr, Solutions},
     util::{ExpressionType, Variable},
     vm::{executor::LLVMExecutor, AnalysisError, LLVMExecutorError},
 };
@@ -18,8 +19,25 @@ use crate::{
 use super::PathResult;
 
 /// Hook type
+///
+/// Hooks should access memory through [`LLVMExecutor::read_memory`] and
+/// [`LLVMExecutor::write_memory`], which resolve symbolic addresses.
 pub type Hook = fn(&mut LLVMExecutor<'_>, &[Value]) -> Result<PathResult, LLVMExecutorError>;
 
+/// Formatting functions that are stubbed by [`Hooks::add_fmt_hooks`], with the hook for each.
+///
+/// Formatting rarely matters for the property being checked, but symbolically executing it
+/// explores a large number of paths.
+const FMT_HOOKS: [(&str, Hook); 7] = [
+    ("core::fmt::write", fmt_ok),
+    ("core::fmt::Formatter::write_fmt", fmt_ok),
+    ("core::fmt::Formatter::write_str", fmt_ok),
+    ("core::fmt::Formatter::pad", fmt_ok),
+    ("core::fmt::Formatter::pad_integral", fmt_ok),
+    ("std::io::stdio::_print", ignore_call),
+    ("std::io::stdio::_eprint", ignore_call),
+];
+
 pub struct Hooks {
     hooks: HashMap<String, Hook>,
 }
@@ -37,11 +55,16 @@ impl Hooks {
         };
 
         hooks.add("symex_lib::assume", assume);
+        hooks.add("symex_lib::assert", assert);
         hooks.add("symex_lib::symbolic", symbolic);
+        hooks.add("symex_lib::symbolic_named", symbolic_named);
+        hooks.add("symex_lib::concretize_bytes", concretize);
         hooks.add("symex_lib::ignore_path", ignore);
+        hooks.add("symex_lib::reachable", reachable);
 
         // These are not mangled, so these can be called from e.g. C.
         hooks.add("symex_assume", assume);
+        hooks.add("symex_assert", assert);
         hooks.add("symex_symbolic", symbolic_no_type);
 
         hooks.add("__rust_alloc", rust_alloc);
@@ -49,17 +72,42 @@ impl Hooks {
         hooks.add("__rust_realloc", rust_realloc);
         hooks.add("__rust_alloc_zeroed", rust_alloc_zeroed);
         hooks.add("std::process::exit", exit);
-        hooks.add("core::panicking::panic_bounds_check", abort);
+        hooks.add("core::panicking::panic_bounds_check", index_out_of_bounds);
+        hooks.add(
+            "core::slice::index::slice_start_index_len_fail",
+            index_out_of_bounds,
+        );
+        hooks.add(
+            "core::slice::index::slice_end_index_len_fail",
+            index_out_of_bounds,
+        );
+        hooks.add("core::slice::index::slice_index_fail", slice_index_fail);
         hooks.add("core::panicking::panic", abort);
         hooks.add("core::panicking::panic_fmt", abort);
 
+        hooks.add_fmt_hooks();
+
         hooks
     }
 
-    fn add(&mut self, name: impl Into<String>, hook: Hook) {
+    pub(crate) fn add(&mut self, name: impl Into<String>, hook: Hook) {
         self.hooks.insert(name.into(), hook);
     }
 
+    /// Stub the `core::fmt` entry points so formatting succeeds without being executed.
+    pub fn add_fmt_hooks(&mut self) {
+        for (name, hook) in FMT_HOOKS {
+            self.add(name, hook);
+        }
+    }
+
+    /// Remove the formatting stubs, so formatting code is analyzed as any other code.
+    pub fn remove_fmt_hooks(&mut self) {
+        for (name, _) in FMT_HOOKS {
+            self.hooks.remove(name);
+        }
+    }
+
     pub fn get(&self, name: &str) -> Option<Hook> {
         self.hooks.get(name).copied()
     }
@@ -96,6 +144,49 @@ pub fn assume(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult, L
     }
 }
 
+/// Check that the condition holds, the path fails if it can be false.
+///
+/// If the condition can be both true and false the path is forked, so the path where it holds
+/// continues.
+pub fn assert(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult, LLVMExecutorError> {
+    trace!("assert info: {:?}", args);
+
+    let condition = vm.state.get_expr(&args[0])?;
+    let condition = match condition.len() {
+        // Boolean condition.
+        1 => condition,
+        // Otherwise, check for non zero.
+        _ => condition._ne(&vm.state.ctx.zero(condition.len())),
+    };
+
+    let can_fail = vm
+        .state
+        .constraints
+        .is_sat_with_constraint(&condition.not())?;
+    if !can_fail {
+        return Ok(PathResult::Success(None));
+    }
+
+    if vm.state.constraints.is_sat_with_constraint(&condition)? {
+        vm.fork(condition.clone())?;
+    }
+    vm.state.constraints.assert(&condition.not());
+    Ok(PathResult::Failure(AnalysisError::AssertionFailed))
+}
+
+/// Record that the `reachable` marker at the current call was reached.
+pub fn reachable(
+    vm: &mut LLVMExecutor<'_>,
+    _args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    let call = vm.state.current_frame()?.current_instruction().cloned();
+    if let Some(call) = call {
+        trace!("Hook: reached marker {call}");
+        vm.vm.reached_markers.insert(call);
+    }
+    Ok(PathResult::Success(None))
+}
+
 pub fn symbolic_no_type(
     vm: &mut LLVMExecutor<'_>,
     args: &[Value],
@@ -115,18 +206,19 @@ pub fn symbolic_no_type(
         None => panic!("Size for symbolic requires constant size"),
     };
 
-    let name = get_operand_name(addr);
+    let name = get_operand_name(vm.state.ctx, addr);
     let new_value = vm.state.ctx.unconstrained(concrete_size_in_bits, &name);
 
     let addr = vm.state.get_expr(addr)?;
-    vm.state.memory.write(&addr, new_value.clone())?;
+    vm.write_memory(&addr, new_value.clone())?;
 
     let var = Variable {
         name: None,
         value: new_value,
         ty: ExpressionType::Unknown,
+        pointee: None,
     };
-    vm.state.marked_symbolic.push(var);
+    vm.mark_symbolic(var);
 
     Ok(PathResult::Success(None))
 }
@@ -135,47 +227,113 @@ pub fn symbolic(
     vm: &mut LLVMExecutor<'_>,
     args: &[Value],
 ) -> Result<PathResult, LLVMExecutorError> {
-    let addr = &args[0];
+    make_symbolic(vm, &args[0], None)
+}
 
-    if addr.ty().is_pointer() {
-        // TODO: We need the size of the pointed to value, which we cannot easily get with
-        // opaque pointers.
-        let addr_expr = vm.state.get_expr(addr)?;
-        let size = {
-            // HACK:
-            // Read the pointed to object from memory and get the size from there, not entirely
-            // sure this works for all cases... Since, I think we may sometimes only want
-            // part of the memory object to be reset to entirely symbolic.
-            let addr = addr_expr.get_constant().expect("expected constant addr");
-            let obj = vm
-                .state
-                .memory
-                .get_object(addr)
-                .expect("coult not find object");
-            obj.bit_size()
-        };
+/// Same as [`symbolic`], but the variable is reported with the name passed as a `&str`.
+pub fn symbolic_named(
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    // The `&str` is passed as a pointer and a length.
+    let name = read_str(vm, &args[1], &args[2])?;
+    make_symbolic(vm, &args[0], Some(name))
+}
 
-        // let size = vm.project.bit_size(inner_ty.as_ref())?;
-        let name = get_operand_name(addr);
-        let new_value = vm.state.ctx.unconstrained(size as u32, &name);
+/// Replace the value pointed to by `addr` with an unconstrained value.
+///
+/// The variable is reported as `name` if passed, otherwise a unique name is generated.
+fn make_symbolic(
+    vm: &mut LLVMExecutor<'_>,
+    addr: &Value,
+    name: Option<String>,
+) -> Result<PathResult, LLVMExecutorError> {
+    if !addr.ty().is_pointer() {
+        return Err(LLVMExecutorError::MalformedInstruction);
+    }
 
-        let var = Variable {
-            name: Some(name),
-            value: new_value.clone(),
-            // ty: type_to_expr_type(inner_ty.as_ref(), vm.project),
-            ty: ExpressionType::Unknown,
-        };
-        vm.state.marked_symbolic.push(var);
+    let addr_expr = vm.state.get_expr(addr)?;
+    let size = pointee_bit_size(vm, &addr_expr)?;
 
-        vm.state.memory.write(&addr_expr, new_value)?;
+    // let size = vm.project.bit_size(inner_ty.as_ref())?;
+    // The solver requires unique symbols, even if the same name is used more than once.
+    let symbol = match &name {
+        Some(name) => vm.state.ctx.fresh_name(name),
+        None => get_operand_name(vm.state.ctx, addr),
+    };
+    let new_value = vm.state.ctx.unconstrained(size as u32, &symbol);
 
-        Ok(PathResult::Success(None))
-    } else {
-        panic!("not a pointer type");
+    let var = Variable {
+        name: Some(name.unwrap_or(symbol)),
+        value: new_value.clone(),
+        // ty: type_to_expr_type(inner_ty.as_ref(), vm.project),
+        ty: ExpressionType::Unknown,
+        pointee: None,
+    };
+    vm.mark_symbolic(var);
+
+    vm.write_memory(&addr_expr, new_value)?;
+
+    Ok(PathResult::Success(None))
+}
+
+/// Replace the value pointed to by the argument with one of its possible values.
+///
+/// The size of the value in bytes is passed as the second argument. The rest of the path only
+/// sees that value, the other values it could have are not explored.
+pub fn concretize(
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    let (addr, size) = match args {
+        [addr, size] if addr.ty().is_pointer() => (addr, size),
+        _ => return Err(LLVMExecutorError::MalformedInstruction),
+    };
+
+    let addr = vm.state.get_expr(addr)?;
+    let size = get_single_u64_from_op(vm, size)?;
+    if size == 0 {
+        return Ok(PathResult::Success(None));
     }
+
+    let value = vm.read_memory(&addr, size as u32 * BITS_IN_BYTE)?;
+    let concrete = vm.state.constraints.get_value(&value)?;
+    trace!("concretize: {value:?} = {concrete:?}");
+
+    vm.state.constraints.assert(&value._eq(&concrete));
+    vm.write_memory(&addr, concrete)?;
+
+    Ok(PathResult::Success(None))
 }
 
-fn get_operand_name(_op: &Value) -> String {
+/// Size of the rest of the allocation that `addr` points into.
+fn pointee_bit_size(vm: &LLVMExecutor<'_>, addr: &DExpr) -> Result<u64, LLVMExecutorError> {
+    // TODO: We need the size of the pointed to value, which we cannot easily get with
+    // opaque pointers.
+    //
+    // HACK:
+    // Use the size from the pointed to address to the end of the allocation, not entirely
+    // sure this works for all cases... Since, I think we may sometimes only want
+    // part of the memory object to be reset to entirely symbolic.
+    let address = match vm.state.constraints.get_values(addr, 1)? {
+        Solutions::Exactly(solutions) if solutions.len() == 1 => {
+            solutions[0].get_constant().unwrap()
+        }
+        _ => {
+            return Err(LLVMExecutorError::TooManySolutions(
+                "address passed to symex_lib::symbolic".to_owned(),
+            ))
+        }
+    };
+    let (base, size) = vm
+        .state
+        .memory
+        .allocation_containing(address)
+        .ok_or(MemoryError::OutOfBounds)?;
+    Ok(size - (address - base) * BITS_IN_BYTE as u64)
+}
+
+fn get_operand_name(ctx: &DContext, _op: &Value) -> String {
     // let name = (op);
     // let name = if name.is_empty() {
     //     "symex".to_string()
@@ -184,9 +342,29 @@ fn get_operand_name(_op: &Value) -> String {
     // };
     let name = "name-todo";
 
-    // Add random number at the end, this is to give each call to symbolic a unique name. Even if
-    // its called for variables with the same name.
-    format!("{}-{}", name, rand::random::<u32>())
+    // Number the name, this is to give each call to symbolic a unique name. Even if its called for
+    // variables with the same name.
+    ctx.fresh_name(name)
+}
+
+/// Read a string of `len` bytes starting at `addr`, the bytes are concretized if symbolic.
+fn read_str(
+    vm: &mut LLVMExecutor<'_>,
+    addr: &Value,
+    len: &Value,
+) -> Result<String, LLVMExecutorError> {
+    let len = get_single_u64_from_op(vm, len)?;
+    let addr = vm.state.get_expr(addr)?;
+
+    let mut bytes = Vec::new();
+    for offset in 0..len {
+        let offset = vm.state.ctx.from_u64(offset, vm.project.ptr_size);
+        let byte = vm.read_memory(&addr.add(&offset), BITS_IN_BYTE)?;
+        let byte = vm.state.constraints.get_value(&byte)?;
+        bytes.push(byte.get_constant().unwrap() as u8);
+    }
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 // Temporary function to get a single u64 value.
@@ -209,33 +387,115 @@ fn exit(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult, LLVMExe
     Err(LLVMExecutorError::Abort(exit_code as i64))
 }
 
+/// Hook for formatting functions that return `fmt::Result`, always returns `Ok(())`.
+fn fmt_ok(vm: &mut LLVMExecutor<'_>, _args: &[Value]) -> Result<PathResult, LLVMExecutorError> {
+    trace!("Hook: skipping formatting");
+    // `fmt::Result` is passed as a bool where `false` is `Ok(())`.
+    Ok(PathResult::Success(Some(vm.state.ctx.from_bool(false))))
+}
+
+/// Hook for functions without a return value that should not be executed.
+fn ignore_call(
+    _vm: &mut LLVMExecutor<'_>,
+    _args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    Ok(PathResult::Success(None))
+}
+
 /// Hook that tells the VM to abort.
 pub fn abort(_vm: &mut LLVMExecutor<'_>, _args: &[Value]) -> Result<PathResult, LLVMExecutorError> {
     debug!("Hook: panic!");
     Ok(PathResult::Failure(AnalysisError::Panic))
 }
 
+/// Hook for failed bounds checks, reports the failing index and length.
+///
+/// Used for `panic_bounds_check(index, len, location)` and the slice variants which share the same
+/// first two arguments.
+pub fn index_out_of_bounds(
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    report_index_out_of_bounds(vm, &args[0], &args[1])
+}
+
+// fn slice_index_fail(start: usize, end: usize, len: usize) -> !;
+fn slice_index_fail(
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
+) -> Result<PathResult, LLVMExecutorError> {
+    // Report the end of the range, as that is the index that exceeds the length.
+    report_index_out_of_bounds(vm, &args[1], &args[2])
+}
+
+fn report_index_out_of_bounds(
+    vm: &mut LLVMExecutor<'_>,
+    index: &Value,
+    len: &Value,
+) -> Result<PathResult, LLVMExecutorError> {
+    let index = vm.state.get_expr(index)?;
+    let len = vm.state.get_expr(len)?;
+
+    // Solve both at once so the values come from the same model.
+    let len_bits = len.len();
+    let solution = vm.state.constraints.get_value(&index.concat(&len))?;
+    let len = solution.slice(0, len_bits - 1).get_constant().unwrap();
+    let index = solution
+        .slice(len_bits, solution.len() - 1)
+        .get_constant()
+        .unwrap();
+
+    debug!("Hook: index out of bounds, index: {index}, len: {len}");
+    Ok(PathResult::Failure(AnalysisError::IndexOutOfBounds {
+        index,
+        len,
+    }))
+}
+
 // fn __rust_alloc(size: usize, align: usize) -> *mut u8;
 fn rust_alloc(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult, LLVMExecutorError> {
     assert_eq!(args.len(), 2);
 
-    let size_in_bytes = get_single_u64_from_op(vm, &args[0])?;
-    let size_in_bits = size_in_bytes * BITS_IN_BYTE as u64;
-
     let align = get_single_u64_from_op(vm, &args[1])?;
-
-    let addr = vm.state.memory.allocate(size_in_bits, align)?;
-    let addr = vm.state.ctx.from_u64(addr, vm.project.ptr_size);
+    let uninitialized = vm.vm.uninitialized_memory;
+    let addr = allocate(vm, &args[0], align, uninitialized)?;
 
     Ok(PathResult::Success(Some(addr)))
 }
 
+/// Allocate `size` bytes, where the size can be symbolic.
+///
+/// A symbolic size allocates the largest size it can take, up to
+/// [`VM::max_symbolic_allocation`](crate::vm::VM::max_symbolic_allocation).
+fn allocate(
+    vm: &mut LLVMExecutor<'_>,
+    size: &Value,
+    align: u64,
+    uninitialized: UninitializedMemory,
+) -> Result<DExpr, LLVMExecutorError> {
+    let size_in_bytes = vm.state.get_expr(size)?;
+    let addr = match size_in_bytes.get_constant() {
+        Some(size_in_bytes) => {
+            let size_in_bits = size_in_bytes * BITS_IN_BYTE as u64;
+            vm.state
+                .memory
+                .allocate_with(size_in_bits, align, uninitialized)?
+        }
+        None => vm.allocate_symbolic(&size_in_bytes, BITS_IN_BYTE as u64, align, uninitialized)?,
+    };
+    Ok(vm.state.ctx.from_u64(addr, vm.project.ptr_size))
+}
+
 // fn __rust_dealloc(ptr: *mut u8, size: usize, align: usize);
 fn rust_dealloc(
-    _vm: &mut LLVMExecutor<'_>,
-    _args: &[Value],
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
 ) -> Result<PathResult, LLVMExecutorError> {
-    // Currently, de-allocating is not supported.
+    assert_eq!(args.len(), 3);
+
+    // Freeing it again or freeing a pointer into the middle of an allocation fails the path.
+    let addr = vm.state.get_expr(&args[0])?;
+    vm.free_memory(&addr)?;
     Ok(PathResult::Success(None))
 }
 
@@ -247,16 +507,29 @@ fn rust_realloc(
     assert_eq!(args.len(), 4);
 
     let addr = vm.state.get_expr(&args[0])?;
-    let size = get_single_u64_from_op(vm, &args[1])?;
+    let old_size_in_bytes = get_single_u64_from_op(vm, &args[1])?;
     let align = get_single_u64_from_op(vm, &args[2])?;
     let size_in_bytes = get_single_u64_from_op(vm, &args[3])?;
     let size_in_bits = size_in_bytes * BITS_IN_BYTE as u64;
 
-    let new_addr = vm.state.memory.allocate(size_in_bits, align)?;
+    // Read the old data before allocating, reading a freed pointer fails the path.
+    let old_size_in_bits = old_size_in_bytes.min(size_in_bytes) * BITS_IN_BYTE as u64;
+    let old_data = match old_size_in_bits {
+        0 => None,
+        bits => Some(vm.read_memory(&addr, bits as u32)?),
+    };
+
+    let new_addr =
+        vm.state
+            .memory
+            .allocate_with(size_in_bits, align, vm.vm.uninitialized_memory)?;
     let new_addr = vm.state.ctx.from_u64(new_addr, vm.project.ptr_size);
+    if let Some(old_data) = old_data {
+        vm.write_memory(&new_addr, old_data)?;
+    }
 
-    let old_data = vm.state.memory.read(&addr, size as u32)?;
-    vm.state.memory.write(&new_addr, old_data)?;
+    // The old allocation is no longer valid after it has been moved.
+    vm.free_memory(&addr)?;
 
     Ok(PathResult::Success(Some(new_addr)))
 }
@@ -268,16 +541,8 @@ fn rust_alloc_zeroed(
 ) -> Result<PathResult, LLVMExecutorError> {
     assert_eq!(args.len(), 2);
 
-    let size_in_bytes = get_single_u64_from_op(vm, &args[0])?;
-    let size_in_bits = size_in_bytes * BITS_IN_BYTE as u64;
-
     let align = get_single_u64_from_op(vm, &args[1])?;
-
-    let addr = vm.state.memory.allocate(size_in_bits, align)?;
-    let addr = vm.state.ctx.from_u64(addr, vm.project.ptr_size);
-
-    let zeroes = vm.state.ctx.zero(size_in_bits as u32);
-    vm.state.memory.write(&addr, zeroes)?;
+    let addr = allocate(vm, &args[0], align, UninitializedMemory::Byte(0))?;
 
     Ok(PathResult::Success(Some(addr)))
 }
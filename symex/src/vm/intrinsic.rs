@@ -8,8 +8,8 @@ use std::collections::HashMap;
 use tracing::{debug, trace, warn};
 
 use crate::memory::BITS_IN_BYTE;
-use crate::smt::{DExpr, Solutions};
-use crate::vm::{binop, bit_size, LLVMExecutorError, PathResult, Result};
+use crate::smt::{DContext, DExpr, Solutions};
+use crate::vm::{binop, struct_from_fields, LLVMExecutorError, PathResult, Result};
 use crate::MAX_INTRINSIC_CONCRETIZATIONS;
 
 use super::LLVMExecutor;
@@ -33,6 +33,9 @@ pub type Intrinsic = fn(&mut LLVMExecutor<'_>, &[Value]) -> Result<PathResult>;
 /// the retrieved name.
 #[derive(Clone)]
 pub struct Intrinsics {
+    /// User provided intrinsics, these take priority over the defaults.
+    overrides: Trie<String, Intrinsic>,
+
     /// Fixed length intrinsic values, e.g. `llvm.va_start`.
     fixed: HashMap<String, Intrinsic>,
 
@@ -47,6 +50,7 @@ impl Intrinsics {
     /// Creates a new intrinsic hook storage with all the default intrinsics enabled.
     pub fn new_with_defaults() -> Self {
         let mut s = Self {
+            overrides: Trie::new(),
             fixed: HashMap::new(),
             variable: Trie::new(),
         };
@@ -56,8 +60,16 @@ impl Intrinsics {
 
         // Add variable intrinsics.
         s.add_variable("llvm.memcpy.", llvm_memcpy);
+        s.add_variable("llvm.memcpy.inline.", llvm_memcpy_inline);
         s.add_variable("llvm.memmove.", llvm_memmove);
         s.add_variable("llvm.memset.", llvm_memset);
+        s.add_variable("llvm.memset.inline.", llvm_memset);
+
+        // The element-wise atomic variants take the element size instead of the volatile flag as
+        // the last argument. Since execution is single threaded the atomicity does not matter.
+        s.add_variable("llvm.memcpy.element.unordered.atomic.", llvm_memcpy);
+        s.add_variable("llvm.memmove.element.unordered.atomic.", llvm_memmove);
+        s.add_variable("llvm.memset.element.unordered.atomic.", llvm_memset);
         s.add_variable("llvm.umax.", llvm_umax);
 
         s.add_variable("llvm.sadd.with.overflow.", llvm_sadd_with_overflow);
@@ -72,7 +84,16 @@ impl Intrinsics {
         s.add_variable("llvm.ssub.sat.", llvm_ssub_sat);
         s.add_variable("llvm.usub.sat.", llvm_usub_sat);
 
+        s.add_variable("llvm.ctpop.", llvm_ctpop);
+        s.add_variable("llvm.ctlz.", llvm_ctlz);
+        s.add_variable("llvm.cttz.", llvm_cttz);
+        s.add_variable("llvm.bswap.", llvm_bswap);
+        s.add_variable("llvm.bitreverse.", llvm_bitreverse);
+        s.add_variable("llvm.fshl.", llvm_fshl);
+        s.add_variable("llvm.fshr.", llvm_fshr);
+
         s.add_variable("llvm.expect.", llvm_expect);
+        s.add_variable("llvm.expect.with.probability.", llvm_expect);
 
         // Temporary.
         s.add_variable("llvm.dbg", noop);
@@ -96,14 +117,23 @@ impl Intrinsics {
         self.variable.insert(name.into(), hook);
     }
 
+    /// Add an intrinsic which is used instead of any default implementation.
+    ///
+    /// The name is matched as a prefix, so `llvm.sqrt.` overrides the intrinsic for all types
+    /// while `llvm.sqrt.f64` only overrides that single version.
+    pub fn add_override(&mut self, name: impl Into<String>, hook: Intrinsic) {
+        self.overrides.insert(name.into(), hook);
+    }
+
     /// Returns a reference to the hook of the given name. If the hook cannot be found `None` is
     /// returned.
     ///
-    /// It first checks the fixed length names, and if such a name cannot be found it checks the
-    /// variable length names.
+    /// It first checks the overrides, then the fixed length names, and if such a name cannot be
+    /// found it checks the variable length names.
     pub fn get(&self, name: &str) -> Option<&Intrinsic> {
-        self.fixed
-            .get(name)
+        self.overrides
+            .get_ancestor_value(name)
+            .or_else(|| self.fixed.get(name))
             .or_else(|| self.variable.get_ancestor_value(name))
     }
 }
@@ -132,21 +162,27 @@ pub fn llvm_memcpy(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResu
     let src = vm.state.get_expr(&args[1])?;
     let len = vm.state.get_expr(&args[2])?;
 
-    if let Some(len) = len.get_constant() {
-        if len > 0 {
-            let len = len as u32 * BITS_IN_BYTE;
-            let value = vm.state.memory.read(&src, len)?;
-            vm.state.memory.write(&dst, value)?;
-        } else {
-            warn!("memcpy with size 0");
-        }
+    let len = concretize_length(vm, &len, "llvm.memcpy")?;
+    if len > 0 {
+        vm.state.memory.copy(&dst, &src, len)?;
     } else {
-        todo!("symbolic length in llvm.memcpy.*");
+        warn!("memcpy with size 0");
     }
 
     Ok(PathResult::Success(None))
 }
 
+/// Same as `llvm_memcpy` but the length is required to be a constant.
+pub fn llvm_memcpy_inline(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    assert_eq!(args.len(), 4);
+    debug!("llvm_memcpy_inline");
+
+    if vm.state.get_expr(&args[2])?.get_constant().is_none() {
+        return Err(LLVMExecutorError::MalformedInstruction);
+    }
+    llvm_memcpy(vm, args)
+}
+
 pub fn llvm_memset(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
     assert_eq!(args.len(), 4);
     trace!("llvm_memset");
@@ -161,15 +197,12 @@ pub fn llvm_memset(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResu
     let len = vm.state.get_expr(&args[2])?;
 
     assert_eq!(val.len(), BITS_IN_BYTE);
-    if let Some(len) = len.get_constant() {
-        for byte in 0..len {
-            let offset = vm.state.ctx.from_u64(byte, vm.project.ptr_size);
-            let addr = dst.add(&offset);
+    let len = concretize_length(vm, &len, "llvm.memset")?;
+    for byte in 0..len {
+        let offset = vm.state.ctx.from_u64(byte, vm.project.ptr_size);
+        let addr = dst.add(&offset);
 
-            vm.state.memory.write(&addr, val.clone())?;
-        }
-    } else {
-        todo!("symbolic length in llvm.memset.*");
+        vm.write_memory(&addr, val.clone())?;
     }
 
     Ok(PathResult::Success(None))
@@ -187,51 +220,45 @@ pub fn llvm_memmove(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathRes
     let src = vm.state.get_expr(&args[1])?;
     let len = vm.state.get_expr(&args[2])?;
 
-    // TODO: Not sure about the exact semantics when the locations overlap. So copy the bytes
-    // one by one for now.
-
-    let len = match len.get_constant() {
-        Some(len) => len,
-        None => {
-            warn!("symbolic length in llvm.memmove.* is experimental");
-            let concretizations = MAX_INTRINSIC_CONCRETIZATIONS;
-            let solutions = vm.state.constraints.get_values(&len, concretizations)?;
-            let solutions = match solutions {
-                Solutions::Exactly(v) => v,
-                Solutions::AtLeast(v) => {
-                    warn!(
-                        "More than {} solutions found for length in memmove",
-                        concretizations
-                    );
-                    v
-                }
-            };
+    let len = concretize_length(vm, &len, "llvm.memmove")?;
 
-            let (solution, others) = solutions.split_first().unwrap();
+    // The source is read before the destination is written, so overlapping regions are fine.
+    vm.state.memory.copy(&dst, &src, len)?;
 
-            // Fork other paths.
-            for solution in others.iter() {
-                let constraint = len._eq(&solution);
-                vm.fork(constraint)?;
-            }
+    Ok(PathResult::Success(None))
+}
 
-            // OK, lets go.
-            let constraint = len._eq(&solution);
-            vm.state.constraints.assert(&constraint);
-            solution.get_constant().unwrap() // Know this is constant.
+/// Get a concrete value for the length `len` of the memory intrinsic `name`.
+///
+/// A symbolic length is concretized by creating a new path for each possible length but one,
+/// which is used on this path. If the length can take more than [`MAX_INTRINSIC_CONCRETIZATIONS`]
+/// values this is an error, instead of silently leaving out the other lengths.
+fn concretize_length(vm: &mut LLVMExecutor<'_>, len: &DExpr, name: &str) -> Result<u64> {
+    if let Some(len) = len.get_constant() {
+        return Ok(len);
+    }
+
+    let max = MAX_INTRINSIC_CONCRETIZATIONS;
+    let solutions = match vm.state.constraints.get_values(len, max)? {
+        Solutions::Exactly(solutions) => solutions,
+        Solutions::AtLeast(_) => {
+            return Err(LLVMExecutorError::TooManyLengthSolutions(
+                name.to_owned(),
+                max,
+            ))
         }
     };
+    let (solution, others) = solutions
+        .split_first()
+        .expect("Length should have a solution on a satisfiable path");
 
-    for i in 0..len {
-        let increment = vm.state.ctx.from_u64(i, vm.project.ptr_size);
-        let src_addr = src.add(&increment);
-        let dst_addr = dst.add(&increment);
-
-        let value = vm.state.memory.read(&src_addr, BITS_IN_BYTE)?;
-        vm.state.memory.write(&dst_addr, value)?;
+    // Fork other paths.
+    for other in others {
+        vm.fork(len._eq(other))?;
     }
 
-    Ok(PathResult::Success(None))
+    vm.state.constraints.assert(&len._eq(solution));
+    Ok(solution.get_constant().unwrap())
 }
 
 pub fn llvm_umax(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
@@ -297,12 +324,9 @@ fn binary_op_overflow(
     // The result type is a struct so {result, overflow} and for vectors this means {<iX res>, <i1>}
     // so the results and overflows have to be appended separately until the final return. Which the
     // regular `binop` does not handle.
-    let result = match (lhs_value.ty(), rhs_value.ty()) {
+    let (results, overflows) = match (lhs_value.ty(), rhs_value.ty()) {
         // For integers just perform the operation.
-        (Type::Integer(_), Type::Integer(_)) => {
-            let (result, overflow) = operation(lhs, rhs);
-            Ok(overflow.concat(&result))
-        }
+        (Type::Integer(_), Type::Integer(_)) => Ok(operation(lhs, rhs)),
 
         // For vectors each operation has to be done independently, and the return should be in
         // the format of {results, overflows}.
@@ -313,7 +337,7 @@ fn binary_op_overflow(
             assert_eq!(lhs_ty.num_elements(), rhs_ty.num_elements());
 
             let num_elements = lhs_ty.num_elements();
-            let bits = bit_size(&lhs_ty.element_type(), vm.project.ptr_size)?;
+            let bits = vm.project.bit_size(&lhs_ty.element_type())?;
 
             // Perform the operation per element and concatenate the result.
             let (results, overflows) = (0..num_elements)
@@ -330,14 +354,21 @@ fn binary_op_overflow(
                 .ok_or(LLVMExecutorError::MalformedInstruction)?;
 
             trace!("results: {results:?}, overflows: {overflows:?}");
-            Ok(overflows.concat(&results))
+            Ok((results, overflows))
         }
 
         // These types should not appear in a binary operation.
         _ => Err(LLVMExecutorError::MalformedInstruction),
     }?;
 
-    Ok(PathResult::Success(Some(result)))
+    // The fields of the returned struct are placed as given by the data layout.
+    let call = vm.state.current_frame()?.current_instruction();
+    let ty = call
+        .map(|call| call.result_type())
+        .ok_or(LLVMExecutorError::MalformedInstruction)?;
+    let result = struct_from_fields(&vm.state, &ty, [Some(results), Some(overflows)])?;
+
+    Ok(PathResult::Success(result))
 }
 
 /// Signed addition on any bit width, performs a signed addition and indicates whether an overflow
@@ -426,12 +457,232 @@ pub fn llvm_ssub_sat(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathRe
     binary_op_saturate(vm, args, BinaryOpSaturate::SSub)
 }
 
+// -------------------------------------------------------------------------------------------------
+// Bit manipulation intrinsics
+// -------------------------------------------------------------------------------------------------
+
+/// Perform `operation` on an integer, or on each element of a vector of integers.
+fn unop<F>(vm: &mut LLVMExecutor<'_>, op: &Value, operation: F) -> Result<DExpr>
+where
+    F: Fn(&DExpr) -> DExpr,
+{
+    let value = vm.state.get_expr(op)?;
+    match op.ty() {
+        Type::Integer(_) => Ok(operation(&value)),
+        Type::Vector(ty) if !ty.is_scalable() => {
+            let bits = vm.project.bit_size(&ty.element_type())?;
+            (0..ty.num_elements())
+                .map(|i| operation(&value.slice(i * bits, (i + 1) * bits - 1)))
+                .reduce(|acc, v| v.concat(&acc))
+                .ok_or(LLVMExecutorError::MalformedInstruction)
+        }
+        _ => Err(LLVMExecutorError::MalformedInstruction),
+    }
+}
+
+/// Perform `operation` on three integers, or on each element of three vectors of integers.
+fn ternop<F>(
+    vm: &mut LLVMExecutor<'_>,
+    op0: &Value,
+    op1: &Value,
+    op2: &Value,
+    operation: F,
+) -> Result<DExpr>
+where
+    F: Fn(&DExpr, &DExpr, &DExpr) -> DExpr,
+{
+    let value0 = vm.state.get_expr(op0)?;
+    let value1 = vm.state.get_expr(op1)?;
+    let value2 = vm.state.get_expr(op2)?;
+    match op0.ty() {
+        Type::Integer(_) => Ok(operation(&value0, &value1, &value2)),
+        Type::Vector(ty) if !ty.is_scalable() => {
+            let bits = vm.project.bit_size(&ty.element_type())?;
+            (0..ty.num_elements())
+                .map(|i| {
+                    let low = i * bits;
+                    let high = (i + 1) * bits - 1;
+                    operation(
+                        &value0.slice(low, high),
+                        &value1.slice(low, high),
+                        &value2.slice(low, high),
+                    )
+                })
+                .reduce(|acc, v| v.concat(&acc))
+                .ok_or(LLVMExecutorError::MalformedInstruction)
+        }
+        _ => Err(LLVMExecutorError::MalformedInstruction),
+    }
+}
+
+/// Count the set bits of `value`, the result has the same width.
+fn count_ones(value: &DExpr) -> DExpr {
+    let bits = value.len();
+    (0..bits)
+        .map(|i| value.slice(i, i).zero_ext(bits))
+        .reduce(|acc, bit| acc.add(&bit))
+        .unwrap()
+}
+
+/// Count the zero bits of `value` before the first set bit, starting from the most significant
+/// bit if `leading` is set and from the least significant bit otherwise.
+///
+/// A zero value gives the width of the value. If `is_zero_poison` is set the result for zero is
+/// poison instead, which is modeled as an unconstrained value.
+fn count_zeros(ctx: &DContext, value: &DExpr, leading: bool, is_zero_poison: bool) -> DExpr {
+    let bits = value.len();
+    let zero_result = match is_zero_poison {
+        true => ctx.fresh_unconstrained(bits, "count-zeros-poison"),
+        false => ctx.from_u64(bits as u64, bits),
+    };
+
+    // The bit closest to the end that is counted from decides the result, so it is checked last,
+    // ending up as the outermost condition.
+    let count = |i: u32| match leading {
+        true => bits - 1 - i,
+        false => i,
+    };
+    let mut order: Vec<u32> = (0..bits).collect();
+    if !leading {
+        order.reverse();
+    }
+    order.into_iter().fold(zero_result, |result, i| {
+        let count = ctx.from_u64(count(i) as u64, bits);
+        value.slice(i, i).ite(&count, &result)
+    })
+}
+
+/// Count the number of set bits.
+pub fn llvm_ctpop(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    assert_eq!(args.len(), 1);
+    trace!("llvm_ctpop");
+
+    let result = unop(vm, &args[0], count_ones)?;
+    Ok(PathResult::Success(Some(result)))
+}
+
+/// Count the leading zero bits.
+///
+/// The second operand `is_zero_poison` makes the result for zero poison.
+pub fn llvm_ctlz(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    llvm_count_zeros(vm, args, true)
+}
+
+/// Count the trailing zero bits.
+///
+/// The second operand `is_zero_poison` makes the result for zero poison.
+pub fn llvm_cttz(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    llvm_count_zeros(vm, args, false)
+}
+
+fn llvm_count_zeros(
+    vm: &mut LLVMExecutor<'_>,
+    args: &[Value],
+    leading: bool,
+) -> Result<PathResult> {
+    assert_eq!(args.len(), 2);
+    trace!("llvm_count_zeros leading={leading}");
+
+    // The poison flag is an immediate argument, so it is always constant.
+    let is_zero_poison = vm
+        .state
+        .get_expr(&args[1])?
+        .get_constant_bool()
+        .ok_or(LLVMExecutorError::MalformedInstruction)?;
+
+    let ctx = vm.state.ctx;
+    let operation = |value: &DExpr| count_zeros(ctx, value, leading, is_zero_poison);
+    let result = unop(vm, &args[0], operation)?;
+    Ok(PathResult::Success(Some(result)))
+}
+
+/// Split `value` into chunks of `chunk_bits` and concatenate them in reverse order.
+fn reverse_chunks(value: &DExpr, chunk_bits: u32) -> DExpr {
+    let bits = value.len();
+    assert_eq!(bits % chunk_bits, 0);
+
+    // The chunks are visited from the least significant, each concatenated chunk ends up as the
+    // least significant part of the result.
+    (0..bits / chunk_bits)
+        .map(|i| value.slice(i * chunk_bits, (i + 1) * chunk_bits - 1))
+        .reduce(|acc, chunk| acc.concat(&chunk))
+        .unwrap()
+}
+
+/// Swap the byte order.
+///
+/// The width of the integer, or of the elements for a vector, must be a multiple of a byte.
+pub fn llvm_bswap(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    assert_eq!(args.len(), 1);
+    trace!("llvm_bswap");
+
+    let bits = match args[0].ty() {
+        Type::Vector(ty) => vm.project.bit_size(&ty.element_type())?,
+        ty => vm.project.bit_size(&ty)?,
+    };
+    if bits % BITS_IN_BYTE != 0 {
+        warn!("llvm.bswap on a width of {bits} bits that is not a multiple of a byte");
+        return Err(LLVMExecutorError::MalformedInstruction);
+    }
+
+    let result = unop(vm, &args[0], |value| reverse_chunks(value, BITS_IN_BYTE))?;
+    Ok(PathResult::Success(Some(result)))
+}
+
+/// Reverse the bit order.
+pub fn llvm_bitreverse(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    assert_eq!(args.len(), 1);
+    trace!("llvm_bitreverse");
+
+    let result = unop(vm, &args[0], |value| reverse_chunks(value, 1))?;
+    Ok(PathResult::Success(Some(result)))
+}
+
+/// Concatenate `high` and `low`, shift the result by `shift` modulo the width and extract the
+/// upper half for left shifts or the lower half for right shifts.
+fn funnel_shift(ctx: &DContext, high: &DExpr, low: &DExpr, shift: &DExpr, left: bool) -> DExpr {
+    let bits = high.len();
+    let shift = shift.urem(&ctx.from_u64(bits as u64, bits));
+
+    // Shift the double width value so no bits are lost.
+    let value = high.concat(low);
+    let shift = shift.zero_ext(2 * bits);
+    match left {
+        true => value.sll(&shift).slice(bits, 2 * bits - 1),
+        false => value.srl(&shift).slice(0, bits - 1),
+    }
+}
+
+/// Funnel shift left, a rotate left if both the first operands are the same.
+pub fn llvm_fshl(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    llvm_funnel_shift(vm, args, true)
+}
+
+/// Funnel shift right, a rotate right if both the first operands are the same.
+pub fn llvm_fshr(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+    llvm_funnel_shift(vm, args, false)
+}
+
+fn llvm_funnel_shift(vm: &mut LLVMExecutor<'_>, args: &[Value], left: bool) -> Result<PathResult> {
+    assert_eq!(args.len(), 3);
+    trace!("llvm_funnel_shift left={left}");
+
+    let ctx = vm.state.ctx;
+    let operation =
+        |high: &DExpr, low: &DExpr, shift: &DExpr| funnel_shift(ctx, high, low, shift, left);
+    let result = ternop(vm, &args[0], &args[1], &args[2], operation)?;
+    Ok(PathResult::Success(Some(result)))
+}
+
 // -------------------------------------------------------------------------------------------------
 // General intrinsics
 // -------------------------------------------------------------------------------------------------
 
+/// Branch hint, returns the first operand unchanged.
+///
+/// Also used for `llvm.expect.with.probability` which has the probability as a third operand.
 pub fn llvm_expect(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
-    assert_eq!(args.len(), 2);
+    assert!(args.len() == 2 || args.len() == 3);
     let val = vm.state.get_expr(&args[0])?;
     Ok(PathResult::Success(Some(val)))
 }
@@ -447,9 +698,11 @@ pub fn llvm_assume(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResu
 
 #[cfg(test)]
 mod tests {
+    use llvm_ir::Value;
+
     use crate::{
-        smt::DContext,
-        vm::{PathResult, Project, VM},
+        smt::{DContext, Solutions},
+        vm::{LLVMExecutor, LLVMExecutorError, PathResult, Project, Result, VM},
     };
 
     fn run(fn_name: &str) -> Vec<Option<i64>> {
@@ -460,8 +713,12 @@ mod tests {
         //     .expect("setting default subscriber failed");
 
         let path = format!("tests/unit_tests/intrinsics.bc");
-        let project = Box::new(Project::from_path(&path).expect("Failed to created project"));
-        let project = Box::leak(project);
+        let project = Project::from_path(&path).expect("Failed to created project");
+        run_project(project, fn_name)
+    }
+
+    fn run_project(project: Project, fn_name: &str) -> Vec<Option<i64>> {
+        let project = Box::leak(Box::new(project));
 
         let context = Box::new(DContext::new());
         let context = Box::leak(context);
@@ -499,6 +756,13 @@ mod tests {
         assert_eq!(res[0], Some(0x6543fe671234abcd));
     }
 
+    #[test]
+    fn test_memcpy_inline() {
+        let res = run("test_memcpy_inline");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x6543fe671234abcd));
+    }
+
     #[test]
     fn test_memmove() {
         let res = run("test_memmove");
@@ -535,6 +799,157 @@ mod tests {
         assert_eq!(res[0], Some(-6076574517859464245i64)); // 0xababababcbcbcbcb
     }
 
+    #[test]
+    fn test_memcpy_symbolic_len() {
+        let mut res = run("test_memcpy_symbolic_len");
+        res.sort();
+        assert_eq!(res, vec![Some(0x00abcdef), Some(0x12abcdef)]);
+    }
+
+    #[test]
+    fn test_memset_symbolic_len() {
+        let mut res = run("test_memset_symbolic_len");
+        res.sort();
+        assert_eq!(res, vec![Some(0x00ababab), Some(0xabababab)]);
+    }
+
+    #[test]
+    fn test_memset_unbounded_len() {
+        let path = "tests/unit_tests/intrinsics.bc";
+        let project = Project::from_path(path).expect("Failed to created project");
+        let project = Box::leak(Box::new(project));
+
+        let context = Box::leak(Box::new(DContext::new()));
+        let mut vm =
+            VM::new(project, context, "test_memset_unbounded_len").expect("Failed to create VM");
+        assert_eq!(
+            vm.run().err(),
+            Some(LLVMExecutorError::TooManyLengthSolutions(
+                "llvm.memset".to_owned(),
+                crate::MAX_INTRINSIC_CONCRETIZATIONS
+            ))
+        );
+    }
+
+    #[test]
+    fn test_ctpop() {
+        let res = run("test_ctpop");
+        assert_eq!(res, vec![Some(16)]);
+    }
+
+    #[test]
+    fn test_ctpop_symbolic() {
+        let path = "tests/unit_tests/intrinsics.bc";
+        let project = Project::from_path(path).expect("Failed to created project");
+        let project = Box::leak(Box::new(project));
+
+        let context = Box::leak(Box::new(DContext::new()));
+        let mut vm = VM::new(project, context, "test_ctpop_symbolic").expect("Failed to create VM");
+        let (result, state) = vm.run().expect("Failed to run path").expect("No path");
+        let PathResult::Success(Some(result)) = result else {
+            panic!("Unexpected result {result:?}");
+        };
+
+        // The input is in the upper byte and the count in the lower byte.
+        let Solutions::Exactly(solutions) = state.constraints.get_values(&result, 300).unwrap()
+        else {
+            panic!("Too many solutions");
+        };
+        assert_eq!(solutions.len(), 256);
+        for solution in solutions {
+            let solution = solution.get_constant().unwrap();
+            assert_eq!((solution >> 8).count_ones() as u64, solution & 0xff);
+        }
+    }
+
+    #[test]
+    fn test_ctlz() {
+        let res = run("test_ctlz");
+        assert_eq!(res, vec![Some(8)]);
+        let res = run("test_ctlz_zero");
+        assert_eq!(res, vec![Some(32)]);
+    }
+
+    #[test]
+    fn test_cttz() {
+        let res = run("test_cttz");
+        assert_eq!(res, vec![Some(20)]);
+        let res = run("test_cttz_zero");
+        assert_eq!(res, vec![Some(32)]);
+    }
+
+    #[test]
+    fn test_bswap() {
+        let res = run("test_bswap");
+        assert_eq!(res, vec![Some(0x78563412)]);
+    }
+
+    #[test]
+    fn test_bswap_symbolic() {
+        let path = "tests/unit_tests/intrinsics.bc";
+        let project = Project::from_path(path).expect("Failed to created project");
+        let project = Box::leak(Box::new(project));
+
+        let context = Box::leak(Box::new(DContext::new()));
+        let mut vm = VM::new(project, context, "test_bswap_symbolic").expect("Failed to create VM");
+        let (result, state) = vm.run().expect("Failed to run path").expect("No path");
+        let PathResult::Success(Some(result)) = result else {
+            panic!("Unexpected result {result:?}");
+        };
+
+        // The input is in the upper half and the swapped input in the lower half.
+        let value = state.constraints.get_value(&result).unwrap();
+        let value = value.get_constant().unwrap();
+        let input = (value >> 32) as u32;
+        assert_eq!(input.swap_bytes(), value as u32);
+    }
+
+    #[test]
+    fn test_bitreverse() {
+        let res = run("test_bitreverse");
+        assert_eq!(res, vec![Some(0x8004)]);
+    }
+
+    #[test]
+    fn test_fshl() {
+        let res = run("test_fshl");
+        assert_eq!(res, vec![Some(0x03)]);
+        let res = run("test_fshl_modulo");
+        assert_eq!(res, vec![Some(0x23)]);
+    }
+
+    #[test]
+    fn test_fshr() {
+        let res = run("test_fshr");
+        assert_eq!(res, vec![Some(0x23)]);
+    }
+
+    #[test]
+    fn test_rotate_symbolic() {
+        let path = "tests/unit_tests/intrinsics.bc";
+        let project = Project::from_path(path).expect("Failed to created project");
+        let project = Box::leak(Box::new(project));
+
+        let context = Box::leak(Box::new(DContext::new()));
+        let mut vm =
+            VM::new(project, context, "test_rotate_symbolic").expect("Failed to create VM");
+        let (result, state) = vm.run().expect("Failed to run path").expect("No path");
+        let PathResult::Success(Some(result)) = result else {
+            panic!("Unexpected result {result:?}");
+        };
+
+        // The result holds the input, the shift amount and the rotated input, one byte each.
+        let (Solutions::Exactly(solutions) | Solutions::AtLeast(solutions)) =
+            state.constraints.get_values(&result, 100).unwrap();
+        assert!(!solutions.is_empty());
+        for solution in solutions {
+            let solution = solution.get_constant().unwrap();
+            let input = (solution >> 16) as u8;
+            let shift = (solution >> 8) as u8;
+            assert_eq!(input.rotate_left(shift as u32), solution as u8);
+        }
+    }
+
     #[test]
     fn test_umax() {
         let res = run("test_umax");
@@ -542,6 +957,21 @@ mod tests {
         assert_eq!(res[0], Some(0xbcef));
     }
 
+    #[test]
+    fn test_override_intrinsic() {
+        fn umax_constant(vm: &mut LLVMExecutor<'_>, args: &[Value]) -> Result<PathResult> {
+            let lhs = vm.state.get_expr(&args[0])?;
+            let result = vm.state.ctx.from_u64(0x1234, lhs.len());
+            Ok(PathResult::Success(Some(result)))
+        }
+
+        let path = "tests/unit_tests/intrinsics.bc";
+        let mut project = Project::from_path(path).expect("Failed to created project");
+        project.override_intrinsic("llvm.umax.", umax_constant);
+        let res = run_project(project, "test_umax");
+        assert_eq!(res, vec![Some(0x1234)]);
+    }
+
     #[test]
     fn test_umax_vec() {
         let res = run("test_umax_vec");
@@ -682,6 +1112,70 @@ mod tests {
         assert_eq!(res[0], Some(0x00fa));
     }
 
+    #[test]
+    fn test_ssub_with_overflow0() {
+        let res = run("test_ssub_with_overflow0");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x016a));
+    }
+
+    #[test]
+    fn test_ssub_with_overflow1() {
+        let res = run("test_ssub_with_overflow1");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x00f6));
+    }
+
+    #[test]
+    fn test_usub_with_overflow0() {
+        let res = run("test_usub_with_overflow0");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x01f6));
+    }
+
+    #[test]
+    fn test_usub_with_overflow1() {
+        let res = run("test_usub_with_overflow1");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x000a));
+    }
+
+    #[test]
+    fn test_smul_with_overflow0() {
+        let res = run("test_smul_with_overflow0");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0180));
+    }
+
+    #[test]
+    fn test_smul_with_overflow1() {
+        let res = run("test_smul_with_overflow1");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0080));
+    }
+
+    #[test]
+    fn test_umul_with_overflow0() {
+        let res = run("test_umul_with_overflow0");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x0100));
+    }
+
+    #[test]
+    fn test_umul_with_overflow1() {
+        let res = run("test_umul_with_overflow1");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0], Some(0x00ff));
+    }
+
+    #[test]
+    fn test_checked_add() {
+        // Symbolic operands can both overflow and not, so both paths should be explored.
+        let mut res = run("test_checked_add");
+        res.sort();
+        assert_eq!(res, vec![Some(0), Some(1)]);
+    }
+
     #[test]
     fn test_expect() {
         let res = run("test_expect");
@@ -689,6 +1183,12 @@ mod tests {
         assert_eq!(res[0], Some(100));
     }
 
+    #[test]
+    fn test_expect_with_probability() {
+        let res = run("test_expect_with_probability");
+        assert_eq!(res, vec![Some(1)]);
+    }
+
     #[test]
     fn test_assume() {
         let res = run("test_assume");
@@ -2,6 +2,7 @@ mod executor;
 mod hooks;
 mod intrinsic;
 mod path_selection;
+mod path_tree;
 mod project;
 mod state;
 mod vm;
@@ -10,6 +11,7 @@ pub use executor::*;
 pub use hooks::*;
 pub use intrinsic::*;
 pub use path_selection::*;
+pub use path_tree::*;
 pub use project::*;
 pub use state::*;
 pub use vm::*;
@@ -23,11 +25,85 @@ use crate::{memory::MemoryError, smt::SolverError};
 /// to the execution of the VM/Executor.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum AnalysisError {
-    // CallDepthExceeded,
-    // IterationCountExceeded,
     // NoPath,
     Panic,
     Unreachable,
+
+    /// A bounds check failed, contains the solved index and length.
+    IndexOutOfBounds {
+        index: u64,
+        len: u64,
+    },
+
+    /// An indirect branch can jump to an address which is not one of its listed destinations.
+    InvalidBranchTarget,
+
+    /// A memory access through a symbolic pointer can be outside of all allocations.
+    OutOfBounds,
+
+    /// The offset computed by a `getelementptr` with a symbolic index overflows the pointer
+    /// width, contains the solved index.
+    PointerOverflow {
+        index: u64,
+    },
+
+    /// The size of a type was needed but is not known, e.g. for an opaque structure declared for
+    /// FFI. Contains the name of the type.
+    UnsizedType(String),
+
+    /// A symbolic pointer can point to more locations than the resolution limit, see
+    /// [`VM::max_memory_access_resolutions`]. Contains the limit.
+    TooManyAddressResolutions {
+        max: usize,
+    },
+
+    /// A loop header was entered again with exactly the same state as in the previous iteration,
+    /// so the loop never terminates. Contains the loop header, see
+    /// [`VM::detect_non_termination`].
+    NonTermination {
+        function: String,
+        block: String,
+    },
+
+    /// A load or store can use an address that is not a multiple of the alignment given on the
+    /// instruction, contains the alignment in bytes. See [`VM::check_alignment`].
+    MisalignedAccess {
+        align: u64,
+    },
+
+    /// A call would make the call stack deeper than the limit, contains the limit. See
+    /// [`VM::max_call_depth`].
+    CallDepthExceeded {
+        max: usize,
+    },
+
+    /// More instructions than the limit were executed on the path, contains the limit. See
+    /// [`VM::max_iter_count`].
+    IterationCountExceeded {
+        max: u64,
+    },
+
+    /// Memory was read, written or freed after its allocation was freed.
+    UseAfterFree,
+
+    /// A pointer that is not the start of an allocation was freed.
+    InvalidFree,
+
+    /// The condition passed to `symex_lib::assert` can be false.
+    AssertionFailed,
+
+    /// An allocation with a symbolic size is always larger than the limit, contains the limit in
+    /// bytes. See [`VM::max_symbolic_allocation`].
+    AllocationTooLarge {
+        max: u64,
+    },
+}
+
+impl AnalysisError {
+    /// Returns `true` if the error comes from a Rust panic, which unwinds the stack.
+    pub fn is_panic(&self) -> bool {
+        matches!(self, Self::Panic | Self::IndexOutOfBounds { .. })
+    }
 }
 
 pub type Result<T> = std::result::Result<T, LLVMExecutorError>;
@@ -41,12 +117,22 @@ pub enum LLVMExecutorError {
     #[error("Function not found: {0}")]
     FunctionNotFound(String),
 
+    /// Several functions match the name, contains the name and a description of each candidate
+    /// with the source file it is defined in.
+    #[error("Multiple functions with name {name} exist: {}", candidates.join(", "))]
+    AmbiguousFunction {
+        name: String,
+        candidates: Vec<String>,
+    },
+
     /// Local register variable not found.
     #[error("Local not found: {0}")]
     LocalNotFound(String),
 
-    #[error("Cannot take size of type")]
-    NoSize,
+    /// The size of the type is not known, e.g. for an opaque structure. Contains the name of the
+    /// type.
+    #[error("Cannot take size of type {0}")]
+    NoSize(String),
 
     /// MalformedInstruction
     #[error("MalformedInstruction")]
@@ -62,6 +148,24 @@ pub enum LLVMExecutorError {
     #[error("No active stack frame")]
     NoStackFrame,
 
+    /// A reported value has more than one possible solution, contains the name of the value. See
+    /// [`MaxSolutionsExceeded::Error`](crate::run::MaxSolutionsExceeded::Error).
+    #[error("{0} has more than one possible value")]
+    TooManySolutions(String),
+
+    /// A symbolic pointer can take more values than the resolution limit.
+    #[error("More than {0} possible addresses for a symbolic pointer")]
+    TooManyAddressResolutions(usize),
+
+    /// An allocation with a symbolic size is always larger than the limit in bytes.
+    #[error("Symbolic allocation is always larger than {0} bytes")]
+    AllocationTooLarge(u64),
+
+    /// The symbolic length of a memory intrinsic such as `llvm.memcpy` can take more values than
+    /// the concretization limit. Contains the name of the intrinsic and the limit.
+    #[error("More than {1} possible lengths for the symbolic length of {0}")]
+    TooManyLengthSolutions(String, usize),
+
     #[error("Memory error")]
     MemoryError(#[from] MemoryError),
 
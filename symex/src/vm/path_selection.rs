@@ -1,7 +1,17 @@
 //! Path exploration strategies.
 //!
-//! Currently the only supported strategy is [`DFSPathExploration`] which explores all paths using
-//! depth-first search.
+//! The VM uses [`DFSPathSelection`] by default, which explores all paths using depth-first search.
+//! Other strategies implement [`PathSelection`] and are set with
+//! [`VM::set_path_selection`](super::VM::set_path_selection), e.g. [`BFSPathSelection`] which
+//! explores paths in the order they were created, [`RandomPathSelection`] which picks a random
+//! path, or [`PriorityPathSelection`] which runs the path with the highest score first.
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::smt::DExpr;
 
 use super::state::LLVMState;
@@ -20,6 +30,9 @@ pub struct Path {
 
     /// Constraints to add before starting execution on this path.
     pub constraints: Vec<DExpr>,
+
+    /// Id of the path in the exploration tree.
+    pub id: usize,
 }
 
 impl Path {
@@ -31,10 +44,38 @@ impl Path {
             None => vec![],
         };
 
-        Self { state, constraints }
+        Self {
+            state,
+            constraints,
+            id: 0,
+        }
     }
 }
 
+/// Strategy for which of the saved paths to explore next.
+///
+/// All paths share a single solver, see [`DSolver`](crate::smt::DSolver). A strategy must leave
+/// the solver with the constraints of the returned path asserted, which for anything but
+/// depth-first search means saving them with the path, as [`PriorityPathSelection`] does.
+pub trait PathSelection: std::fmt::Debug {
+    /// Add a new path to be explored.
+    fn save_path(&mut self, path: Path);
+
+    /// Retrieve the next path to explore.
+    fn get_path(&mut self) -> Option<Path>;
+
+    /// Number of paths waiting to be explored.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no paths left to explore.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Discard all saved paths.
+    fn clear(&mut self);
+}
+
 /// Depth-first search path exploration.
 ///
 /// Each path is explored for as long as possible, when a path finishes the most recently added
@@ -49,15 +90,21 @@ impl DFSPathSelection {
     pub fn new() -> Self {
         Self { paths: Vec::new() }
     }
+}
 
-    /// Add a new path to be explored.
-    pub fn save_path(&mut self, path: Path) {
+impl Default for DFSPathSelection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathSelection for DFSPathSelection {
+    fn save_path(&mut self, path: Path) {
         path.state.constraints.push();
         self.paths.push(path);
     }
 
-    /// Retrieve the next path to explore.
-    pub fn get_path(&mut self) -> Option<Path> {
+    fn get_path(&mut self) -> Option<Path> {
         match self.paths.pop() {
             Some(path) => {
                 path.state.constraints.pop();
@@ -66,4 +113,204 @@ impl DFSPathSelection {
             None => None,
         }
     }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
+
+/// Breadth-first search path exploration.
+///
+/// The path that was saved first is the next to be run, so shorter paths finish before longer
+/// ones. This finds the paths that take the fewest branches to an error first.
+#[derive(Debug, Clone)]
+pub struct BFSPathSelection {
+    paths: VecDeque<(Vec<DExpr>, Path)>,
+}
+
+impl BFSPathSelection {
+    /// Creates new without any stored paths.
+    pub fn new() -> Self {
+        Self {
+            paths: VecDeque::new(),
+        }
+    }
+}
+
+impl Default for BFSPathSelection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathSelection for BFSPathSelection {
+    fn save_path(&mut self, path: Path) {
+        // Paths are not resumed in the reverse order they were saved in, so the constraints have to
+        // be restored the same way as for `PriorityPathSelection`.
+        let constraints = &path.state.constraints;
+        let assertions = constraints.assertions();
+        constraints.push();
+        self.paths.push_back((assertions, path));
+    }
+
+    fn get_path(&mut self) -> Option<Path> {
+        let (assertions, path) = self.paths.pop_front()?;
+        path.state.constraints.restore(&assertions);
+        Some(path)
+    }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
+
+/// Explores a random saved path next.
+///
+/// Avoids that a single deep loop starves all other paths, as can happen with depth-first search.
+/// The random choices only depend on the seed, so a run can be reproduced by using the same seed.
+#[derive(Debug, Clone)]
+pub struct RandomPathSelection {
+    paths: Vec<(Vec<DExpr>, Path)>,
+    rng: StdRng,
+}
+
+impl RandomPathSelection {
+    /// Creates new without any stored paths, choosing paths based on `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            paths: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl PathSelection for RandomPathSelection {
+    fn save_path(&mut self, path: Path) {
+        let constraints = &path.state.constraints;
+        let assertions = constraints.assertions();
+        constraints.push();
+        self.paths.push((assertions, path));
+    }
+
+    fn get_path(&mut self) -> Option<Path> {
+        if self.paths.is_empty() {
+            return None;
+        }
+
+        let index = self.rng.gen_range(0..self.paths.len());
+        let (assertions, path) = self.paths.swap_remove(index);
+        path.state.constraints.restore(&assertions);
+        Some(path)
+    }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    fn clear(&mut self) {
+        self.paths.clear();
+    }
+}
+
+/// Score of a path for [`PriorityPathSelection`], higher scores are explored first.
+pub type PathScore = Box<dyn Fn(&LLVMState) -> i64>;
+
+/// Explores the saved path with the highest score first.
+///
+/// The score is computed by a user provided heuristic when the path is saved, e.g. to prefer
+/// paths closer to an uncovered block or with a shallower call stack. Paths with the same score
+/// are explored newest first, so a constant score gives depth-first search.
+pub struct PriorityPathSelection {
+    score: PathScore,
+    paths: BinaryHeap<ScoredPath>,
+
+    /// Number of saved paths, used to order paths with the same score.
+    saved: usize,
+}
+
+/// Saved path together with the constraints to restore when it is resumed.
+struct ScoredPath {
+    score: i64,
+    order: usize,
+    assertions: Vec<DExpr>,
+    path: Path,
+}
+
+impl PartialEq for ScoredPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredPath {}
+
+impl PartialOrd for ScoredPath {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredPath {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.score, self.order).cmp(&(other.score, other.order))
+    }
+}
+
+impl PriorityPathSelection {
+    /// Creates new without any stored paths, ordering paths by `score`.
+    pub fn new(score: impl Fn(&LLVMState) -> i64 + 'static) -> Self {
+        Self {
+            score: Box::new(score),
+            paths: BinaryHeap::new(),
+            saved: 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for PriorityPathSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriorityPathSelection")
+            .field("paths", &self.paths.len())
+            .finish()
+    }
+}
+
+impl PathSelection for PriorityPathSelection {
+    fn save_path(&mut self, path: Path) {
+        let constraints = &path.state.constraints;
+        let assertions = constraints.assertions();
+        // Constraints added by the current path after this must not end up in the outermost
+        // scope, which can not be removed when another path is restored.
+        constraints.push();
+
+        self.saved += 1;
+        self.paths.push(ScoredPath {
+            score: (self.score)(&path.state),
+            order: self.saved,
+            assertions,
+            path,
+        });
+    }
+
+    fn get_path(&mut self) -> Option<Path> {
+        let scored = self.paths.pop()?;
+        scored.path.state.constraints.restore(&scored.assertions);
+        Some(scored.path)
+    }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    fn clear(&mut self) {
+        self.paths.clear();
+    }
 }
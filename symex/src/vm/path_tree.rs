@@ -0,0 +1,182 @@
+//! Exploration tree.
+//!
+//! Keeps track of how paths are forked from each other during exploration. The tree can be
+//! exported as a Graphviz DOT graph, which makes it easy to see which branches are responsible
+//! for a path explosion.
+use std::fmt::Write;
+
+use super::{AnalysisError, PathResult};
+
+/// A single path in the exploration tree.
+#[derive(Debug, Clone)]
+pub struct PathNode {
+    /// The path this path was forked from, `None` for the initial path.
+    pub parent: Option<usize>,
+
+    /// Location of the branch point where the path was created.
+    pub location: String,
+
+    /// Constraint asserted when the path starts executing.
+    pub condition: Option<String>,
+
+    /// Outcome of the path, `None` if the path has not finished.
+    pub outcome: Option<String>,
+}
+
+/// Records the branch points and outcomes of all explored paths.
+#[derive(Debug, Clone)]
+pub struct PathTree {
+    nodes: Vec<PathNode>,
+}
+
+impl PathTree {
+    /// Creates a new tree with only the initial path, which has id `0`.
+    pub fn new() -> Self {
+        let root = PathNode {
+            parent: None,
+            location: "entry".to_owned(),
+            condition: None,
+            outcome: None,
+        };
+
+        Self { nodes: vec![root] }
+    }
+
+    /// Add a path forked from `parent` at `location`, returns the id of the new path.
+    pub fn add_path(
+        &mut self,
+        parent: usize,
+        location: String,
+        condition: Option<String>,
+    ) -> usize {
+        self.nodes.push(PathNode {
+            parent: Some(parent),
+            location,
+            condition,
+            outcome: None,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Record the outcome of the path with `id`.
+    pub fn set_outcome(&mut self, id: usize, result: &PathResult) {
+        let outcome = match result {
+            PathResult::Success(_) => "Success".to_owned(),
+            PathResult::Failure(AnalysisError::Panic) => "Panic".to_owned(),
+            PathResult::Failure(AnalysisError::Unreachable) => "Unreachable".to_owned(),
+            PathResult::Failure(AnalysisError::IndexOutOfBounds { index, len }) => {
+                format!("IndexOutOfBounds index = {index}, len = {len}")
+            }
+            PathResult::Failure(AnalysisError::InvalidBranchTarget) => {
+                "InvalidBranchTarget".to_owned()
+            }
+            PathResult::Failure(AnalysisError::OutOfBounds) => "OutOfBounds".to_owned(),
+            PathResult::Failure(AnalysisError::PointerOverflow { index }) => {
+                format!("PointerOverflow index = {index}")
+            }
+            PathResult::Failure(AnalysisError::UnsizedType(ty)) => format!("UnsizedType {ty}"),
+            PathResult::Failure(AnalysisError::TooManyAddressResolutions { max }) => {
+                format!("TooManyAddressResolutions max = {max}")
+            }
+            PathResult::Failure(AnalysisError::NonTermination { function, block }) => {
+                format!("NonTermination {function} {block}")
+            }
+            PathResult::Failure(AnalysisError::MisalignedAccess { align }) => {
+                format!("MisalignedAccess align = {align}")
+            }
+            PathResult::Failure(AnalysisError::CallDepthExceeded { max }) => {
+                format!("CallDepthExceeded max = {max}")
+            }
+            PathResult::Failure(AnalysisError::IterationCountExceeded { max }) => {
+                format!("IterationCountExceeded max = {max}")
+            }
+            PathResult::Failure(AnalysisError::UseAfterFree) => "UseAfterFree".to_owned(),
+            PathResult::Failure(AnalysisError::InvalidFree) => "InvalidFree".to_owned(),
+            PathResult::Failure(AnalysisError::AllocationTooLarge { max }) => {
+                format!("AllocationTooLarge max = {max}")
+            }
+            PathResult::Failure(AnalysisError::AssertionFailed) => "AssertionFailed".to_owned(),
+            PathResult::AssumptionUnsat => "AssumptionUnsat".to_owned(),
+            PathResult::Suppress => "Suppressed".to_owned(),
+        };
+
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.outcome = Some(outcome);
+        }
+    }
+
+    /// All the paths in the tree, indexed by their id.
+    pub fn nodes(&self) -> &[PathNode] {
+        &self.nodes
+    }
+
+    /// Generate a Graphviz DOT graph of the tree.
+    ///
+    /// Each path is a node labeled with its outcome, and each edge is labeled with the location
+    /// and condition of the branch point that created the child path.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph paths {\n    node [shape=box];\n");
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            let outcome = node.outcome.as_deref().unwrap_or("unfinished");
+            writeln!(
+                dot,
+                "    p{id} [label=\"path {id}\\n{}\"];",
+                escape(outcome)
+            )
+            .unwrap();
+        }
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            let Some(parent) = node.parent else {
+                continue;
+            };
+
+            let mut label = escape(&node.location);
+            if let Some(condition) = &node.condition {
+                label.push_str("\\n");
+                label.push_str(&escape(condition));
+            }
+            writeln!(dot, "    p{parent} -> p{id} [label=\"{label}\"];").unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Default for PathTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escape a string so it can be used inside a quoted DOT label.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_contains_paths_and_edges() {
+        let mut tree = PathTree::new();
+        let child = tree.add_path(
+            0,
+            "main (test.rs:3)".to_owned(),
+            Some("(= x \"a\")".to_owned()),
+        );
+        tree.set_outcome(0, &PathResult::Success(None));
+        tree.set_outcome(child, &PathResult::Failure(AnalysisError::Panic));
+
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph paths {"));
+        assert!(dot.contains("p0 [label=\"path 0\\nSuccess\"];"));
+        assert!(dot.contains("p1 [label=\"path 1\\nPanic\"];"));
+        assert!(dot.contains("p0 -> p1 [label=\"main (test.rs:3)\\n(= x \\\"a\\\")\"];"));
+    }
+}
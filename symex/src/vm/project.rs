@@ -1,10 +1,22 @@
-use std::{ffi::CStr, path::Path};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::CStr,
+    path::{Path, PathBuf},
+};
 
-use llvm_ir::{Function, Module};
+use llvm_ir::{
+    instruction::Instruction, DebugStructure, Function, Global, GlobalAlias, GlobalVariable,
+    Module, StructureType, Type, Value,
+};
 use rustc_demangle::demangle;
 use tracing::debug;
 
-use crate::vm::Result;
+use crate::{
+    memory::BITS_IN_BYTE,
+    util::ExpressionType,
+    vm::{LLVMExecutorError, Result},
+};
 
 pub enum ProjectError {
     InvalidModule,
@@ -15,6 +27,7 @@ pub enum ProjectError {
 }
 
 use super::{
+    bit_size,
     hooks::{Hook, Hooks},
     is_intrinsic, Intrinsic, Intrinsics,
 };
@@ -37,6 +50,16 @@ pub enum Overriden {
     Hook(Hook),
 }
 
+/// Functions calling a function, found by [`Project::callers_of`].
+#[derive(Debug, Clone, Default)]
+pub struct Callers {
+    /// Functions that call the function directly or through an alias.
+    pub direct: Vec<Function>,
+
+    /// Functions with calls through function pointers, these may or may not call the function.
+    pub unresolved: Vec<Function>,
+}
+
 pub struct Project {
     /// All [Module]s.
     pub module: Module,
@@ -53,6 +76,12 @@ pub struct Project {
 
     /// LLVM Instrinsics.
     intrinsics: Intrinsics,
+
+    /// Cached sizes in bits of aggregate types.
+    size_cache: RefCell<HashMap<Type, u32>>,
+
+    /// Function each alias of a function refers to.
+    aliases: HashMap<GlobalAlias, Function>,
 }
 
 impl Project {
@@ -65,37 +94,274 @@ impl Project {
         // let ptr_size = modules[0].data_layout.alignments.ptr_alignment(0).size;
         let ptr_size = 64;
 
+        let aliases = module
+            .aliases()
+            .filter_map(|alias| {
+                let function = alias_target(&alias)?;
+                Some((alias, function))
+            })
+            .collect();
+
         let project = Project {
             module,
             ptr_size,
             default_alignment: 1,
             hooks: Hooks::new(),
             intrinsics: Intrinsics::new_with_defaults(),
+            size_cache: RefCell::new(HashMap::new()),
+            aliases,
         };
 
         Ok(project)
     }
 
+    /// Find the function to start the analysis in.
+    ///
+    /// The name is matched against the mangled and demangled names. `cargo-symex` prefixes the
+    /// name with the module, so if nothing matches the last path segment is also tried as the raw
+    /// symbol name. This finds `#[no_mangle]` and `extern "C"` functions, which keep their name.
+    ///
+    /// If several functions match, e.g. when two versions of a crate are linked in, an
+    /// [`LLVMExecutorError::AmbiguousFunction`] lists the candidates. Use
+    /// [`find_entry_function_in`](Self::find_entry_function_in) to pick one of them.
     pub fn find_entry_function(&self, name: &str) -> Result<Function> {
-        let mut return_function = None;
+        self.find_entry_function_in(name, None)
+    }
+
+    /// Same as [`find_entry_function`](Self::find_entry_function), but if `module` is given only
+    /// functions defined in that source file are considered.
+    ///
+    /// The source file is taken from the debug info, and `module` matches if it is the end of the
+    /// path, e.g. `src/lib.rs` or `foo-1.0.0/src/lib.rs`.
+    pub fn find_entry_function_in(&self, name: &str, module: Option<&str>) -> Result<Function> {
+        let in_module = |function: &Function| match module {
+            Some(module) => function_module(function).map_or(false, |path| path.ends_with(module)),
+            None => true,
+        };
+
+        let function = self.find_function_by(name, |function, fn_name| {
+            in_module(function) && symbol_matches(fn_name, name)
+        })?;
+        if let Some(function) = function {
+            return Ok(function);
+        }
+
+        let raw_name = name.rsplit("::").next().unwrap_or(name);
+        let function = self.find_function_by(name, |function, fn_name| {
+            in_module(function) && fn_name == raw_name
+        })?;
+        if let Some(function) = function {
+            return Ok(function);
+        }
+
+        // The function can also be exported under another name through an alias.
+        let alias = self.aliases.iter().find(|(alias, function)| {
+            let alias_name = alias.name().to_string_lossy();
+            in_module(function) && (symbol_matches(&alias_name, name) || alias_name == raw_name)
+        });
+        match alias {
+            Some((_, function)) => Ok(function.clone()),
+            None => Err(LLVMExecutorError::FunctionNotFound(name.to_owned())),
+        }
+    }
+
+    /// Get the function an alias refers to, if it is an alias of a function.
+    pub fn resolve_alias(&self, alias: &GlobalAlias) -> Option<Function> {
+        self.aliases.get(alias).cloned()
+    }
+
+    /// All aliases of functions together with the function they refer to.
+    pub fn function_aliases(&self) -> impl Iterator<Item = (&GlobalAlias, &Function)> {
+        self.aliases.iter()
+    }
 
+    /// Find a global variable by its mangled or demangled name.
+    pub fn find_global(&self, name: &str) -> Option<GlobalVariable> {
+        self.module
+            .globals()
+            .find(|global| symbol_matches(&global.name().to_string_lossy(), name))
+    }
+
+    /// Find all calls to `symex_lib::reachable` in functions that can be called from `entry`,
+    /// together with the function containing the call.
+    ///
+    /// Only direct calls are followed, so markers in functions only called through a function
+    /// pointer are not included.
+    pub fn reachable_markers(&self, entry: &Function) -> Vec<(Function, Instruction)> {
+        let mut markers = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = vec![entry.clone()];
+        while let Some(function) = queue.pop() {
+            if !visited.insert(function.clone()) {
+                continue;
+            }
+
+            for block in function.basic_blocks() {
+                for instruction in block.instructions() {
+                    let Some(called) = called_function(&instruction) else {
+                        continue;
+                    };
+                    if symbol_matches(&called.name().to_string_lossy(), "symex_lib::reachable") {
+                        markers.push((function.clone(), instruction));
+                    } else {
+                        queue.push(called);
+                    }
+                }
+            }
+        }
+        markers
+    }
+
+    /// Find all functions that call the function `name`, either mangled or demangled.
+    ///
+    /// Calls through an alias of the function count as direct calls. Calls through a function
+    /// pointer cannot be resolved without executing the code, so every function with such a call
+    /// is listed in [`Callers::unresolved`] instead.
+    pub fn callers_of(&self, name: &str) -> Callers {
+        let mut callers = Callers::default();
         for function in self.module.functions() {
-            let fn_name = function.name();
-            let fn_name = fn_name.to_str().unwrap();
-            let demangled = demangle(fn_name);
-
-            let demanged_no_hash = format!("{:#}", demangled);
-            if fn_name == name || demangled.to_string() == name || demanged_no_hash == name {
-                if return_function.is_some() {
-                    panic!("Multiple functions with name {} exist", name);
+            let mut calls = false;
+            let mut unresolved = false;
+            for block in function.basic_blocks() {
+                for instruction in block.instructions() {
+                    let called_value = match &instruction {
+                        Instruction::Call(call) => call.called_value(),
+                        Instruction::Invoke(invoke) => invoke.called_value(),
+                        _ => continue,
+                    };
+                    match called_value {
+                        Value::Function(called) => {
+                            calls |= symbol_matches(&called.name().to_string_lossy(), name);
+                        }
+                        Value::Global(Global::Alias(alias)) => {
+                            let alias_matches =
+                                symbol_matches(&alias.name().to_string_lossy(), name);
+                            let target_matches = self.resolve_alias(&alias).map_or(false, |f| {
+                                symbol_matches(&f.name().to_string_lossy(), name)
+                            });
+                            calls |= alias_matches || target_matches;
+                        }
+                        // Inline assembly never calls into the IR.
+                        Value::InlineAsm => {}
+                        _ => unresolved = true,
+                    }
                 }
-                return_function = Some(function);
             }
+
+            if calls {
+                callers.direct.push(function.clone());
+            }
+            if unresolved {
+                callers.unresolved.push(function);
+            }
+        }
+        callers
+    }
+
+    /// Find the only function that `matches`, returns an error listing the candidates if there
+    /// are several.
+    fn find_function_by(
+        &self,
+        name: &str,
+        matches: impl Fn(&Function, &str) -> bool,
+    ) -> Result<Option<Function>> {
+        let mut functions: Vec<Function> = self
+            .module
+            .functions()
+            .filter(|function| matches(function, function.name().to_str().unwrap()))
+            .collect();
+
+        if functions.len() > 1 {
+            let candidates = functions
+                .iter()
+                .map(|function| {
+                    let symbol = function.name().to_string_lossy();
+                    match function_module(function) {
+                        Some(path) => format!("{symbol} in {}", path.display()),
+                        None => symbol.into_owned(),
+                    }
+                })
+                .collect();
+            return Err(LLVMExecutorError::AmbiguousFunction {
+                name: name.to_owned(),
+                candidates,
+            });
+        }
+
+        Ok(functions.pop())
+    }
+
+    /// Calculates the size of the type in bits.
+    ///
+    /// Same as [`bit_size`] but the sizes of aggregate types are cached, since sizes are needed
+    /// for every allocation, load and store. Types are uniqued by LLVM so they can be used as keys
+    /// directly. A structure can only contain itself through a pointer, so this always terminates.
+    ///
+    /// Structures are laid out as given by the data layout of the module, so their size includes
+    /// the padding between the fields and at the end, see [`Self::field_offsets`].
+    pub fn bit_size(&self, ty: &Type) -> Result<u32> {
+        if !matches!(ty, Type::Vector(_) | Type::Array(_) | Type::Structure(_)) {
+            return bit_size(ty, self.ptr_size, &self.module.data_layout());
+        }
+
+        if let Some(size) = self.size_cache.borrow().get(ty) {
+            return Ok(*size);
         }
 
-        match return_function {
-            Some(function) => Ok(function),
-            None => panic!("Function not found"),
+        let size = match ty {
+            Type::Vector(t) => self.bit_size(&t.element_type())? * t.num_elements(),
+            Type::Array(t) => self.bit_size(&t.element_type())? * t.num_elements() as u32,
+            Type::Structure(t) => {
+                // The layout is only known for sized structures, so check the fields first.
+                for field in t.fields() {
+                    self.bit_size(&field)?;
+                }
+                t.abi_size(&self.module.data_layout()) as u32 * BITS_IN_BYTE
+            }
+            _ => unreachable!(),
+        };
+
+        self.size_cache.borrow_mut().insert(ty.clone(), size);
+        Ok(size)
+    }
+
+    /// Offsets in bits of the fields of a structure, as given by the data layout of the module.
+    ///
+    /// Fields are aligned, so there can be padding between them. Fails for structures that
+    /// contain a type without a size.
+    pub fn field_offsets(&self, ty: &StructureType) -> Result<Vec<u32>> {
+        self.bit_size(&Type::Structure(ty.clone()))?;
+        let offsets = ty.field_offsets(&self.module.data_layout());
+        Ok(offsets
+            .into_iter()
+            .map(|offset| offset as u32 * BITS_IN_BYTE)
+            .collect())
+    }
+
+    /// Use `intrinsic` for the intrinsics starting with `name`, instead of the built-in version.
+    ///
+    /// This makes it possible to model intrinsics that are not supported yet, or to replace the
+    /// default model of one.
+    pub fn override_intrinsic(&mut self, name: impl Into<String>, intrinsic: Intrinsic) {
+        self.intrinsics.add_override(name, intrinsic);
+    }
+
+    /// Call `hook` instead of the function `name`.
+    ///
+    /// The name is matched against the mangled name and the demangled name, both with and without
+    /// the hash, e.g. `my_crate::expensive_fn`. Hooks take priority over the functions in the IR,
+    /// so this can replace a function that is defined as well as one that is only declared. A
+    /// hook added with the same name as an existing one replaces it.
+    pub fn add_hook(&mut self, name: impl Into<String>, hook: Hook) {
+        self.hooks.add(name, hook);
+    }
+
+    /// Set if the `core::fmt` formatting functions should be replaced by stubs, see
+    /// [`Hooks::add_fmt_hooks`]. Enabled by default.
+    pub fn set_fmt_hooks(&mut self, enabled: bool) {
+        match enabled {
+            true => self.hooks.add_fmt_hooks(),
+            false => self.hooks.remove_fmt_hooks(),
         }
     }
 
@@ -155,3 +421,141 @@ impl Project {
         None
     }
 }
+
+/// Follow an alias, possibly through other aliases, to the function it refers to.
+fn alias_target(alias: &GlobalAlias) -> Option<Function> {
+    let mut aliasee = alias.aliasee();
+    loop {
+        match aliasee {
+            Value::Function(function) => return Some(function),
+            // Aliases cannot form cycles, so this always terminates.
+            Value::Global(Global::Alias(alias)) => aliasee = alias.aliasee(),
+            _ => return None,
+        }
+    }
+}
+
+/// Source file a function is defined in, if it has debug info.
+fn function_module(function: &Function) -> Option<PathBuf> {
+    let location = function.debug_location()?;
+    let filename = Path::new(location.filename.to_str().ok()?);
+    match &location.directory {
+        Some(directory) => Some(Path::new(directory.to_str().ok()?).join(filename)),
+        None => Some(filename.to_owned()),
+    }
+}
+
+/// Check if the symbol `symbol` has the name `name`, either mangled or demangled with or without
+/// the hash.
+fn symbol_matches(symbol: &str, name: &str) -> bool {
+    let demangled = demangle(symbol);
+    let demangled_no_hash = format!("{:#}", demangled);
+    symbol == name || demangled.to_string() == name || demangled_no_hash == name
+}
+
+/// Function called directly by a call or invoke instruction.
+fn called_function(instruction: &Instruction) -> Option<Function> {
+    let called_value = match instruction {
+        Instruction::Call(call) => call.called_value(),
+        Instruction::Invoke(invoke) => invoke.called_value(),
+        _ => return None,
+    };
+    match called_value {
+        Value::Function(function) => Some(function),
+        _ => None,
+    }
+}
+
+/// Convert an IR type to the simplified type used when presenting values.
+pub fn type_to_expr_type(ty: &Type, project: &Project) -> ExpressionType {
+    match ty {
+        Type::Integer(t) => ExpressionType::Integer(t.bits() as usize),
+        Type::Float(t) => ExpressionType::Float(t.bits() as usize),
+        // Pointers are opaque, so the type of the pointed to value is unknown.
+        Type::Pointer(_) => {
+            ExpressionType::Pointer(project.ptr_size as usize, Box::new(ExpressionType::Unknown))
+        }
+        Type::Vector(t) => ExpressionType::Array(
+            Box::new(type_to_expr_type(&t.element_type(), project)),
+            t.num_elements() as usize,
+        ),
+        Type::Array(t) => ExpressionType::Array(
+            Box::new(type_to_expr_type(&t.element_type(), project)),
+            t.num_elements() as usize,
+        ),
+        Type::Structure(t) => ExpressionType::Struct(
+            t.fields()
+                .iter()
+                .zip(field_padding(t, project))
+                .map(|(field, padding)| padded(type_to_expr_type(field, project), padding))
+                .collect(),
+        ),
+        _ => ExpressionType::Unknown,
+    }
+}
+
+/// Number of padding bits after each field of a structure, as given by the data layout.
+///
+/// There is no padding if the structure has no size.
+fn field_padding(t: &StructureType, project: &Project) -> Vec<usize> {
+    let fields = t.fields();
+    let size = project.bit_size(&Type::Structure(t.clone()));
+    let (Ok(size), Ok(offsets)) = (size, project.field_offsets(t)) else {
+        return vec![0; fields.len()];
+    };
+
+    let ends = offsets.iter().skip(1).copied().chain([size]);
+    fields
+        .iter()
+        .zip(offsets.iter().zip(ends))
+        .map(|(field, (offset, end))| {
+            let field_size = project.bit_size(field).unwrap_or_default();
+            (end - offset - field_size) as usize
+        })
+        .collect()
+}
+
+/// Wrap a field in [`ExpressionType::Padded`] if there is padding after it.
+fn padded(ty: ExpressionType, padding: usize) -> ExpressionType {
+    match padding {
+        0 => ty,
+        padding => ExpressionType::Padded(Box::new(ty), padding),
+    }
+}
+
+/// Convert a structure type to an [`ExpressionType::NamedStruct`] using the source names from
+/// the debug info.
+///
+/// Fields are matched to the debug info by their offset, fields without a match are left unnamed.
+/// Returns `None` if the type is not a structure or no field could be matched.
+pub fn named_struct_to_expr_type(
+    ty: &Type,
+    structure: &DebugStructure,
+    project: &Project,
+) -> Option<ExpressionType> {
+    let Type::Structure(t) = ty else {
+        return None;
+    };
+
+    // The debug info uses the ABI offsets, so the padding from the data layout must be included.
+    let offsets = project.field_offsets(t).ok()?;
+    let padding = field_padding(t, project);
+
+    let mut fields = Vec::new();
+    for ((field, offset), padding) in t.fields().iter().zip(offsets).zip(padding) {
+        let field_ty = padded(type_to_expr_type(field, project), padding);
+
+        let name = structure
+            .fields
+            .iter()
+            .find(|f| f.offset_in_bits == offset as u64)
+            .map(|f| f.name.clone());
+        fields.push((name, field_ty));
+    }
+
+    if fields.iter().all(|(name, _)| name.is_none()) {
+        return None;
+    }
+
+    Some(ExpressionType::NamedStruct(structure.name.clone(), fields))
+}
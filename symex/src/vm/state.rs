@@ -1,22 +1,28 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use llvm_ir::{
     constant::{Constant, Expression},
     instruction::LLVMIntPredicate,
-    instruction::{BasicBlock, Instruction},
-    Function, Global, GlobalVariable, Value,
+    instruction::{self, BasicBlock, Instruction},
+    FloatingPointType, Function, Global, GlobalVariable, Type, Value,
 };
 use tracing::{debug, trace, warn};
 
-use super::{binop, bit_size, project::Project};
-use crate::vm::{executor::convert_to_map, LLVMExecutorError};
+use super::{binop, project::Project, BranchDecision, CallEvent};
+use crate::vm::{
+    executor::{byte_offset, byte_size, convert_to_map, fp_to_int, int_to_fp, struct_from_fields},
+    LLVMExecutorError,
+};
 use crate::{
-    memory::ObjectMemory,
+    memory::{ObjectMemory, BITS_IN_BYTE},
     smt::{DContext, DExpr, DSolver},
-    util::Variable,
+    util::{BlockVisits, ExpressionType, Variable},
     vm::Result,
 };
 
+/// Maximum number of bytes shown for a pointed to value of unknown type.
+const MAX_POINTEE_BYTES: u64 = 16;
+
 /// Stack frame keeps track of information related to a specific stack frame.
 #[derive(Debug, Clone)]
 pub struct StackFrame {
@@ -27,6 +33,22 @@ pub struct StackFrame {
     registers: HashMap<Value, DExpr>,
 
     location: Location,
+
+    /// Header of the loop being summarized, see [`VM::loop_summary`](super::VM::loop_summary).
+    summarized_loop: Option<BasicBlock>,
+
+    /// State on the last branch to each basic block, see
+    /// [`VM::detect_non_termination`](super::VM::detect_non_termination).
+    loop_snapshots: HashMap<BasicBlock, LoopSnapshot>,
+}
+
+/// State of a stack frame and the memory when branching to a basic block.
+#[derive(Debug, Clone)]
+struct LoopSnapshot {
+    previous_block: BasicBlock,
+    registers: HashMap<Value, DExpr>,
+    memory: ObjectMemory,
+    assertions: usize,
 }
 
 impl StackFrame {
@@ -39,6 +61,8 @@ impl StackFrame {
             function,
             registers: HashMap::new(),
             location: Location::new(basic_block)?,
+            summarized_loop: None,
+            loop_snapshots: HashMap::new(),
         })
     }
 
@@ -60,6 +84,8 @@ impl StackFrame {
             function,
             registers,
             location: Location::new(basic_block)?,
+            summarized_loop: None,
+            loop_snapshots: HashMap::new(),
         })
     }
 
@@ -75,6 +101,10 @@ impl StackFrame {
         self.registers.get(register)
     }
 
+    pub fn remove_register(&mut self, register: &Value) {
+        self.registers.remove(register);
+    }
+
     /// Changes the location to another basic block.
     pub fn set_basic_block(&mut self, bb: BasicBlock) -> Result<()> {
         self.location = Location::new_jump(self.location.clone(), bb)?;
@@ -85,6 +115,10 @@ impl StackFrame {
         self.location.instr.as_ref()
     }
 
+    pub fn current_block(&self) -> &BasicBlock {
+        &self.location.block
+    }
+
     pub fn previous_block(&self) -> Option<&BasicBlock> {
         self.location.previous_block.as_ref()
     }
@@ -93,6 +127,14 @@ impl StackFrame {
     pub fn increase_pc(&mut self) {
         self.location.increase_pc();
     }
+
+    pub fn summarized_loop(&self) -> Option<&BasicBlock> {
+        self.summarized_loop.as_ref()
+    }
+
+    pub fn set_summarized_loop(&mut self, header: Option<BasicBlock>) {
+        self.summarized_loop = header;
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +207,33 @@ pub struct LLVMState {
     pub global_lookup_rev: HashMap<u64, Value>,
     pub global_lookup: HashMap<Value, u64>,
     pub init_global: HashSet<u64>,
+
+    /// Addresses of basic blocks which have had their address taken with `blockaddress`.
+    pub block_lookup: HashMap<BasicBlock, u64>,
+    pub block_lookup_rev: HashMap<u64, BasicBlock>,
+
+    /// Functions to call, in order, when the call stack is empty. Used to run global
+    /// constructors before, and global destructors after, the entry function.
+    pub(crate) pending_functions: VecDeque<Function>,
+
+    /// Value returned by the entry function, kept while global destructors run.
+    pub(crate) entry_return: Option<DExpr>,
+
+    /// Constraints of the symbolic branches taken on this path, in order. Only recorded when
+    /// [`VM::log_constraints`](super::VM::log_constraints) is set.
+    pub branch_constraints: Vec<BranchDecision>,
+
+    /// Functions entered and exited on this path, in order. Only recorded when
+    /// [`VM::log_calls`](super::VM::log_calls) is set.
+    pub call_log: Vec<CallEvent>,
+
+    /// Number of times each basic block was branched to on this path. Only recorded when
+    /// [`VM::log_block_visits`](super::VM::log_block_visits) is set.
+    pub block_visits: HashMap<BasicBlock, usize>,
+
+    /// Number of instructions executed on this path, see
+    /// [`VM::max_iter_count`](super::VM::max_iter_count).
+    pub instructions: u64,
 }
 
 impl std::fmt::Debug for LLVMState {
@@ -198,6 +267,14 @@ impl LLVMState {
             global_lookup: HashMap::new(),
             global_lookup_rev: HashMap::new(),
             init_global: HashSet::new(),
+            block_lookup: HashMap::new(),
+            block_lookup_rev: HashMap::new(),
+            pending_functions: VecDeque::new(),
+            entry_return: None,
+            branch_constraints: Vec::new(),
+            call_log: Vec::new(),
+            block_visits: HashMap::new(),
+            instructions: 0,
         })
     }
 
@@ -213,6 +290,126 @@ impl LLVMState {
             .ok_or(LLVMExecutorError::NoStackFrame)
     }
 
+    /// The constraints of the path so far as an SMT-LIB v2 script, including the declarations of
+    /// all symbols, e.g. to replay the path in another solver. See [`DSolver::to_smt2`].
+    pub fn dump_smtlib(&self) -> String {
+        self.constraints.to_smt2()
+    }
+
+    /// Get the address of a basic block, the block is given an address on first use.
+    pub fn block_address(&mut self, block: &BasicBlock) -> Result<DExpr> {
+        let address = match self.block_lookup.get(block) {
+            Some(address) => *address,
+            None => {
+                // Like functions the block only needs an address, nothing is stored there.
+                let address = self.memory.allocate(self.project.ptr_size as u64, 4)?;
+                self.block_lookup.insert(block.clone(), address);
+                self.block_lookup_rev.insert(address, block.clone());
+                address
+            }
+        };
+        Ok(self.ctx.from_u64(address, self.project.ptr_size))
+    }
+
+    /// Basic blocks branched to more than once on this path, the most visited first.
+    ///
+    /// These are the loop headers, together with the number of iterations of the loop.
+    pub fn repeated_blocks(&self) -> Vec<BlockVisits> {
+        let mut repeated: Vec<_> = self
+            .block_visits
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .map(|(block, count)| BlockVisits {
+                function: block.parent().name().to_string_lossy().into_owned(),
+                block: block_label(block),
+                count: *count,
+            })
+            .collect();
+        repeated.sort_by(|a, b| {
+            (b.count, &a.function, &a.block).cmp(&(a.count, &b.function, &b.block))
+        });
+        repeated
+    }
+
+    /// Check if branching to `target` in the current frame repeats the state of the previous
+    /// branch to it, then the loop with `target` as header never terminates.
+    ///
+    /// The registers of the frame, the memory and the number of asserted constraints must be the
+    /// same and both branches must come from the same block. Otherwise the current state is saved
+    /// for the next branch to `target`.
+    pub fn repeats_loop_state(&mut self, target: &BasicBlock) -> Result<bool> {
+        let assertions = self.constraints.num_assertions();
+        let frame = self
+            .stack_frames
+            .last_mut()
+            .ok_or(LLVMExecutorError::NoStackFrame)?;
+        let previous_block = frame.current_block().clone();
+
+        let repeated = frame.loop_snapshots.get(target).map_or(false, |snapshot| {
+            snapshot.previous_block == previous_block
+                && snapshot.assertions == assertions
+                && snapshot.registers == frame.registers
+                && snapshot.memory.same_contents(&self.memory)
+        });
+        if !repeated {
+            let snapshot = LoopSnapshot {
+                previous_block,
+                registers: frame.registers.clone(),
+                memory: self.memory.clone(),
+                assertions,
+            };
+            frame.loop_snapshots.insert(target.clone(), snapshot);
+        }
+
+        Ok(repeated)
+    }
+
+    /// Get the basic block at `address`, if the address belongs to a basic block.
+    pub fn block_at_address(&self, address: u64) -> Option<&BasicBlock> {
+        self.block_lookup_rev.get(&address)
+    }
+
+    /// Solve the value of type `pointee` at `address`, if the address is inside a known allocation.
+    ///
+    /// When the type is unknown, e.g. for opaque pointers, the rest of the allocation is shown as
+    /// bytes instead.
+    pub fn solve_pointee(
+        &self,
+        address: u64,
+        pointee: &ExpressionType,
+    ) -> Result<Option<Variable>> {
+        let Some((base, size)) = self.memory.allocation_containing(address) else {
+            return Ok(None);
+        };
+        let available = size - (address - base) * BITS_IN_BYTE as u64;
+
+        let (bits, ty) = match pointee.size_in_bits() {
+            Some(bits) if bits as u64 <= available => (bits as u64, pointee.clone()),
+            Some(_) => return Ok(None),
+            None => {
+                let bytes = (available / BITS_IN_BYTE as u64).min(MAX_POINTEE_BYTES);
+                let ty = ExpressionType::Array(
+                    Box::new(ExpressionType::Integer(BITS_IN_BYTE as usize)),
+                    bytes as usize,
+                );
+                (bytes * BITS_IN_BYTE as u64, ty)
+            }
+        };
+        if bits == 0 {
+            return Ok(None);
+        }
+
+        let address = self.ctx.from_u64(address, self.project.ptr_size);
+        let value = self.memory.read(&address, bits as u32)?;
+        let value = self.constraints.get_value(&value)?;
+        Ok(Some(Variable {
+            name: None,
+            value,
+            ty,
+            pointee: None,
+        }))
+    }
+
     /// Retrieves or creates an [Expr] from an [Operand] or [Constant].
     pub fn get_expr(&mut self, value: &Value) -> Result<DExpr> {
         trace!("Get expression: {value:?} -> {value}");
@@ -223,7 +420,10 @@ impl LLVMState {
 /// Convert an operand to an expression.
 pub fn operand_to_expr(state: &mut LLVMState, value: &Value) -> Result<DExpr> {
     match value {
-        Value::Function(_) | Value::Global(_) | Value::Constant(_) => const_to_expr(state, value),
+        Value::Function(_) | Value::Global(_) | Value::Constant(_) => {
+            let expr = const_to_expr_zero_size(state, value)?;
+            Ok(expr.unwrap_or_else(|| zero_sized_placeholder(state.ctx)))
+        }
         Value::Instruction(_) | Value::Argument(_) => state
             .current_frame_mut()?
             .get_register(&value)
@@ -235,23 +435,39 @@ pub fn operand_to_expr(state: &mut LLVMState, value: &Value) -> Result<DExpr> {
     }
 }
 
-pub(crate) fn init_gv(state: &mut LLVMState, gv: &GlobalVariable, addr: u64) {
+/// Placeholder for values of zero sized types, e.g. `()` or `{}`.
+///
+/// Expressions cannot be zero bits wide, so a single zero bit stands in for them. It is never
+/// written to or read from memory, and is skipped when inserted into an aggregate.
+pub(crate) fn zero_sized_placeholder(ctx: &DContext) -> DExpr {
+    ctx.zero(1)
+}
+
+pub(crate) fn init_gv(state: &mut LLVMState, gv: &GlobalVariable, addr: u64) -> Result<()> {
     if state.init_global.contains(&addr) {
-        return;
+        return Ok(());
     }
 
     if let Some(initializer) = gv.initializer() {
-        match state.get_expr(&initializer) {
-            Ok(value) => {
+        match const_to_expr_zero_size(state, &initializer) {
+            Ok(Some(value)) => {
                 let addre = state.ctx.from_u64(addr, state.project.ptr_size);
                 state.memory.write(&addre, value).unwrap();
                 state.init_global.insert(addr);
             }
+            // Nothing to write for zero sized globals.
+            Ok(None) => {
+                state.init_global.insert(addr);
+            }
+            // The initializer cannot be written without a size, e.g. for an opaque structure.
+            Err(err @ LLVMExecutorError::NoSize(_)) => return Err(err),
             Err(err) => {
                 warn!("Error initializing global: {:?}", err);
             }
         }
     }
+
+    Ok(())
 }
 
 /// Convert a constant to an expression.
@@ -279,7 +495,7 @@ pub(crate) fn const_to_expr_zero_size(
             };
 
             if let Value::Global(Global::Variable(global_variable)) = value {
-                init_gv(state, global_variable, global_address);
+                init_gv(state, global_variable, global_address)?;
             }
 
             let expr = state.ctx.from_u64(global_address, state.project.ptr_size);
@@ -306,23 +522,24 @@ pub(crate) fn const_to_expr_zero_size(
         // Not sure if the generated LLVM does not allow for these errors to happen, but if it does
         // those kind of errors are covered.
         Constant::Undef(_) | Constant::Poison(_) => {
-            let size = bit_size(&ty, state.project.ptr_size)?;
+            let size = state.project.bit_size(&ty)?;
 
             let e = match size {
                 0 => None,
-                n => {
-                    let name = format!("undef_{}", rand::random::<u32>());
-                    Some(state.ctx.unconstrained(n as u32, &name))
-                }
+                n => Some(state.ctx.fresh_unconstrained(n as u32, "undef")),
             };
             Ok(e)
         }
 
         Constant::TargetNone(_) => todo!("TargetNone"),
 
+        Constant::BlockAddress(block_address) => {
+            state.block_address(&block_address.basic_block()).map(Some)
+        }
+
         // Both null pointers and the aggregate of zeroes are initialized to zero.
         Constant::PointerNull(_) | Constant::AggregateZero(_) => {
-            let size = bit_size(&ty, state.project.ptr_size)?;
+            let size = state.project.bit_size(&ty)?;
             Ok(match size {
                 0 => None,
                 n => Some(state.ctx.zero(n as u32)),
@@ -330,11 +547,23 @@ pub(crate) fn const_to_expr_zero_size(
         }
 
         Constant::Integer(constant) => {
-            let bits = bit_size(&ty, state.project.ptr_size)?;
+            let bits = state.project.bit_size(&ty)?;
             Ok(Some(state.ctx.from_u64(constant.value(), bits)))
         }
 
-        Constant::Float(_) => todo!("const fp"),
+        Constant::Float(constant) => {
+            let value = constant.value();
+            let (value, bits) = match &ty {
+                Type::Float(FloatingPointType::Float) => ((value as f32).to_bits() as u64, 32),
+                Type::Float(FloatingPointType::Double) => (value.to_bits(), 64),
+                _ => {
+                    return Err(LLVMExecutorError::UnsupportedInstruction(format!(
+                        "floating point constant of type {ty}"
+                    )))
+                }
+            };
+            Ok(Some(state.ctx.from_u64(value, bits)))
+        }
 
         Constant::Array(array) => {
             let elements = array
@@ -354,12 +583,12 @@ pub(crate) fn const_to_expr_zero_size(
         }
 
         Constant::Structure(structure) => {
-            let elements = structure
+            let fields = structure
                 .fields()
-                .filter_map(|constant| const_to_expr_zero_size(state, &constant).transpose())
+                .map(|constant| const_to_expr_zero_size(state, &constant))
                 .collect::<Result<Vec<_>>>()?;
 
-            Ok(elements.into_iter().reduce(|acc, v| v.concat(&acc)))
+            struct_from_fields(state, &structure.ty(), fields)
         }
 
         Constant::Expression(expression) => Some(match expression {
@@ -377,10 +606,10 @@ pub(crate) fn const_to_expr_zero_size(
             }
             Expression::FPTrunc(_) => todo!(),
             Expression::FPExt(_) => todo!(),
-            Expression::FPToUI(_) => todo!(),
-            Expression::FPToSI(_) => todo!(),
-            Expression::UIToFP(_) => todo!(),
-            Expression::SIToFP(_) => todo!(),
+            Expression::FPToUI(i) => fp_to_int(state, i.value(), &i.to_type(), false),
+            Expression::FPToSI(i) => fp_to_int(state, i.value(), &i.to_type(), true),
+            Expression::UIToFP(i) => int_to_fp(state, i.value(), &i.to_type(), false),
+            Expression::SIToFP(i) => int_to_fp(state, i.value(), &i.to_type(), true),
             Expression::PtrToInt(i) => {
                 let operation = |value: DExpr, target_size: u32| value.resize_unsigned(target_size);
                 convert_to_map(state, i.value(), &i.to_type(), operation)
@@ -391,7 +620,7 @@ pub(crate) fn const_to_expr_zero_size(
             }
             Expression::BitCast(i) => const_to_expr(state, &i.value()),
             Expression::AddrSpaceCast(i) => const_to_expr(state, &i.value()),
-            Expression::GetElementPtr(_) => todo!(),
+            Expression::GetElementPtr(i) => const_get_element_ptr(state, i),
             Expression::ICmp(i) => {
                 let f = |lhs: &DExpr, rhs: &DExpr| match i.predicate() {
                     LLVMIntPredicate::LLVMIntEQ => lhs._eq(&rhs),
@@ -427,3 +656,56 @@ pub(crate) fn const_to_expr_zero_size(
 
     e.map(|e| e.map(|e| e.simplify()))
 }
+
+/// Calculate the address of a constant `getelementptr` expression.
+///
+/// All the indices are constants, so unlike the instruction there is no need to check for
+/// overflows.
+fn const_get_element_ptr(state: &mut LLVMState, i: &instruction::GetElementPtr) -> Result<DExpr> {
+    let ptr_size = state.project.ptr_size;
+    let address = const_to_expr(state, &i.address())?;
+
+    let source_element_type = i.source_element_type();
+    let indices = i.indices();
+    let Some(first) = indices.first() else {
+        panic!("getelementptr should always have at least one index");
+    };
+
+    // The first index steps over whole elements of the source element type.
+    let index = const_index_to_expr(state, first)?;
+    let size_bytes = byte_size(&source_element_type, state.project)?;
+    let mut total_offset = index.mul(&state.ctx.from_u64(size_bytes.into(), ptr_size));
+
+    let mut curr_ty = source_element_type;
+    for index in indices.iter().skip(1) {
+        let index = const_index_to_expr(state, index)?;
+        let (offset, ty) = byte_offset(&curr_ty, &index, state.project, state.ctx)?;
+        total_offset = total_offset.add(&offset);
+        curr_ty = ty;
+    }
+
+    Ok(address.add(&total_offset))
+}
+
+/// Convert a constant `getelementptr` index to an expression of the pointer width.
+///
+/// Indices are signed and can have any width.
+fn const_index_to_expr(state: &mut LLVMState, index: &Value) -> Result<DExpr> {
+    let ptr_size = state.project.ptr_size;
+    let index = const_to_expr(state, index)?;
+    Ok(match index.len() < ptr_size {
+        true => index.sign_ext(ptr_size),
+        false => index.resize_unsigned(ptr_size),
+    })
+}
+
+/// Name of a basic block for reports, e.g. `%loop`, or its position in the function if unnamed.
+pub(crate) fn block_label(block: &BasicBlock) -> String {
+    match block.name().to_string_lossy() {
+        name if name.is_empty() => {
+            let position = block.parent().basic_blocks().position(|b| &b == block);
+            format!("block {}", position.unwrap_or_default())
+        }
+        name => format!("%{name}"),
+    }
+}
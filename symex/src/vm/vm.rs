@@ -1,25 +1,294 @@
-use llvm_ir::{Global, GlobalValue, Value};
-use tracing::trace;
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use llvm_ir::{
+    constant::Constant,
+    instruction::{BasicBlock, Instruction},
+    Function, Global, GlobalValue, Type, Value,
+};
+use tracing::{trace, warn};
 
 use crate::{
-    smt::{DContext, DSolver},
-    util::Variable,
-    vm::bit_size,
+    memory::{UninitializedMemory, BITS_IN_BYTE},
+    smt::{DContext, DExpr, DSolver, Solutions, SolverError},
+    util::{ExpressionType, Variable},
 };
 
 use super::{
-    path_selection::{DFSPathSelection, Path},
-    project::Project,
-    state::LLVMState,
-    LLVMExecutor, LLVMExecutorError, PathResult,
+    executor::instruction_location,
+    path_selection::{DFSPathSelection, Path, PathSelection},
+    path_tree::PathTree,
+    project::{named_struct_to_expr_type, type_to_expr_type, Project},
+    state::{zero_sized_placeholder, LLVMState, StackFrame},
+    AnalysisError, LLVMExecutor, LLVMExecutorError, PathResult,
 };
 
+/// Maximum number of bytes read by [`VM::read_cstring`] when looking for the terminator.
+pub const MAX_CSTRING_LEN: usize = 4096;
+
+/// A symbolic branch taken while running with concrete inputs.
+#[derive(Debug, Clone)]
+pub struct BranchDecision {
+    /// Location of the branch.
+    pub location: String,
+
+    /// Constraint that holds for the taken branch.
+    pub condition: DExpr,
+}
+
+/// A function being entered or exited on a path, see [`VM::log_calls`].
+///
+/// Both variants hold the (mangled) name of the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallEvent {
+    Enter(String),
+    Exit(String),
+}
+
+/// Result of running a single path with concrete inputs, see [`VM::run_concrete`].
+#[derive(Debug)]
+pub struct ConcreteRun {
+    /// Result of the path.
+    pub result: PathResult,
+
+    /// State at the end of the path.
+    pub state: LLVMState,
+
+    /// All symbolic branches taken, in order. Together they form the path condition.
+    pub decisions: Vec<BranchDecision>,
+}
+
+/// Statistics of an ongoing run, given to the callback set with [`VM::set_progress_callback`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of paths that have finished, including suppressed ones.
+    pub paths_completed: usize,
+
+    /// Number of saved paths waiting to be explored.
+    pub paths_pending: usize,
+
+    /// Number of instructions executed on all paths.
+    pub instructions: u64,
+
+    /// Time since the first path was started.
+    pub elapsed: Duration,
+}
+
+/// Callback for reporting progress, called with the current statistics.
+pub type ProgressCallback = Box<dyn FnMut(&Progress)>;
+
+/// Progress callback and how often it is called.
+struct ProgressReporter {
+    callback: ProgressCallback,
+
+    /// Number of executed instructions between each call.
+    interval: u64,
+}
+
+/// Inputs and recorded decisions of an ongoing concrete run.
+#[derive(Debug)]
+pub(crate) struct ConcreteInputs {
+    pub(crate) inputs: VecDeque<u64>,
+    pub(crate) decisions: Vec<BranchDecision>,
+}
+
+/// Basic blocks executed on any path.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    functions: HashSet<Function>,
+    blocks: HashSet<BasicBlock>,
+}
+
+impl Coverage {
+    pub(crate) fn visit(&mut self, function: &Function, block: &BasicBlock) {
+        self.functions.insert(function.clone());
+        self.blocks.insert(block.clone());
+    }
+
+    /// Number of basic blocks that have been executed.
+    pub fn covered_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Total number of basic blocks in the functions that have been executed.
+    pub fn total_blocks(&self) -> usize {
+        self.functions
+            .iter()
+            .map(|function| function.num_basic_blocks() as usize)
+            .sum()
+    }
+
+    /// Percentage of the basic blocks in the executed functions which have been executed.
+    pub fn percent(&self) -> f64 {
+        match self.total_blocks() {
+            0 => 0.0,
+            total => 100.0 * self.covered_blocks() as f64 / total as f64,
+        }
+    }
+}
+
+/// What to do when calling a function that is only declared, and is neither hooked nor an
+/// intrinsic, e.g. a foreign function that is not linked in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownFunctionPolicy {
+    /// Stop the analysis with [`LLVMExecutorError::FunctionNotFound`]. Nothing is known about
+    /// what the function does, so this is the only sound choice.
+    #[default]
+    Error,
+
+    /// Assume the function has no side effects and returns an unconstrained value. Any writes the
+    /// function makes, through its arguments or to global state, are missed.
+    ReturnSymbolic,
+
+    /// Overwrite what each pointer argument points to, from the pointer to the end of its
+    /// allocation, with unconstrained values and return an unconstrained value. Writes to global
+    /// state, or through pointers stored in the pointed to memory, are still missed.
+    HavocArgsAndReturnSymbolic,
+}
+
 pub struct VM {
     project: &'static Project,
 
-    pub(crate) paths: DFSPathSelection,
+    /// Context all expressions on the paths are created in.
+    ctx: &'static DContext,
+
+    /// If the global constructors and destructors are run around the entry function.
+    run_global_ctors: bool,
+
+    /// The function being analyzed.
+    entry: Function,
+
+    /// Strategy for which saved path to explore next, see [`VM::set_path_selection`].
+    pub(crate) paths: Box<dyn PathSelection>,
 
     pub inputs: Vec<Variable>,
+
+    /// Tree of all explored paths and where they were forked.
+    pub path_tree: PathTree,
+
+    /// Id in the exploration tree of the path currently being executed.
+    pub(crate) current_path: usize,
+
+    /// If the branch condition that created each path should be recorded in the [`VM::path_tree`],
+    /// used by the DOT output. Off by default since formatting the conditions is expensive.
+    pub record_path_conditions: bool,
+
+    /// Set while running with concrete inputs, no alternative paths are saved.
+    pub(crate) concrete: Option<ConcreteInputs>,
+
+    /// Basic blocks executed so far.
+    pub coverage: Coverage,
+
+    /// Calls to `symex_lib::reachable` that have been executed on any path.
+    pub(crate) reached_markers: HashSet<Instruction>,
+
+    /// If a panic should also continue in the landing pad that catches it, as if it unwound.
+    ///
+    /// The panicking path is still reported as a failure, the continuation is explored as a new
+    /// path. This makes it possible to find errors in cleanup code such as drop glue.
+    pub continue_after_panic: bool,
+
+    /// Maximum number of possible values, or allocations, a symbolic address is resolved to.
+    ///
+    /// Paths with a symbolic address that can take more values than this fail with
+    /// [`AnalysisError::TooManyAddressResolutions`] instead of forking for each of them.
+    pub max_memory_access_resolutions: usize,
+
+    /// Largest allocation in bytes made for an `alloca` or heap allocation with a symbolic size.
+    ///
+    /// The largest value the size can take is allocated. Sizes above this limit are not explored,
+    /// see [`ObjectMemory::allocate_symbolic`](crate::memory::ObjectMemory::allocate_symbolic).
+    pub max_symbolic_allocation: u64,
+
+    /// Maximum number of instructions executed on a single path, if any.
+    ///
+    /// Paths executing more instructions fail with [`AnalysisError::IterationCountExceeded`], so a
+    /// loop that does not terminate does not hang the run.
+    pub max_iter_count: Option<u64>,
+
+    /// Maximum depth of the call stack.
+    ///
+    /// Paths making a call at this depth fail with [`AnalysisError::CallDepthExceeded`], e.g. for
+    /// unbounded recursion.
+    pub max_call_depth: usize,
+
+    /// Value of stack and heap allocations before they are written to. Globals always start out
+    /// with their initializer.
+    pub uninitialized_memory: UninitializedMemory,
+
+    /// If the constraints of each taken branch should be recorded in the path's state, see
+    /// [`LLVMState::branch_constraints`]. Off by default to avoid the overhead.
+    pub log_constraints: bool,
+
+    /// If the functions entered and exited on each path should be recorded in the path's state,
+    /// see [`LLVMState::call_log`]. Off by default to avoid the overhead.
+    pub log_calls: bool,
+
+    /// If the number of times each basic block is branched to should be recorded in the path's
+    /// state, see [`LLVMState::block_visits`]. This shows how many iterations each loop ran. Off
+    /// by default to avoid the overhead.
+    pub log_block_visits: bool,
+
+    /// Fail a path with [`AnalysisError::NonTermination`] when a loop header is entered again
+    /// with the same registers, memory and path constraints as on the previous entry.
+    ///
+    /// Since nothing changed, the next iteration takes the same branches and the loop never
+    /// exits. Loops that do change the state, e.g. an infinite loop incrementing a counter, are
+    /// not detected. Off by default, since the state of the frame is copied on every branch.
+    pub detect_non_termination: bool,
+
+    /// Fail a path with [`AnalysisError::MisalignedAccess`] when the address of a load or store
+    /// can be misaligned with respect to the alignment on the instruction.
+    ///
+    /// The memory model handles any alignment, but many embedded targets fault on unaligned
+    /// accesses. Off by default, since it is not an error on e.g. x86.
+    pub check_alignment: bool,
+
+    /// What to do when calling a function without a definition, hook or intrinsic. Defaults to
+    /// stopping the analysis, see [`UnknownFunctionPolicy`] for the assumptions of the others.
+    /// The unconstrained return values are marked as symbolic.
+    pub unknown_function_policy: UnknownFunctionPolicy,
+
+    /// Experimental: summarize loops instead of unrolling them.
+    ///
+    /// When a loop header is entered again, the phi values that changed since the last visit are
+    /// treated as induction variables and replaced by fresh symbols. The loop is then left through
+    /// the conditional branch in the header, constrained by the exit condition, instead of running
+    /// the body again.
+    ///
+    /// This is unsound, anything else the loop body changes, e.g. memory or other registers, keeps
+    /// the value from the first iteration and values only reachable in later iterations are not
+    /// explored. Only loops whose induction variables are phi nodes, as in optimized code, and that
+    /// exit from a conditional branch in the header are summarized. Off by default.
+    pub loop_summary: bool,
+
+    /// Execute leaf functions with at most this many instructions directly in the caller's stack
+    /// frame, instead of pushing a new frame for each call. Zero, the default, disables this.
+    ///
+    /// Only functions with a single basic block that do not call other functions or write to
+    /// memory are inlined, so a path forked inside the function can safely execute the whole call
+    /// again. Errors in an inlined function are reported at the call site.
+    pub inline_threshold: usize,
+
+    /// Basic blocks where an error has been found, set while searching for reachable errors.
+    ///
+    /// Paths entering one of these blocks are suppressed, since they would only reach the same
+    /// error again.
+    pub(crate) error_blocks: Option<HashSet<BasicBlock>>,
+
+    /// Callback reporting the progress of the run, if any.
+    progress: Option<ProgressReporter>,
+
+    /// Number of instructions executed on all paths.
+    instructions: u64,
+
+    /// Number of paths that have finished.
+    paths_completed: usize,
+
+    /// When the first path was started.
+    started: Option<Instant>,
 }
 
 impl VM {
@@ -29,40 +298,539 @@ impl VM {
         fn_name: &str,
     ) -> Result<Self, LLVMExecutorError> {
         let function = project.find_entry_function(fn_name)?;
-        if function.parameters().count() > 0 {
-            panic!(
-                "Function {:?} has parameters which isn't allowed",
-                function.name()
-            );
-        }
+        Self::create(project, ctx, function, false)
+    }
+
+    /// Create a new VM starting in the function `fn_name` defined in the source file `module`.
+    ///
+    /// Used to pick between several functions with the same name, see
+    /// [`Project::find_entry_function_in`].
+    pub fn new_in_module(
+        project: &'static Project,
+        ctx: &'static DContext,
+        fn_name: &str,
+        module: &str,
+    ) -> Result<Self, LLVMExecutorError> {
+        let function = project.find_entry_function_in(fn_name, Some(module))?;
+        Self::create(project, ctx, function, false)
+    }
+
+    /// Create a new VM which runs the functions in `llvm.global_ctors` before the entry function
+    /// and the functions in `llvm.global_dtors` after it.
+    ///
+    /// This is needed when statics are initialized at runtime, e.g. C++ static constructors.
+    /// The constructors are executed symbolically as well, so they can fork the initial path.
+    pub fn new_with_global_ctors(
+        project: &'static Project,
+        ctx: &'static DContext,
+        fn_name: &str,
+    ) -> Result<Self, LLVMExecutorError> {
+        let function = project.find_entry_function(fn_name)?;
+        Self::create(project, ctx, function, true)
+    }
 
+    fn create(
+        project: &'static Project,
+        ctx: &'static DContext,
+        function: Function,
+        run_global_ctors: bool,
+    ) -> Result<Self, LLVMExecutorError> {
         let mut vm = Self {
             project,
-            paths: DFSPathSelection::new(),
+            ctx,
+            run_global_ctors,
+            entry: function.clone(),
+            paths: Box::new(DFSPathSelection::new()),
             inputs: Vec::new(),
+            path_tree: PathTree::new(),
+            current_path: 0,
+            record_path_conditions: false,
+            concrete: None,
+            coverage: Coverage::default(),
+            reached_markers: HashSet::new(),
+            continue_after_panic: false,
+            max_memory_access_resolutions: 50,
+            max_symbolic_allocation: 4096,
+            max_iter_count: None,
+            max_call_depth: 100,
+            uninitialized_memory: UninitializedMemory::default(),
+            log_constraints: false,
+            log_calls: false,
+            log_block_visits: false,
+            detect_non_termination: false,
+            check_alignment: false,
+            unknown_function_policy: UnknownFunctionPolicy::default(),
+            loop_summary: false,
+            inline_threshold: 0,
+            error_blocks: None,
+            progress: None,
+            instructions: 0,
+            paths_completed: 0,
+            started: None,
         };
-
-        let solver = DSolver::new(ctx);
-        let mut state = LLVMState::new(ctx, project, solver, function)?;
-        vm.initialize_global_references(&mut state)?;
-        vm.paths.save_path(Path::new(state, None));
+        vm.save_initial_path(function)?;
 
         Ok(vm)
     }
 
+    /// Use `selection` to pick which path to explore next, instead of depth-first search.
+    ///
+    /// Paths that are already saved, e.g. the initial path, are moved over to the new strategy.
+    pub fn set_path_selection(&mut self, mut selection: Box<dyn PathSelection>) {
+        while let Some(path) = self.paths.get_path() {
+            selection.save_path(path);
+        }
+        self.paths = selection;
+    }
+
+    /// Reset the VM to start over at another entry function.
+    ///
+    /// All saved paths, inputs and coverage are discarded. A fresh solver context is used for the
+    /// new run, so no constraints or symbols from previous runs leak into it. Note that the old
+    /// context is never freed, same as the one given when the VM was created.
+    pub fn reset_to(&mut self, fn_name: &str) -> Result<(), LLVMExecutorError> {
+        let function = self.project.find_entry_function(fn_name)?;
+
+        self.ctx = Box::leak(Box::new(self.ctx.fresh()));
+        self.entry = function.clone();
+        self.paths.clear();
+        self.inputs.clear();
+        self.path_tree = PathTree::new();
+        self.current_path = 0;
+        self.concrete = None;
+        self.coverage = Coverage::default();
+        self.reached_markers.clear();
+        self.error_blocks = None;
+        self.instructions = 0;
+        self.paths_completed = 0;
+        self.started = None;
+        self.save_initial_path(function)
+    }
+
+    /// Create the state at the start of `function` and save it as the first path to explore.
+    fn save_initial_path(&mut self, function: Function) -> Result<(), LLVMExecutorError> {
+        let solver = DSolver::new(self.ctx);
+        let mut state = LLVMState::new(self.ctx, self.project, solver, function.clone())?;
+        self.bind_parameters(&mut state, &function)?;
+        self.initialize_global_references(&mut state)?;
+        if self.run_global_ctors {
+            self.schedule_global_ctors(&mut state)?;
+        }
+        self.paths.save_path(Path::new(state, None));
+
+        Ok(())
+    }
+
+    /// Solve the value returned by a path, giving up to `max` solutions.
+    ///
+    /// The solutions are typed using the return type of the entry function. Returns `None` if the
+    /// path did not return a value.
+    pub fn solve_return(
+        &self,
+        state: &LLVMState,
+        result: &PathResult,
+        max: usize,
+    ) -> Result<Option<Vec<Variable>>, SolverError> {
+        let PathResult::Success(Some(value)) = result else {
+            return Ok(None);
+        };
+
+        let ty = match self.entry.value_type() {
+            Type::Function(function_type) => {
+                let return_type = function_type.return_type();
+
+                // Prefer the source names of structures if there is debug info.
+                self.entry
+                    .debug_return_structure()
+                    .and_then(|structure| {
+                        named_struct_to_expr_type(&return_type, &structure, self.project)
+                    })
+                    .unwrap_or_else(|| type_to_expr_type(&return_type, self.project))
+            }
+            _ => ExpressionType::Unknown,
+        };
+
+        let solutions = match state.constraints.get_values(value, max)? {
+            Solutions::Exactly(solutions) | Solutions::AtLeast(solutions) => solutions,
+        };
+        let solutions = solutions
+            .into_iter()
+            .map(|value| {
+                // Show what the pointer points to if possible, otherwise leave it as an address.
+                let pointee = match (&ty, value.get_constant()) {
+                    (ExpressionType::Pointer(_, pointee), Some(address)) => state
+                        .solve_pointee(address, pointee)
+                        .ok()
+                        .flatten()
+                        .map(Box::new),
+                    _ => None,
+                };
+
+                Variable {
+                    name: Some("output".to_owned()),
+                    value,
+                    ty: ty.clone(),
+                    pointee,
+                }
+            })
+            .collect();
+
+        Ok(Some(solutions))
+    }
+
+    /// Solve the value of the global variable `name` at the end of a path, giving up to `max`
+    /// solutions.
+    ///
+    /// This makes it possible to check code that communicates through statics. The global is
+    /// found the same way as the entry function. Returns `None` if there is no such global.
+    pub fn solve_global(
+        &self,
+        state: &mut LLVMState,
+        name: &str,
+        max: usize,
+    ) -> Result<Option<Vec<Variable>>, LLVMExecutorError> {
+        let Some(global) = self.project.find_global(name) else {
+            return Ok(None);
+        };
+        let value_type = global.value_type();
+        let ty = type_to_expr_type(&value_type, self.project);
+        let bits = self.project.bit_size(&value_type)?;
+
+        // Globals are initialized on first use, so get the address through the state.
+        let address = state.get_expr(&Value::Global(Global::Variable(global)))?;
+        let value = state.memory.read(&address, bits)?;
+
+        let solutions = match state.constraints.get_values(&value, max)? {
+            Solutions::Exactly(solutions) | Solutions::AtLeast(solutions) => solutions,
+        };
+        let solutions = solutions
+            .into_iter()
+            .map(|value| Variable {
+                name: Some(name.to_owned()),
+                value,
+                ty: ty.clone(),
+                pointee: None,
+            })
+            .collect();
+
+        Ok(Some(solutions))
+    }
+
+    /// Read a NUL terminated string starting at `addr`.
+    ///
+    /// Each byte must have a single possible value for the string to be read, otherwise `None` is
+    /// returned. Strings without a terminator in the first [`MAX_CSTRING_LEN`] bytes are also
+    /// treated as unknown.
+    pub fn read_cstring(
+        &self,
+        state: &LLVMState,
+        addr: &DExpr,
+    ) -> Result<Option<String>, LLVMExecutorError> {
+        let ptr_size = self.project.ptr_size;
+        let mut bytes = Vec::new();
+        for offset in 0..MAX_CSTRING_LEN {
+            let address = addr.add(&state.ctx.from_u64(offset as u64, ptr_size));
+            let byte = state.memory.read(&address, 8)?;
+            let byte = match state.constraints.get_values(&byte, 1)? {
+                Solutions::Exactly(solutions) if solutions.len() == 1 => {
+                    solutions[0].get_constant().unwrap() as u8
+                }
+                _ => return Ok(None),
+            };
+
+            if byte == 0 {
+                return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+            }
+            bytes.push(byte);
+        }
+
+        Ok(None)
+    }
+
+    /// Locations of the `symex_lib::reachable` markers that can be called from the entry function
+    /// but have not been reached on any path so far.
+    pub fn unreached_markers(&self) -> Vec<String> {
+        self.project
+            .reachable_markers(&self.entry)
+            .into_iter()
+            .filter(|(_, call)| !self.reached_markers.contains(call))
+            .map(|(function, call)| instruction_location(&function, Some(&call)))
+            .collect()
+    }
+
     pub fn run(&mut self) -> Result<Option<(PathResult, LLVMState)>, LLVMExecutorError> {
+        self.started.get_or_insert_with(Instant::now);
         while let Some(path) = self.paths.get_path() {
-            let mut executor = LLVMExecutor::from_state(path.state, self, self.project);
+            self.current_path = path.id;
+            if let Ok(frame) = path.state.current_frame() {
+                if self.is_known_error(frame.current_block()) {
+                    self.path_tree.set_outcome(path.id, &PathResult::Suppress);
+                    self.paths_completed += 1;
+                    continue;
+                }
+            }
+
+            let mut state = path.state;
+            if self.log_calls && state.call_log.is_empty() {
+                // Nothing has been recorded for this path yet, start from the functions on the
+                // stack, which is just the entry function for a new path.
+                for frame in state.stack_frames.iter() {
+                    let name = frame.function().name().to_string_lossy().into_owned();
+                    state.call_log.push(CallEvent::Enter(name));
+                }
+            }
+
+            let mut executor = LLVMExecutor::from_state(state, self, self.project);
             for constraint in path.constraints {
                 executor.state.constraints.assert(&constraint);
             }
 
-            let result = executor.resume_execution()?;
-            return Ok(Some((result, executor.state)));
+            let result = match executor.resume_execution() {
+                // Only this path is given up on when a symbolic pointer cannot be resolved.
+                Err(LLVMExecutorError::TooManyAddressResolutions(max)) => {
+                    PathResult::Failure(AnalysisError::TooManyAddressResolutions { max })
+                }
+                result => result?,
+            };
+            let state = executor.state;
+
+            self.path_tree.set_outcome(self.current_path, &result);
+            self.paths_completed += 1;
+            return Ok(Some((result, state)));
         }
         Ok(None)
     }
 
+    /// Returns true if `result` is a path that diverged in an entry function that never returns,
+    /// e.g. a function returning `!` that panics.
+    ///
+    /// Such a function can only end by panicking or reaching `unreachable`, so these are expected
+    /// and not errors.
+    pub fn is_divergence(&self, result: &PathResult) -> bool {
+        self.entry.is_noreturn()
+            && matches!(
+                result,
+                PathResult::Failure(error) if error.is_panic() || *error == AnalysisError::Unreachable
+            )
+    }
+
+    /// Call `callback` with the statistics of the run every `interval` executed instructions.
+    ///
+    /// This is only for reporting, e.g. rendering a progress bar, and does not affect the
+    /// exploration. An interval of zero is treated as one.
+    pub fn set_progress_callback(&mut self, interval: u64, callback: ProgressCallback) {
+        self.progress = Some(ProgressReporter {
+            callback,
+            interval: interval.max(1),
+        });
+    }
+
+    /// Statistics of the run so far.
+    pub fn progress(&self) -> Progress {
+        Progress {
+            paths_completed: self.paths_completed,
+            paths_pending: self.paths.len(),
+            instructions: self.instructions,
+            elapsed: self.started.map(|s| s.elapsed()).unwrap_or_default(),
+        }
+    }
+
+    /// Count an executed instruction, and report the progress if the interval is reached.
+    pub(crate) fn count_instruction(&mut self) {
+        self.instructions += 1;
+        let Some(reporter) = &self.progress else {
+            return;
+        };
+        if self.instructions % reporter.interval != 0 {
+            return;
+        }
+
+        let progress = self.progress();
+        if let Some(reporter) = &mut self.progress {
+            (reporter.callback)(&progress);
+        }
+    }
+
+    /// Find the distinct errors reachable from the entry function, with one path for each.
+    ///
+    /// Errors are told apart by the basic block they occur in. When an error has been found, paths
+    /// that enter its block are suppressed instead of explored, since they would only reach the
+    /// same error. This is much faster than exploring all paths, but errors in a block can be
+    /// missed if another error in the same block was found first. The returned states can be used
+    /// to solve for a witness of each error.
+    pub fn find_reachable_errors(
+        &mut self,
+    ) -> Result<Vec<(PathResult, LLVMState)>, LLVMExecutorError> {
+        self.error_blocks = Some(HashSet::new());
+
+        let mut errors = Vec::new();
+        let result = loop {
+            let (result, state) = match self.run() {
+                Ok(Some(path)) => path,
+                Ok(None) => break Ok(errors),
+                Err(error) => break Err(error),
+            };
+            if !matches!(result, PathResult::Failure(_)) {
+                continue;
+            }
+
+            let block = state
+                .current_frame()
+                .map(|frame| frame.current_block().clone());
+            let is_new = match (block, self.error_blocks.as_mut()) {
+                (Ok(block), Some(blocks)) => blocks.insert(block),
+                _ => true,
+            };
+            if is_new {
+                errors.push((result, state));
+            }
+        };
+
+        self.error_blocks = None;
+        result
+    }
+
+    /// Returns `true` if an error has already been found in `block`.
+    pub(crate) fn is_known_error(&self, block: &BasicBlock) -> bool {
+        self.error_blocks
+            .as_ref()
+            .map_or(false, |blocks| blocks.contains(block))
+    }
+
+    /// Run a single path with the symbolic inputs fixed to concrete values.
+    ///
+    /// Each value marked as symbolic is constrained to the next value in `inputs`, in the order
+    /// they are created. No alternative paths are saved, if both sides of a branch are still
+    /// satisfiable the true side is taken. The path is started from the next saved path, so this
+    /// should be used on a newly created VM.
+    pub fn run_concrete(
+        &mut self,
+        inputs: &[u64],
+    ) -> Result<Option<ConcreteRun>, LLVMExecutorError> {
+        self.concrete = Some(ConcreteInputs {
+            inputs: inputs.iter().copied().collect(),
+            decisions: Vec::new(),
+        });
+        let result = self.run();
+        let concrete = self.concrete.take();
+
+        let Some((result, state)) = result? else {
+            return Ok(None);
+        };
+        Ok(Some(ConcreteRun {
+            result,
+            state,
+            decisions: concrete.map(|c| c.decisions).unwrap_or_default(),
+        }))
+    }
+
+    /// Give each parameter of the entry function an unconstrained value.
+    ///
+    /// Zero sized parameters, e.g. `()`, carry no data and are bound to a placeholder instead of
+    /// being marked as symbolic. Parameters passed `byval` are bound to a new allocation with
+    /// unconstrained contents, which are marked as symbolic.
+    fn bind_parameters(
+        &self,
+        state: &mut LLVMState,
+        function: &Function,
+    ) -> Result<(), LLVMExecutorError> {
+        for (index, parameter) in function.parameters().enumerate() {
+            let size = self.project.bit_size(&parameter.ty())?;
+            let byval_type = function.byval_type(index as u32);
+            let value = if size == 0 {
+                zero_sized_placeholder(self.ctx)
+            } else if let Some(ty) = byval_type {
+                let bits = self.project.bit_size(&ty)? as u64;
+                let align = self.project.ptr_size as u64 / BITS_IN_BYTE as u64;
+                let address = state.memory.allocate(bits.max(1), align)?;
+                let address = self.ctx.from_u64(address, self.project.ptr_size);
+                if bits > 0 {
+                    state.marked_symbolic.push(Variable {
+                        name: Some(format!("{parameter}")),
+                        value: state.memory.read(&address, bits as u32)?,
+                        ty: type_to_expr_type(&ty, self.project),
+                        pointee: None,
+                    });
+                }
+                address
+            } else {
+                let name = format!("{parameter}");
+                // Parameter names are only unique within a function, the symbols must be unique.
+                let value = self.ctx.fresh_unconstrained(size, &name);
+                state.marked_symbolic.push(Variable {
+                    name: Some(name),
+                    value: value.clone(),
+                    ty: type_to_expr_type(&parameter.ty(), self.project),
+                    pointee: None,
+                });
+                value
+            };
+            state.current_frame_mut()?.set_register(parameter, value);
+        }
+
+        Ok(())
+    }
+
+    /// Run the global constructors before the entry function, and the global destructors after.
+    ///
+    /// Constructors run in ascending priority order, destructors in descending priority order.
+    fn schedule_global_ctors(&self, state: &mut LLVMState) -> Result<(), LLVMExecutorError> {
+        let mut ctors = self.global_structors("llvm.global_ctors");
+        let mut dtors = self.global_structors("llvm.global_dtors");
+        if ctors.is_empty() && dtors.is_empty() {
+            return Ok(());
+        }
+        ctors.sort_by_key(|(priority, _)| *priority);
+        dtors.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+        let entry = state.stack_frames.pop().expect("entry stack frame");
+        let mut functions: Vec<Function> = ctors.into_iter().map(|(_, f)| f).collect();
+        functions.push(entry.function().clone());
+        functions.extend(dtors.into_iter().map(|(_, f)| f));
+
+        let mut functions = functions.into_iter();
+        let first = functions.next().expect("entry function");
+        state.stack_frames.push(StackFrame::new(first)?);
+        state.pending_functions.extend(functions);
+        Ok(())
+    }
+
+    /// Get the `(priority, function)` entries of a global constructor or destructor list.
+    fn global_structors(&self, name: &str) -> Vec<(u64, Function)> {
+        let Some(gv) = self
+            .project
+            .module
+            .globals()
+            .find(|gv| gv.name().to_bytes() == name.as_bytes())
+        else {
+            return Vec::new();
+        };
+        let Some(Value::Constant(Constant::Array(entries))) = gv.initializer() else {
+            return Vec::new();
+        };
+
+        let mut structors = Vec::new();
+        for entry in entries.elements() {
+            let Value::Constant(Constant::Structure(entry)) = entry else {
+                continue;
+            };
+            let mut fields = entry.fields();
+            let priority = match fields.next() {
+                Some(Value::Constant(Constant::Integer(priority))) => priority.value(),
+                _ => continue,
+            };
+            match fields.next() {
+                Some(Value::Function(function)) => structors.push((priority, function)),
+                other => warn!("Skipping unsupported entry in {name}: {other:?}"),
+            }
+        }
+        structors
+    }
+
+    /// Give all functions and global variables an address.
+    ///
+    /// Addresses are allocated in the order the symbols appear in the module, so the same project
+    /// always gets the same layout. Do not allocate while iterating over hash maps here.
     fn initialize_global_references(&self, state: &mut LLVMState) -> Result<(), LLVMExecutorError> {
         // Add functions.
         //
@@ -85,6 +853,14 @@ impl VM {
             state.global_lookup_rev.insert(address, function);
         }
 
+        // Aliases share the address of the function they refer to.
+        for (alias, function) in self.project.function_aliases() {
+            let function = Value::Function(function.clone());
+            let address = state.global_lookup[&function];
+            let alias = Value::Global(Global::Alias(alias.clone()));
+            state.global_lookup.insert(alias, address);
+        }
+
         // All GlobalVariable's should be pointers. Allocation size is based on the underlying type.
         for gv in self.project.module.globals() {
             // If no specific alignment is specified, use the project default.
@@ -95,11 +871,12 @@ impl VM {
                 alignment
             };
 
-            // If the global is zero sized, just allocate a small amount for it.
+            // If the global is zero sized, just allocate a small amount for it. The same goes for
+            // globals of unknown size, initializing those fails the path that uses them.
             let allocated_size = if let Some(initializer) = gv.initializer() {
-                match bit_size(&initializer.ty(), self.project.ptr_size)? {
-                    0 => self.project.ptr_size,
-                    size => size,
+                match self.project.bit_size(&initializer.ty()) {
+                    Ok(0) | Err(LLVMExecutorError::NoSize(_)) => self.project.ptr_size,
+                    size => size?,
                 }
             } else {
                 self.project.ptr_size
@@ -1,6 +1,6 @@
 mod test_runner;
 
-use test_runner::run;
+use test_runner::{run, run_concrete, run_with_global_ctors};
 
 // Check that simple instructions work.
 #[test]
@@ -31,6 +31,29 @@ fn multiple_paths() {
     assert_eq!(res[1], Some(2));
 }
 
+// Check that running with concrete inputs only takes the path for those inputs.
+#[test]
+fn concrete_inputs() {
+    let (res, decisions) = run_concrete("tests/samples/multiple_paths.bc", "main", &[1]);
+    assert_eq!(res, Some(1));
+    assert_eq!(decisions, 1);
+
+    let (res, decisions) = run_concrete("tests/samples/multiple_paths.bc", "main", &[5]);
+    assert_eq!(res, Some(2));
+    assert_eq!(decisions, 1);
+}
+
+// Check that global constructors are run in priority order before the entry function, and only
+// when asked to.
+#[test]
+fn global_ctors() {
+    let res = run("tests/samples/global_ctors.bc", "main");
+    assert_eq!(res, vec![Some(1)]);
+
+    let res = run_with_global_ctors("tests/samples/global_ctors.bc", "main", true);
+    assert_eq!(res, vec![Some(12)]);
+}
+
 // Check that function calls work.
 //
 // This also has backtracking so it ensures it correctly handles re-entry into an inner function.
@@ -126,3 +149,12 @@ fn structs_work() {
     assert_eq!(res.len(), 1, "expected 1 path");
     assert_eq!(res[0], Some(15));
 }
+
+// Check that selecting between aggregates works, each path should get the correct tuple.
+#[test]
+fn select_aggregates() {
+    let res = run("tests/samples/aggregates.bc", "aggregates::select_tuple");
+    assert_eq!(res.len(), 2, "expected 2 paths");
+    assert_eq!(res[0], Some(12));
+    assert_eq!(res[1], Some(34));
+}
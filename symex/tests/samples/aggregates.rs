@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+
+#[no_mangle]
+fn symex_symbolic(_: *mut std::ffi::c_void, _: u64) {}
+fn symbolic<T>(value: &mut T) {
+    unsafe {
+        let size = std::mem::size_of_val(value);
+        let ptr = std::mem::transmute(value);
+        symex_symbolic(ptr, size as u64);
+    }
+}
+
+fn pick(c: bool) -> (u32, u32) {
+    if c {
+        (1, 2)
+    } else {
+        (3, 4)
+    }
+}
+
+fn select_tuple() -> u32 {
+    let mut c = false;
+    symbolic(&mut c);
+    let (a, b) = pick(c);
+    a * 10 + b
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+int value = 1;
+
+__attribute__((constructor(101))) void init_first(void) {
+    value = value * 10;
+}
+
+__attribute__((constructor(102))) void init_second(void) {
+    value = value + 2;
+}
+
+int main() {
+    return value;
+}
@@ -15,7 +15,44 @@ fn get_u128_value(expr: DExpr, state: &LLVMState) -> u128 {
     u128::from_str_radix(&binary_str, 2).unwrap()
 }
 
+/// Run a single path with the symbolic inputs fixed to `inputs`.
+///
+/// Returns the result of the path and the number of symbolic branches taken.
+pub fn run_concrete(
+    path: impl AsRef<Path>,
+    function: &str,
+    inputs: &[u64],
+) -> (Option<u128>, usize) {
+    let context = Box::new(DContext::new());
+    let context = Box::leak(context);
+
+    let project = Box::new(Project::from_path(path).unwrap());
+    let project = Box::leak(project);
+
+    let mut vm = VM::new(project, context, function).expect("Failed to create VM");
+    let run = vm
+        .run_concrete(inputs)
+        .expect("Failed to run path")
+        .expect("No path to run");
+    assert!(vm.run().unwrap().is_none(), "expected a single path");
+
+    let result = match run.result {
+        PathResult::Success(value) => value.map(|value| get_u128_value(value, &run.state)),
+        _ => panic!("analysis failed"),
+    };
+    (result, run.decisions.len())
+}
+
 pub fn run(path: impl AsRef<Path>, function: &str) -> Vec<Option<u128>> {
+    run_with_global_ctors(path, function, false)
+}
+
+/// Same as [`run`], but optionally runs the global constructors before `function`.
+pub fn run_with_global_ctors(
+    path: impl AsRef<Path>,
+    function: &str,
+    global_ctors: bool,
+) -> Vec<Option<u128>> {
     // let subscriber = tracing_subscriber::FmtSubscriber::builder()
     //     .with_max_level(tracing::Level::TRACE)
     //     .finish();
@@ -28,7 +65,11 @@ pub fn run(path: impl AsRef<Path>, function: &str) -> Vec<Option<u128>> {
     let project = Box::new(Project::from_path(path).unwrap());
     let project = Box::leak(project);
 
-    let mut vm = VM::new(project, context, function).expect("Failed to create VM");
+    let mut vm = match global_ctors {
+        true => VM::new_with_global_ctors(project, context, function),
+        false => VM::new(project, context, function),
+    }
+    .expect("Failed to create VM");
 
     let mut results = Vec::new();
     while let Some((path_result, state)) = vm.run().expect("Failed to run paths") {
@@ -2,6 +2,160 @@
 target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
 target triple = "x86_64-pc-linux-gnu"
 
+; --------------------------------------------------------------------------------------------------
+; Terminator Instructions
+;
+; indirectbr, invoke, resume, callbr
+; --------------------------------------------------------------------------------------------------
+
+; Branch through a jump table, all three arms should be explored.
+define dso_local i32 @test_indirectbr() #0 {
+    %local = alloca i32, align 4
+    call void @symex_symbolic(ptr %local, i32 4)
+    %val = load i32, ptr %local
+    %is_one = icmp eq i32 %val, 1
+    %is_two = icmp eq i32 %val, 2
+    %t0 = select i1 %is_two, ptr blockaddress(@test_indirectbr, %two), ptr blockaddress(@test_indirectbr, %three)
+    %target = select i1 %is_one, ptr blockaddress(@test_indirectbr, %one), ptr %t0
+    indirectbr ptr %target, [label %one, label %two, label %three]
+one:
+    ret i32 1
+two:
+    ret i32 2
+three:
+    ret i32 3
+}
+
+; The address is never the start of the only destination, so the branch cannot be taken.
+define dso_local i32 @test_indirectbr_no_destination() #0 {
+    %local = alloca i64, align 8
+    call void @symex_symbolic(ptr %local, i32 8)
+    %val = load i64, ptr %local
+    %offset = or i64 %val, 1
+    %target = getelementptr i8, ptr blockaddress(@test_indirectbr_no_destination, %one), i64 %offset
+    indirectbr ptr %target, [label %one]
+one:
+    ret i32 1
+}
+
+; The address is either the start of the only destination or one byte past it.
+define dso_local i32 @test_indirectbr_invalid_destination() #0 {
+    %local = alloca i64, align 8
+    call void @symex_symbolic(ptr %local, i32 8)
+    %val = load i64, ptr %local
+    %offset = and i64 %val, 1
+    %target = getelementptr i8, ptr blockaddress(@test_indirectbr_invalid_destination, %one), i64 %offset
+    indirectbr ptr %target, [label %one]
+one:
+    ret i32 1
+}
+
+; Store the address of a block and branch to it later.
+define dso_local i32 @test_indirectbr_stored_address() #0 {
+    %slot = alloca ptr, align 8
+    store ptr blockaddress(@test_indirectbr_stored_address, %two), ptr %slot
+    br label %dispatch
+dispatch:
+    %target = load ptr, ptr %slot
+    indirectbr ptr %target, [label %one, label %two]
+one:
+    ret i32 1
+two:
+    ret i32 2
+}
+
+; Branch to a block which is not listed as a destination.
+define dso_local i32 @test_indirectbr_unlisted_destination() #0 {
+    indirectbr ptr blockaddress(@test_indirectbr_unlisted_destination, %two), [label %one]
+one:
+    ret i32 1
+two:
+    ret i32 2
+}
+
+; Invoke which returns normally.
+define dso_local i32 @test_invoke() #0 personality ptr @rust_eh_personality {
+    %val = invoke i32 @test_call_called_function() to label %ok unwind label %cleanup
+ok:
+    ret i32 %val ; expect 0xabcd
+cleanup:
+    %lp = landingpad { ptr, i32 } cleanup
+    ret i32 0
+}
+
+; Panics and runs its cleanup before continuing to unwind.
+define internal void @panic_with_cleanup() #0 personality ptr @rust_eh_personality {
+    invoke void @_ZN4core9panicking5panic17h0123456789abcdefE() to label %ok unwind label %cleanup
+ok:
+    ret void
+cleanup:
+    %lp = landingpad { ptr, i32 } cleanup
+    resume { ptr, i32 } %lp
+}
+
+; When continuing after panics the unwind should end up in the landing pad here.
+define dso_local i32 @test_resume() #0 personality ptr @rust_eh_personality {
+    invoke void @panic_with_cleanup() to label %ok unwind label %cleanup
+ok:
+    ret i32 1
+cleanup:
+    %lp = landingpad { ptr, i32 } cleanup
+    ret i32 3
+}
+
+; A failed bounds check unwinds like any other panic.
+define dso_local i32 @test_resume_bounds_check() #0 personality ptr @rust_eh_personality {
+    invoke void @_ZN4core9panicking18panic_bounds_check17h0123456789abcdefE(i64 5, i64 3, ptr null) to label %ok unwind label %cleanup
+ok:
+    ret i32 1
+cleanup:
+    %lp = landingpad { ptr, i32 } cleanup
+    ret i32 3
+}
+
+; Assembly that may jump to a label, both the fallthrough and the label are explored.
+define dso_local i32 @test_callbr() #0 {
+    callbr void asm "", "!i"() to label %fallthrough [label %indirect]
+fallthrough:
+    ret i32 1
+indirect:
+    ret i32 2
+}
+
+; Two distinct panics where the first one is reachable from several paths.
+define dso_local i32 @test_reachable_errors() #0 {
+    %local = alloca i32, align 4
+    call void @symex_symbolic(ptr %local, i32 4)
+    %val = load i32, ptr %local
+    switch i32 %val, label %ok [ i32 1, label %one
+                                 i32 2, label %two
+                                 i32 3, label %three ]
+one:
+    br label %first_panic
+two:
+    br label %first_panic
+three:
+    call void @_ZN4core9panicking5panic17h0123456789abcdefE()
+    unreachable
+first_panic:
+    call void @_ZN4core9panicking5panic17h0123456789abcdefE()
+    unreachable
+ok:
+    ret i32 0
+}
+
+; fn test_diverges(c: bool) -> !, panics on both paths.
+define dso_local void @test_diverges(i1 %c) #2 {
+start:
+    br i1 %c, label %a, label %b
+a:
+    call void @_ZN4core9panicking5panic17h0123456789abcdefE()
+    unreachable
+b:
+    call void @_ZN4core9panicking5panic17h0123456789abcdefE()
+    unreachable
+}
+
 ; --------------------------------------------------------------------------------------------------
 ; Unary Operations
 ;
@@ -124,9 +278,34 @@ define dso_local i8 @test_ashr2() #0 {
 ; --------------------------------------------------------------------------------------------------
 ; Vector Operations
 ;
-; Unsupported: extractelement, insertelement, shufflevector
+; shufflevector
+;
+; Unsupported: extractelement, insertelement
 ; --------------------------------------------------------------------------------------------------
 
+define dso_local i64 @test_shuffle_vector() #0 {
+    %1 = shufflevector <2 x i16> <i16 1, i16 2>, <2 x i16> <i16 3, i16 4>, <4 x i32> <i32 3, i32 0, i32 2, i32 1>
+    %2 = bitcast <4 x i16> %1 to i64
+    ret i64 %2 ; expect 0x0002_0003_0001_0004
+}
+
+; The undefined lane can take any value, so both sides of the branch are explored.
+define dso_local i64 @test_shuffle_vector_undef() #0 {
+    %1 = shufflevector <2 x i16> <i16 1, i16 2>, <2 x i16> <i16 3, i16 4>, <4 x i32> <i32 3, i32 undef, i32 0, i32 2>
+    %2 = bitcast <4 x i16> %1 to i64
+    %3 = lshr i64 %2, 16
+    %4 = trunc i64 %3 to i16
+    %5 = icmp eq i16 %4, 0
+    %6 = and i64 %2, -4294901761 ; clear the undefined lane
+    br i1 %5, label %zero, label %nonzero
+
+zero:
+    ret i64 %6 ; expect 0x0003_0001_0000_0004
+
+nonzero:
+    ret i64 %6 ; expect 0x0003_0001_0000_0004
+}
+
 ; --------------------------------------------------------------------------------------------------
 ; Aggregate Operations
 ;
@@ -246,8 +425,8 @@ define dso_local { i16, i16, [2 x i16] } @test_insert_value_struct4() #0 {
 ; --------------------------------------------------------------------------------------------------
 ; Memory access and Addressing Operations
 ;
-; alloca, load, store, getelementptr
-; Unsupported: fence, cmpxchg, atomicrmw
+; alloca, load, store, getelementptr, fence, atomicrmw
+; Unsupported: cmpxchg
 ; --------------------------------------------------------------------------------------------------
 
 ; tests with alloca, load & store.
@@ -271,6 +450,20 @@ define dso_local i8 @test_load_store2() #0 {
     ret i8 %val ; expect 0x56
 }
 
+; Same as `AtomicBool::swap(false, Ordering::SeqCst)` on a `true` value.
+define dso_local i16 @test_atomic_swap() #0 {
+    %flag = alloca i8, align 1
+    store i8 1, ptr %flag
+    %old = atomicrmw xchg ptr %flag, i8 0 seq_cst
+    fence seq_cst
+    %new = load i8, ptr %flag
+    %old.ext = zext i8 %old to i16
+    %old.shifted = shl i16 %old.ext, 8
+    %new.ext = zext i8 %new to i16
+    %res = or i16 %old.shifted, %new.ext
+    ret i16 %res ; expect 0x0100
+}
+
 ; Simpler version of the struct in the LLVM IR reference.
 %struct.RT = type { i8, [2 x i32], i8 }
 %struct.ST = type { i32, i64, %struct.RT }
@@ -345,11 +538,337 @@ define dso_local i32 @test_gep_arr2() #0 {
     ret i32 %2 ; expect 2 ([2])
 }
 
+; Null pointers and zero initializers.
+@null_ptr = internal constant ptr null, align 8
+@zeroed = internal global [4 x i32] zeroinitializer, align 4
+
+define dso_local i1 @test_null_global() #0 {
+    %ptr = load ptr, ptr @null_ptr
+    %is_null = icmp eq ptr %ptr, null
+    ret i1 %is_null ; expect 1
+}
+
+define dso_local i32 @test_zeroed_global() #0 {
+    %1 = getelementptr inbounds [4 x i32], ptr @zeroed, i64 0, i32 3
+    %2 = load i32, ptr %1
+    %3 = add i32 %2, 7
+    ret i32 %3 ; expect 7
+}
+
+define dso_local i32 @test_store_aggregate_zero() #0 {
+    %ptr = alloca [4 x i32], align 4
+    store [4 x i32] [i32 1, i32 2, i32 3, i32 4], ptr %ptr
+    store [4 x i32] zeroinitializer, ptr %ptr
+    %1 = getelementptr inbounds [4 x i32], ptr %ptr, i64 0, i32 1
+    %2 = load i32, ptr %1
+    ret i32 %2 ; expect 0
+}
+
+; Global initializers with constant expressions.
+@table_values = internal constant [3 x i32] [i32 10, i32 20, i32 30], align 4
+@table = internal constant [2 x ptr] [
+    ptr getelementptr inbounds (i32, ptr @table_values, i64 1),
+    ptr getelementptr inbounds ([3 x i32], ptr @table_values, i64 0, i64 2)
+], align 8
+@table_address = internal constant i64 ptrtoint (ptr getelementptr (i8, ptr @table_values, i64 -4) to i64), align 8
+
+define dso_local i32 @test_global_pointer_table() #0 {
+    %1 = getelementptr inbounds [2 x ptr], ptr @table, i64 0, i64 1
+    %2 = load ptr, ptr %1
+    %3 = load i32, ptr %2
+    %4 = load ptr, ptr @table
+    %5 = load i32, ptr %4
+    %6 = add i32 %3, %5
+    ret i32 %6 ; expect 50 (30 + 20)
+}
+
+define dso_local i64 @test_global_constant_expression() #0 {
+    %1 = load i64, ptr @table_address
+    %2 = add i64 %1, 4
+    %3 = ptrtoint ptr @table_values to i64
+    %4 = sub i64 %2, %3
+    ret i64 %4 ; expect 0
+}
+
+; Global initializers with aggregates.
+@static_array = internal constant [4 x i32] [i32 1, i32 2, i32 3, i32 4], align 4
+@static_struct = internal constant { i8, i32, [2 x i16] } { i8 1, i32 2, [2 x i16] [i16 3, i16 4] }, align 4
+
+define dso_local i32 @test_static_array() #0 {
+    %1 = getelementptr inbounds [4 x i32], ptr @static_array, i64 0, i64 2
+    %2 = load i32, ptr %1
+    ret i32 %2 ; expect 3
+}
+
+define dso_local i16 @test_static_struct() #0 {
+    %1 = getelementptr inbounds { i8, i32, [2 x i16] }, ptr @static_struct, i64 0, i32 2, i64 1
+    %2 = load i16, ptr %1
+    %3 = getelementptr inbounds { i8, i32, [2 x i16] }, ptr @static_struct, i64 0, i32 1
+    %4 = load i32, ptr %3
+    %5 = trunc i32 %4 to i16
+    %6 = add i16 %2, %5
+    ; The i32 is aligned to four bytes, so the fields sit at byte offsets 0, 4 and 8.
+    %7 = getelementptr inbounds i8, ptr @static_struct, i64 10
+    %8 = load i16, ptr %7
+    %9 = getelementptr inbounds i8, ptr @static_struct, i64 4
+    %10 = load i32, ptr %9
+    %11 = trunc i32 %10 to i16
+    %12 = add i16 %6, %8
+    %13 = add i16 %12, %11
+    ret i16 %13 ; expect 12 (4 + 2 + 4 + 2)
+}
+
+@large_zeroed = internal global [1024 x i8] zeroinitializer, align 1
+@undef_value = internal global i32 undef, align 4
+
+define dso_local i8 @test_large_zeroed_global() #0 {
+    %1 = getelementptr inbounds [1024 x i8], ptr @large_zeroed, i64 0, i64 1000
+    %2 = load i8, ptr %1
+    ret i8 %2 ; expect 0
+}
+
+define dso_local i32 @test_undef_global() #0 {
+    %1 = load i32, ptr @undef_value
+    %2 = icmp eq i32 %1, 5
+    br i1 %2, label %five, label %other
+
+five:
+    ret i32 1
+
+other:
+    ret i32 0 ; expect both paths, undef can be any value
+}
+
+@pointee = internal global i32 u0x1234, align 4
+
+define dso_local ptr @test_return_pointer() #0 {
+    ret ptr @pointee
+}
+
+@counter = internal global i32 0, align 4
+
+define dso_local void @test_write_global() #0 {
+    %old = load i32, ptr @counter
+    %new = add i32 %old, 42
+    store i32 %new, ptr @counter
+    ret void
+}
+
+@message = internal global [6 x i8] zeroinitializer, align 1
+@symbolic_message = internal global [2 x i8] zeroinitializer, align 1
+
+define dso_local ptr @test_write_cstring(i8 %c) #0 {
+    store [6 x i8] c"hello\00", ptr @message
+    store i8 %c, ptr @symbolic_message
+    ret ptr @message
+}
+
+; Counted loop over a symbolic bound, only feasible to explore with loop summaries.
+define dso_local i32 @test_loop_summary(i32 %n) #0 {
+start:
+    br label %header
+
+header:
+    %i = phi i32 [ 0, %start ], [ %next, %body ]
+    %cond = icmp ult i32 %i, %n
+    br i1 %cond, label %body, label %exit
+
+body:
+    %next = add i32 %i, 1
+    br label %header
+
+exit:
+    %done = icmp uge i32 %i, %n
+    %r = zext i1 %done to i32
+    ret i32 %r
+}
+
+; Two counted loops after each other, each gets its own summary.
+define dso_local i32 @test_loop_summary_two_loops(i32 %n, i32 %m) #0 {
+start:
+    br label %first
+
+first:
+    %i = phi i32 [ 0, %start ], [ %i_next, %first_body ]
+    %i_cond = icmp ult i32 %i, %n
+    br i1 %i_cond, label %first_body, label %second_start
+
+first_body:
+    %i_next = add i32 %i, 1
+    br label %first
+
+second_start:
+    br label %second
+
+second:
+    %j = phi i32 [ 0, %second_start ], [ %j_next, %second_body ]
+    %j_cond = icmp ult i32 %j, %m
+    br i1 %j_cond, label %second_body, label %exit
+
+second_body:
+    %j_next = add i32 %j, 1
+    br label %second
+
+exit:
+    %i_done = icmp uge i32 %i, %n
+    %j_done = icmp uge i32 %j, %m
+    %done = and i1 %i_done, %j_done
+    %r = zext i1 %done to i32
+    ret i32 %r
+}
+
+; symex_lib::reachable
+declare void @_ZN9symex_lib9reachable17h0123456789abcdefE()
+
+; Only the markers in the first branch can be reached, the second branch is never taken.
+define dso_local i32 @test_reachable(i32 %x) #0 {
+start:
+    %big = icmp ugt i32 %x, 10
+    br i1 %big, label %reached, label %small
+
+reached:
+    call void @_ZN9symex_lib9reachable17h0123456789abcdefE()
+    call void @test_reachable_callee()
+    ret i32 1
+
+small:
+    %never = icmp ugt i32 %x, 20
+    br i1 %never, label %unreached, label %done
+
+unreached:
+    call void @_ZN9symex_lib9reachable17h0123456789abcdefE()
+    br label %done
+
+done:
+    ret i32 0
+}
+
+define dso_local void @test_reachable_callee() #0 {
+    call void @_ZN9symex_lib9reachable17h0123456789abcdefE()
+    ret void
+}
+
+; Never called from @test_reachable, so the marker is not expected to be reached.
+define dso_local void @test_reachable_uncalled() #0 {
+    call void @_ZN9symex_lib9reachable17h0123456789abcdefE()
+    ret void
+}
+
+; symex_lib::symbolic_named
+declare void @_ZN9symex_lib14symbolic_named17h0123456789abcdefE(ptr, ptr, i64)
+
+@symbolic_name = internal constant [5 x i8] c"input", align 1
+
+define dso_local i32 @test_symbolic_named() #0 {
+    %x = alloca i32, align 4
+    store i32 0, ptr %x
+    call void @_ZN9symex_lib14symbolic_named17h0123456789abcdefE(ptr %x, ptr @symbolic_name, i64 5)
+    %1 = load i32, ptr %x
+    ret i32 %1
+}
+
+; symex_lib::concretize_bytes
+declare void @_ZN9symex_lib16concretize_bytes17h0123456789abcdefE(ptr, i64)
+
+; After concretizing only one side of the branch can be taken. The value is the second element of
+; the allocation, so only those four bytes are concretized.
+define dso_local i32 @test_concretize(i32 %x) #0 {
+    %a = alloca [2 x i32], align 4
+    store i32 0, ptr %a
+    %p = getelementptr inbounds [2 x i32], ptr %a, i64 0, i64 1
+    store i32 %x, ptr %p
+    call void @_ZN9symex_lib16concretize_bytes17h0123456789abcdefE(ptr %p, i64 4)
+    %y = load i32, ptr %p
+    %is_five = icmp eq i32 %x, 5
+    br i1 %is_five, label %five, label %other
+
+five:
+    ret i32 %y
+
+other:
+    ret i32 %y
+}
+
+; State owned by foreign code, only the address is known.
+%struct.FfiState = type opaque
+@ffi_state = external global %struct.FfiState
+
+define dso_local ptr @test_opaque_global() #0 {
+    ret ptr @ffi_state
+}
+
+; Default state for foreign code, the initializer cannot be written since the size is unknown.
+@ffi_default = global %struct.FfiState undef
+
+define dso_local i8 @test_opaque_global_init() #0 {
+    %v = load i8, ptr @ffi_default
+    ret i8 %v
+}
+
+; Store through a pointer into either of two buffers, where the index can be one past the end.
+define dso_local i32 @test_store_symbolic_pointer() #0 {
+    %idx_local = alloca i64, align 8
+    %sel_local = alloca i8, align 1
+    %a = alloca [4 x i32], align 4
+    %b = alloca [4 x i32], align 4
+    call void @symex_symbolic(ptr %idx_local, i32 8)
+    call void @symex_symbolic(ptr %sel_local, i32 1)
+    %idx = load i64, ptr %idx_local
+    %sel = load i8, ptr %sel_local
+    %in_range = icmp ule i64 %idx, 4
+    br i1 %in_range, label %store, label %skip
+store:
+    %use_b = icmp eq i8 %sel, 0
+    %buf = select i1 %use_b, ptr %b, ptr %a
+    %ptr = getelementptr inbounds [4 x i32], ptr %buf, i64 0, i64 %idx
+    store i32 7, ptr %ptr
+    ret i32 1
+skip:
+    ret i32 0
+}
+
+; Load an i32 from a byte offset into the buffer, which is misaligned unless the offset is zero.
+define dso_local i32 @test_misaligned_load(i64 %offset) #0 {
+    %buf = alloca [8 x i8], align 4
+    store i64 0, ptr %buf, align 4
+    %masked = and i64 %offset, 3
+    %ptr = getelementptr inbounds i8, ptr %buf, i64 %masked
+    %val = load i32, ptr %ptr, align 4
+    ret i32 %val
+}
+
+; Load through a pointer which can point to any of 16 bytes.
+define dso_local i8 @test_address_resolutions() #0 {
+    %idx_local = alloca i64, align 8
+    %buf = alloca [16 x i8], align 1
+    store [16 x i8] zeroinitializer, ptr %buf
+    call void @symex_symbolic(ptr %idx_local, i32 8)
+    %idx = load i64, ptr %idx_local
+    %in_range = icmp ult i64 %idx, 16
+    br i1 %in_range, label %load, label %skip
+load:
+    %ptr = getelementptr inbounds [16 x i8], ptr %buf, i64 0, i64 %idx
+    %val = load i8, ptr %ptr
+    ret i8 %val
+skip:
+    ret i8 0
+}
+
+; A large symbolic index makes the offset overflow the pointer width.
+define dso_local i32 @test_gep_overflow() #0 {
+    %idx_local = alloca i64, align 8
+    %arr = alloca [4 x i64], align 8
+    call void @symex_symbolic(ptr %idx_local, i32 8)
+    %idx = load i64, ptr %idx_local
+    %ptr = getelementptr [4 x i64], ptr %arr, i64 0, i64 %idx
+    ret i32 0
+}
+
 ; --------------------------------------------------------------------------------------------------
 ; Conversion Operations
 ;
-; trunc, zext, sext, ptrtoint, inttoptr, bitcast, addrspacecast
-; Unsupported: fptrunc, fpext, fptoui, fptosi, uitpfp, sitofp
+; trunc, zext, sext, fptoui, fptosi, uitofp, sitofp, ptrtoint, inttoptr, bitcast, addrspacecast
+; Unsupported: fptrunc, fpext
 ; --------------------------------------------------------------------------------------------------
 
 define dso_local i8 @test_trunc() #0 {
@@ -367,11 +886,26 @@ define dso_local <2 x i16> @test_zext_vec() #0 {
     ret <2 x i16> %1 ; expect: 0x000F00FF
 }
 
+define dso_local <4 x i32> @test_zext_vec4() #0 {
+    %1 = zext <4 x i8> <i8 u0x01, i8 u0x80, i8 u0xFF, i8 u0x7F> to <4 x i32>
+    ret <4 x i32> %1 ; expect: 0x0000007F_000000FF_00000080_00000001
+}
+
 define dso_local i16 @test_sext() #0 {
     %1 = sext i8 u0xFF to i16
     ret i16 %1 ; expect: 0xFFFF
 }
 
+define dso_local <4 x i32> @test_sext_vec4() #0 {
+    %1 = sext <4 x i8> <i8 u0x01, i8 u0x80, i8 u0xFF, i8 u0x7F> to <4 x i32>
+    ret <4 x i32> %1 ; expect: 0x0000007F_FFFFFFFF_FFFFFF80_00000001
+}
+
+define dso_local <4 x i8> @test_trunc_vec4() #0 {
+    %1 = trunc <4 x i32> <i32 u0x1234, i32 u0xABCD, i32 u0xFF00, i32 u0x0F> to <4 x i8>
+    ret <4 x i8> %1 ; expect: 0x0F00CD34
+}
+
 ; on the current architecture the pointer size is 64 bits.
 
 ; inttoptr
@@ -408,6 +942,25 @@ define dso_local i64 @test_ptrtoint_noop() #0 {
 ; todo
 ; define dso_local i64 @test_ptrtoint_extend() #0 {}
 
+; A pointer tagged in its unused low bit through an integer still points to the same value.
+define dso_local i32 @test_ptrtoint_roundtrip() #0 {
+    %local = alloca i32, align 4
+    store i32 42, ptr %local
+    %int = ptrtoint ptr %local to i64
+    %tagged = or i64 %int, 1
+    %untagged = and i64 %tagged, -2
+    %ptr = inttoptr i64 %untagged to ptr
+    %val = load i32, ptr %ptr
+    ret i32 %val ; expect 42
+}
+
+; An integer that was never a pointer does not point into any allocation.
+define dso_local i32 @test_inttoptr_dangling() #0 {
+    %ptr = inttoptr i64 u0x1111222233334444 to ptr
+    %val = load i32, ptr %ptr
+    ret i32 %val
+}
+
 define dso_local <2 x i32> @test_ptrtoint_vec_trunc() #0 {
     %1 = inttoptr <2 x i64> <i64 u0x1111222233334444, i64 u0x5555666677778888> to <2 x i32*>
     %2 = ptrtoint <2 x i32*> %1 to <2 x i32>
@@ -433,11 +986,45 @@ define dso_local i32 addrspace(1)* @test_addrspacecast() #0 {
     ret i32 addrspace(1)* %2 ; expect: 0x1111222233334444
 }
 
+; fptoui, fptosi, uitofp, sitofp
+
+define dso_local i32 @test_sitofp_fptosi() #0 {
+    %1 = add i32 0, -42
+    %2 = sitofp i32 %1 to double
+    %3 = fptosi double %2 to i32
+    ret i32 %3 ; expect -42 -> 0xffffffd6
+}
+
+define dso_local i64 @test_uitofp() #0 {
+    %1 = add i32 0, 3
+    %2 = uitofp i32 %1 to double
+    %3 = bitcast double %2 to i64
+    ret i64 %3 ; expect 3.0 -> 0x4008000000000000
+}
+
+define dso_local i32 @test_uitofp_round() #0 {
+    %1 = add i32 0, 16777217
+    %2 = uitofp i32 %1 to float
+    %3 = bitcast float %2 to i32
+    ret i32 %3 ; expect 2^24 + 1 rounded to 2^24 -> 0x4b800000
+}
+
+define dso_local i32 @test_fptoui() #0 {
+    %1 = fptoui float 7.5 to i32
+    ret i32 %1 ; expect 7
+}
+
+define dso_local i8 @test_fptosi_negative() #0 {
+    %1 = fptosi double -2.75 to i8
+    ret i8 %1 ; expect -2 -> 0xfe
+}
+
 ; --------------------------------------------------------------------------------------------------
 ; Other Operations
 ;
-; icmp, phi, select, call
-; Unsupported: fcmp, freeze, va_arg, landingpad, catchpad, cleanuppad
+; icmp, fcmp, phi, select, call
+; landingpad
+; Unsupported: freeze, va_arg, catchpad, cleanuppad
 ; --------------------------------------------------------------------------------------------------
 
 ; eq: lhs == rhs
@@ -556,6 +1143,47 @@ define dso_local <3 x i1> @test_icmp_eq_vec() #0 {
     ret <3 x i1> %1 ; expect: <0x1, 0x0, 0x1> -> 0b0101 -> 0x5
 }
 
+; fcmp
+define dso_local i1 @test_fcmp_oeq_signed_zero() #0 {
+    %1 = fcmp oeq double 0.0, -0.0
+    ret i1 %1 ; expect: 0x1
+}
+
+define dso_local i1 @test_fcmp_olt_nan() #0 {
+    %1 = fcmp olt double 0x7FF8000000000000, 1.0
+    ret i1 %1 ; expect: 0x0
+}
+
+define dso_local i1 @test_fcmp_ult_nan() #0 {
+    %1 = fcmp ult double 0x7FF8000000000000, 1.0
+    ret i1 %1 ; expect: 0x1
+}
+
+define dso_local <3 x i1> @test_fcmp_ogt_vec() #0 {
+    %1 = fcmp ogt <3 x float> <float -1.5, float 3.0, float 2.0>, <float -2.0, float 4.0, float 2.0>
+    ret <3 x i1> %1 ; expect: <0x1, 0x0, 0x0> -> 0b001 -> 0x1
+}
+
+; Assuming a comparison of a symbolic float constrains it, so the branch contradicting the
+; assumption is never explored.
+define dso_local i32 @test_fcmp_assume(double %f) #0 {
+    %gt = fcmp ogt double %f, 1.0
+    call void @_ZN9symex_lib6assume17h0123456789abcdefE(i1 zeroext %gt)
+    %le = fcmp ole double %f, 1.0
+    br i1 %le, label %unreachable, label %ok
+unreachable:
+    unreachable
+ok:
+    ret i32 1 ; expect 1
+}
+
+; The assertion can fail for `x <= 0`, which is reported instead of pruned like an assumption.
+define dso_local i32 @test_assert(i32 %x) #0 {
+    %gt = icmp sgt i32 %x, 0
+    call void @_ZN9symex_lib6assert17h0123456789abcdefE(i1 zeroext %gt)
+    ret i32 1
+}
+
 ; phi
 
 define dso_local i32 @test_phi1() #0 {
@@ -588,6 +1216,20 @@ bb3:
     ret i32 %5 ; expect: 0xcd
 }
 
+define dso_local { i32, i8 } @test_phi_struct() #0 {
+    %1 = add i32 0, 100
+    %2 = icmp eq i32 %1, 100
+    br i1 %2, label %bb1, label %bb2
+bb1:
+    %3 = insertvalue { i32, i8 } { i32 0, i8 u0xab }, i32 u0xcd, 0
+    br label %bb3
+bb2:
+    br label %bb3
+bb3:
+    %4 = phi { i32, i8 } [%3, %bb1], [{ i32 1, i8 2 }, %bb2]
+    ret { i32, i8 } %4 ; expect: { 0xcd, 0xab } -> 0xab000000cd
+}
+
 ; select
 
 define dso_local i32 @test_select1() #0 {
@@ -618,6 +1260,20 @@ define dso_local <2 x i32> @test_select_vec_cond() #0 {
     ret <2 x i32> %3 ; expect <0xaa, 0xdd> -> 0x000000dd000000aa
 }
 
+define dso_local { i32, i32 } @test_select_struct() #0 {
+    %1 = add i32 0, 100
+    %2 = icmp eq i32 %1, 101
+    %3 = select i1 %2, { i32, i32 } { i32 1, i32 2 }, { i32, i32 } { i32 3, i32 4 }
+    ret { i32, i32 } %3 ; expect { 3, 4 } -> 0x0000000400000003
+}
+
+define dso_local [2 x i16] @test_select_array() #0 {
+    %1 = add i32 0, 100
+    %2 = icmp eq i32 %1, 100
+    %3 = select i1 %2, [2 x i16] [i16 1, i16 2], [2 x i16] [i16 3, i16 4]
+    ret [2 x i16] %3 ; expect [1, 2] -> 0x00020001
+}
+
 ; call
 
 define dso_local i32 @test_call_called_function() #0 {
@@ -629,6 +1285,192 @@ define dso_local i32 @test_call() #0 {
     ret i32 %1 ; expect 0xabcd
 }
 
+; Calls through an alias and through a pointer to the alias both run the aliased function.
+@test_call_alias = alias i32 (), ptr @test_call_called_function
+
+define dso_local i32 @test_call_through_alias() #0 {
+    %slot = alloca ptr, align 8
+    store ptr @test_call_alias, ptr %slot
+    %fn = load ptr, ptr %slot
+    %1 = call i32 @test_call_alias()
+    %2 = call i32 %fn()
+    %3 = add i32 %1, %2
+    ret i32 %3 ; expect 0x1579a
+}
+
+; A struct passed byval is copied, so the writes in the callee are not seen by the caller.
+%struct.ByValPair = type { i32, i32 }
+
+define dso_local i32 @test_byval_callee(ptr byval(%struct.ByValPair) align 4 %pair) #0 {
+    store i32 7, ptr %pair, align 4
+    %second = getelementptr inbounds %struct.ByValPair, ptr %pair, i32 0, i32 1
+    %value = load i32, ptr %second, align 4
+    ret i32 %value
+}
+
+define dso_local i32 @test_byval() #0 {
+    %pair = alloca %struct.ByValPair, align 4
+    store i32 1, ptr %pair, align 4
+    %second = getelementptr inbounds %struct.ByValPair, ptr %pair, i32 0, i32 1
+    store i32 2, ptr %second, align 4
+    %callee_second = call i32 @test_byval_callee(ptr byval(%struct.ByValPair) align 4 %pair)
+    %first = load i32, ptr %pair, align 4
+    %scaled = mul i32 %callee_second, 16
+    %result = add i32 %first, %scaled
+    ret i32 %result ; expect 0x21
+}
+
+; Calls a function that is only declared, whether it can change the buffer depends on the policy
+; for unknown functions.
+declare dso_local i32 @external_read(ptr)
+
+define dso_local i32 @test_unknown_function() #0 {
+    %buf = alloca i32, align 4
+    store i32 5, ptr %buf, align 4
+    %read = call i32 @external_read(ptr %buf)
+    %value = load i32, ptr %buf, align 4
+    %unchanged = icmp eq i32 %value, 5
+    br i1 %unchanged, label %same, label %changed
+same:
+    ret i32 1
+changed:
+    ret i32 2
+}
+
+declare ptr @__rust_alloc(i64, i64)
+
+declare void @__rust_dealloc(ptr, i64, i64)
+
+declare ptr @__rust_realloc(ptr, i64, i64, i64)
+
+; Reads a heap allocation after it is freed if %late is set, otherwise before it is freed.
+define dso_local i32 @test_use_after_free(i1 %late) #0 {
+    %p = call ptr @__rust_alloc(i64 4, i64 4)
+    store i32 7, ptr %p, align 4
+    br i1 %late, label %free_first, label %read_first
+read_first:
+    %1 = load i32, ptr %p, align 4
+    call void @__rust_dealloc(ptr %p, i64 4, i64 4)
+    ret i32 %1
+free_first:
+    call void @__rust_dealloc(ptr %p, i64 4, i64 4)
+    %2 = load i32, ptr %p, align 4
+    ret i32 %2
+}
+
+; Reallocates memory which has already been freed.
+define dso_local ptr @test_realloc_after_free() #0 {
+    %p = call ptr @__rust_alloc(i64 4, i64 4)
+    call void @__rust_dealloc(ptr %p, i64 4, i64 4)
+    %q = call ptr @__rust_realloc(ptr %p, i64 4, i64 4, i64 8)
+    ret ptr %q
+}
+
+; Copies from a heap allocation after it has been freed.
+define dso_local i32 @test_memcpy_after_free() #0 {
+    %buf = alloca i32, align 4
+    %p = call ptr @__rust_alloc(i64 4, i64 4)
+    store i32 7, ptr %p, align 4
+    call void @__rust_dealloc(ptr %p, i64 4, i64 4)
+    call void @llvm.memcpy.p0.p0.i64(ptr align 4 %buf, ptr align 4 %p, i64 4, i1 false)
+    %1 = load i32, ptr %buf, align 4
+    ret i32 %1
+}
+
+declare void @llvm.memcpy.p0.p0.i64(ptr noalias nocapture writeonly, ptr noalias nocapture readonly, i64, i1 immarg)
+
+; Allocates %n elements on the stack, where %n is at most 4, and writes the fourth one. The write
+; is only in bounds when all 4 elements are allocated.
+define dso_local i32 @test_symbolic_alloca(i32 %n) #0 {
+    %small = icmp ule i32 %n, 4
+    br i1 %small, label %alloc, label %too_large
+alloc:
+    %buf = alloca i32, i32 %n, align 4
+    %last = getelementptr inbounds i32, ptr %buf, i32 3
+    store i32 9, ptr %last, align 4
+    %value = load i32, ptr %last, align 4
+    ret i32 %value
+too_large:
+    ret i32 0
+}
+
+; The size in bits of the allocation always overflows 64 bits.
+define dso_local i32 @test_symbolic_alloca_overflow(i64 %n) #0 {
+    %large = or i64 %n, 288230376151711744
+    %buf = alloca i64, i64 %large, align 8
+    store i64 9, ptr %buf, align 8
+    ret i32 0
+}
+
+; A small leaf function called in a loop, executed in the caller's frame when inlining is enabled.
+define internal i32 @inline_helper(i32 %a, i32 %b) #0 {
+    %1 = mul i32 %a, 3
+    %2 = add i32 %1, %b
+    ret i32 %2
+}
+
+define dso_local i32 @test_inline_loop() #0 {
+entry:
+    br label %loop
+loop:
+    %i = phi i32 [0, %entry], [%next, %loop]
+    %acc = phi i32 [0, %entry], [%acc_next, %loop]
+    %acc_next = call i32 @inline_helper(i32 %acc, i32 %i)
+    %next = add i32 %i, 1
+    %done = icmp eq i32 %next, 10
+    br i1 %done, label %exit, label %loop
+exit:
+    ret i32 %acc_next ; expect 0x39a5
+}
+
+; Same as @inline_helper, with a call to a debug info intrinsic that is not counted when inlining.
+define internal i32 @inline_helper_dbg(i32 %a, i32 %b) #0 !dbg !30 {
+    call void @llvm.dbg.value(metadata i32 %a, metadata !31, metadata !DIExpression()), !dbg !32
+    %1 = mul i32 %a, 3
+    %2 = add i32 %1, %b
+    ret i32 %2
+}
+
+define dso_local i32 @test_inline_loop_dbg() #0 {
+entry:
+    br label %loop
+loop:
+    %i = phi i32 [0, %entry], [%next, %loop]
+    %acc = phi i32 [0, %entry], [%acc_next, %loop]
+    %acc_next = call i32 @inline_helper_dbg(i32 %acc, i32 %i)
+    %next = add i32 %i, 1
+    %done = icmp eq i32 %next, 10
+    br i1 %done, label %exit, label %loop
+exit:
+    ret i32 %acc_next ; expect 0x39a5
+}
+
+declare void @llvm.dbg.value(metadata, metadata, metadata)
+
+; Never leaves the loop when %x is 5, since each iteration starts from the same state.
+define dso_local i32 @test_infinite_loop(i32 %x) #0 {
+entry:
+    br label %loop
+loop:
+    %is_five = icmp eq i32 %x, 5
+    br i1 %is_five, label %loop, label %exit
+exit:
+    ret i32 1
+}
+
+; Recurses %n times, without any bound on %n.
+define dso_local i32 @test_unbounded_recursion(i32 %n) #0 {
+    %done = icmp eq i32 %n, 0
+    br i1 %done, label %base, label %recurse
+base:
+    ret i32 0
+recurse:
+    %m = sub i32 %n, 1
+    %r = call i32 @test_unbounded_recursion(i32 %m)
+    %s = add i32 %r, 1
+    ret i32 %s
+}
+
 ; --------------------------------------------------------------------------------------------------
 ; Constants
 ; --------------------------------------------------------------------------------------------------
@@ -653,7 +1495,164 @@ define dso_local i32 @test_vector_constant2() #0 {
     ret i32 %val
 }
 
+; Reads memory that has not been written to.
+define dso_local i32 @test_uninitialized_read() #0 {
+    %ptr = alloca i32, align 4
+    %val = load i32, ptr %ptr, align 4
+    ret i32 %val
+}
+
+; Formatting is stubbed and always succeeds.
+define dso_local i32 @test_fmt_write() #0 {
+    %err = call zeroext i1 @_ZN4core3fmt5write17h0123456789abcdefE(ptr null, ptr null, ptr null)
+    %ret = zext i1 %err to i32
+    ret i32 %ret
+}
+
+; Returns a structure described by debug info, so the fields can be shown by name.
+define dso_local { i32, i32 } @test_return_named_struct() #0 !dbg !3 {
+    ret { i32, i32 } { i32 1, i32 2 }
+}
+
+; The second field is aligned to four bytes, so the debug info offset includes the padding.
+define dso_local { i8, i32 } @test_return_padded_struct() #0 !dbg !20 {
+    ret { i8, i32 } { i8 1, i32 2 }
+}
+
+define dso_local {} @test_zero_sized_identity({} %unit) #0 {
+start:
+  ret {} %unit
+}
+
+; fn f(_: (), x: u32) -> u32, with the unit passed through memory, aggregates and a call.
+define dso_local i32 @test_zero_sized_param({} %unit, i32 %x) #0 {
+start:
+  %p = alloca {}, align 1
+  store {} %unit, ptr %p, align 1
+  %loaded = load {}, ptr %p, align 1
+  %agg = insertvalue { {}, i32 } undef, {} %loaded, 0
+  %agg1 = insertvalue { {}, i32 } %agg, i32 %x, 1
+  %unit1 = extractvalue { {}, i32 } %agg1, 0
+  %unit2 = call {} @test_zero_sized_identity({} %unit1)
+  store {} %unit2, ptr %p, align 1
+  %y = extractvalue { {}, i32 } %agg1, 1
+  %is_zero = icmp eq i32 %y, 0
+  br i1 %is_zero, label %zero, label %nonzero
+
+zero:
+  ret i32 1
+
+nonzero:
+  ret i32 2
+}
+
+; fn f(p: Option<&u32>) -> u32 { match p { Some(_) => 1, None => 0 } }, the discriminant is
+; derived from the null niche of the pointer.
+define dso_local i32 @test_option_niche(ptr align 4 %p) #0 {
+start:
+  %0 = ptrtoint ptr %p to i64
+  %1 = icmp eq i64 %0, 0
+  %_2 = select i1 %1, i64 0, i64 1
+  switch i64 %_2, label %bb1 [
+    i64 0, label %bb3
+    i64 1, label %bb2
+  ]
+
+bb1:
+  unreachable
+
+bb2:
+  ret i32 1
+
+bb3:
+  ret i32 0
+}
+
+; Option<(u32, &u32)> built with insertvalue, passed through memory and matched on the niche in
+; the pointer field.
+define dso_local i32 @test_option_niche_aggregate(ptr %p, i32 %x) #0 {
+start:
+  %a = insertvalue { i32, ptr } undef, i32 %x, 0
+  %b = insertvalue { i32, ptr } %a, ptr %p, 1
+  %m = alloca { i32, ptr }, align 8
+  store { i32, ptr } %b, ptr %m, align 8
+  %field = getelementptr inbounds { i32, ptr }, ptr %m, i32 0, i32 1
+  %niche = load ptr, ptr %field, align 8
+  %is_none = icmp eq ptr %niche, null
+  br i1 %is_none, label %none, label %some
+
+some:
+  %loaded = load { i32, ptr }, ptr %m, align 8
+  %v = extractvalue { i32, ptr } %loaded, 0
+  %q = extractvalue { i32, ptr } %loaded, 1
+  %same_v = icmp eq i32 %v, %x
+  %same_q = icmp eq ptr %q, %p
+  %same = and i1 %same_v, %same_q
+  %r = select i1 %same, i32 2, i32 3
+  ret i32 %r
+
+none:
+  ret i32 1
+}
+
+; Two copies of dup::f from different versions of a crate, told apart by their source file.
+define dso_local i32 @_ZN3dup1f17h0123456789abcdefE() #0 !dbg !11 {
+start:
+  ret i32 1
+}
+
+define dso_local i32 @_ZN3dup1f17hfedcba9876543210E() #0 !dbg !13 {
+start:
+  ret i32 2
+}
+
 declare void @assume(i32) #1
 
+declare void @_ZN9symex_lib6assume17h0123456789abcdefE(i1 zeroext) #1
+declare void @_ZN9symex_lib6assert17h0123456789abcdefE(i1 zeroext) #1
+
+declare void @symex_symbolic(ptr, i32) #1
+
+declare void @_ZN4core9panicking5panic17h0123456789abcdefE() #1
+
+declare void @_ZN4core9panicking18panic_bounds_check17h0123456789abcdefE(i64, i64, ptr) #1
+
+declare i32 @rust_eh_personality(...) #1
+
+declare zeroext i1 @_ZN4core3fmt5write17h0123456789abcdefE(ptr, ptr, ptr) #1
+
 attributes #0 = { noinline nounwind optnone sspstrong uwtable "frame-pointer"="all" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "tune-cpu"="generic" }
 attributes #1 = { "frame-pointer"="all" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "tune-cpu"="generic" }
+attributes #2 = { noreturn noinline nounwind optnone uwtable "frame-pointer"="all" }
+
+!llvm.dbg.cu = !{!0}
+!llvm.module.flags = !{!2}
+
+!0 = distinct !DICompileUnit(language: DW_LANG_Rust, file: !1, producer: "rustc", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)
+!1 = !DIFile(filename: "instructions.rs", directory: "/tmp")
+!2 = !{i32 2, !"Debug Info Version", i32 3}
+!3 = distinct !DISubprogram(name: "test_return_named_struct", scope: !1, file: !1, line: 1, type: !4, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!4 = !DISubroutineType(types: !5)
+!5 = !{!6}
+!6 = !DICompositeType(tag: DW_TAG_structure_type, name: "Point", scope: !1, file: !1, size: 64, align: 32, elements: !7)
+!7 = !{!8, !9}
+!8 = !DIDerivedType(tag: DW_TAG_member, name: "x", scope: !6, file: !1, baseType: !10, size: 32, align: 32, offset: 0)
+!9 = !DIDerivedType(tag: DW_TAG_member, name: "y", scope: !6, file: !1, baseType: !10, size: 32, align: 32, offset: 32)
+!10 = !DIBasicType(name: "i32", size: 32, encoding: DW_ATE_signed)
+!11 = distinct !DISubprogram(name: "f", scope: !12, file: !12, line: 1, type: !15, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!12 = !DIFile(filename: "dup-1.0.0/src/lib.rs", directory: "/registry")
+!13 = distinct !DISubprogram(name: "f", scope: !14, file: !14, line: 1, type: !15, scopeLine: 1, spFlags: DISPFlagDefinition, unit: !0)
+!14 = !DIFile(filename: "dup-2.0.0/src/lib.rs", directory: "/registry")
+!15 = !DISubroutineType(types: !16)
+!16 = !{!10}
+!20 = distinct !DISubprogram(name: "test_return_padded_struct", scope: !1, file: !1, line: 5, type: !21, scopeLine: 5, spFlags: DISPFlagDefinition, unit: !0)
+!21 = !DISubroutineType(types: !22)
+!22 = !{!23}
+!23 = !DICompositeType(tag: DW_TAG_structure_type, name: "Padded", scope: !1, file: !1, size: 64, align: 32, elements: !24)
+!24 = !{!25, !26}
+!25 = !DIDerivedType(tag: DW_TAG_member, name: "a", scope: !23, file: !1, baseType: !27, size: 8, align: 8, offset: 0)
+!26 = !DIDerivedType(tag: DW_TAG_member, name: "b", scope: !23, file: !1, baseType: !10, size: 32, align: 32, offset: 32)
+!27 = !DIBasicType(name: "u8", size: 8, encoding: DW_ATE_unsigned)
+!30 = distinct !DISubprogram(name: "inline_helper_dbg", scope: !1, file: !1, line: 10, type: !15, scopeLine: 10, spFlags: DISPFlagDefinition, unit: !0)
+!31 = !DILocalVariable(name: "a", arg: 1, scope: !30, file: !1, line: 10, type: !10)
+!32 = !DILocation(line: 10, scope: !30)
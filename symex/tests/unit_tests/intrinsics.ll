@@ -49,6 +49,37 @@ define dso_local [4 x i16] @test_memcpy() #0 {
     ;   -> 0x6543fe671234abcd
 }
 
+define dso_local i32 @test_memcpy_symbolic_len() #0 {
+    %1 = alloca i32, align 4
+    %2 = alloca i32, align 4
+    store i32 u0x12abcdef, i32* %1
+    store i32 0, i32* %2
+    %len = call i32 @symbolic_range(i32 3, i32 4)
+    %src = bitcast i32* %1 to i8*
+    %dst = bitcast i32* %2 to i8*
+    call void @llvm.memcpy.p0i8.p0i8.i32(i8* %dst, i8* %src, i32 %len, i1 0)
+    %3 = load i32, i32* %2
+    ret i32 %3 ; expect 0x00abcdef or 0x12abcdef
+}
+
+; memcpy.inline, same as memcpy but requires a constant length
+declare void @llvm.memcpy.inline.p0i8.p0i8.i32(i8* %dst, i8* %src, i32 immarg %len, i1 immarg %isvolatile)
+
+define dso_local [4 x i16] @test_memcpy_inline() #0 {
+    %1 = alloca [4 x i16], align 4
+    %2 = alloca [4 x i16], align 4
+    store [4 x i16] [i16 u0xabcd, i16 u0x1234, i16 u0x5667, i16 u0xbebe], [4 x i16]* %1
+    store [4 x i16] [i16 6, i16 7, i16 u0xfecb, i16 u0x6543], [4 x i16]* %2
+
+    %src = bitcast [4 x i16]* %1 to i8*
+    %dst = bitcast [4 x i16]* %2 to i8*
+    call void @llvm.memcpy.inline.p0i8.p0i8.i32(i8* %dst, i8* %src, i32 5, i1 0)
+
+    %ret = load [4 x i16], [4 x i16]* %2
+    ret [4 x i16] %ret
+    ; expect 0x6543fe671234abcd, same as memcpy
+}
+
 ; memmove
 declare void @llvm.memmove.p0i8.p0i8.i32(i8* %dst, i8* %src, i32 %len, i1 %isvolatile)
 
@@ -144,6 +175,125 @@ define dso_local [8 x i8] @test_memset() #0 {
     ret [8 x i8] %3 ; expect 0xababababcbcbcbcb
 }
 
+define dso_local i32 @test_memset_symbolic_len() #0 {
+    %1 = alloca i32, align 4
+    store i32 0, i32* %1
+    %len = call i32 @symbolic_range(i32 3, i32 4)
+    %2 = bitcast i32* %1 to i8*
+    call void @llvm.memset.p0i8.i32(i8* %2, i8 u0xab, i32 %len, i1 0)
+    %3 = load i32, i32* %1
+    ret i32 %3 ; expect 0x00ababab or 0xabababab
+}
+
+define dso_local void @test_memset_unbounded_len(i32 %len) #0 {
+    %1 = alloca [8 x i8], align 4
+    %2 = bitcast [8 x i8]* %1 to i8*
+    call void @llvm.memset.p0i8.i32(i8* %2, i8 u0xab, i32 %len, i1 0)
+    ret void
+}
+
+; ctpop
+declare i32 @llvm.ctpop.i32(i32)
+declare i8 @llvm.ctpop.i8(i8)
+
+define dso_local i32 @test_ctpop() #0 {
+    %1 = call i32 @llvm.ctpop.i32(i32 u0x0f0f00ff)
+    ret i32 %1 ; expect 16
+}
+
+define dso_local i16 @test_ctpop_symbolic(i8 %x) #0 {
+    %count = call i8 @llvm.ctpop.i8(i8 %x)
+    %high = zext i8 %x to i16
+    %shifted = shl i16 %high, 8
+    %low = zext i8 %count to i16
+    %1 = or i16 %shifted, %low
+    ret i16 %1 ; expect the count of the upper byte in the lower byte
+}
+
+; ctlz
+declare i32 @llvm.ctlz.i32(i32, i1)
+
+define dso_local i32 @test_ctlz() #0 {
+    %1 = call i32 @llvm.ctlz.i32(i32 u0x00f00000, i1 true)
+    ret i32 %1 ; expect 8
+}
+
+define dso_local i32 @test_ctlz_zero() #0 {
+    %1 = call i32 @llvm.ctlz.i32(i32 0, i1 false)
+    ret i32 %1 ; expect 32
+}
+
+; cttz
+declare i32 @llvm.cttz.i32(i32, i1)
+
+define dso_local i32 @test_cttz() #0 {
+    %1 = call i32 @llvm.cttz.i32(i32 u0x00f00000, i1 true)
+    ret i32 %1 ; expect 20
+}
+
+define dso_local i32 @test_cttz_zero() #0 {
+    %1 = call i32 @llvm.cttz.i32(i32 0, i1 false)
+    ret i32 %1 ; expect 32
+}
+
+; bswap
+declare i32 @llvm.bswap.i32(i32)
+
+define dso_local i32 @test_bswap() #0 {
+    %1 = call i32 @llvm.bswap.i32(i32 u0x12345678)
+    ret i32 %1 ; expect 0x78563412
+}
+
+define dso_local i64 @test_bswap_symbolic(i32 %x) #0 {
+    %swapped = call i32 @llvm.bswap.i32(i32 %x)
+    %high = zext i32 %x to i64
+    %shifted = shl i64 %high, 32
+    %low = zext i32 %swapped to i64
+    %1 = or i64 %shifted, %low
+    ret i64 %1 ; expect the swapped upper half in the lower half
+}
+
+; bitreverse
+declare i16 @llvm.bitreverse.i16(i16)
+
+define dso_local i16 @test_bitreverse() #0 {
+    %1 = call i16 @llvm.bitreverse.i16(i16 u0x2001)
+    ret i16 %1 ; expect 0x8004
+}
+
+; fshl
+declare i8 @llvm.fshl.i8(i8, i8, i8)
+
+define dso_local i8 @test_fshl() #0 {
+    %1 = call i8 @llvm.fshl.i8(i8 u0x81, i8 u0x81, i8 1)
+    ret i8 %1 ; expect 0x03
+}
+
+define dso_local i8 @test_fshl_modulo() #0 {
+    %1 = call i8 @llvm.fshl.i8(i8 u0x12, i8 u0x34, i8 12)
+    ret i8 %1 ; expect 0x23, the same as shifting by 4
+}
+
+define dso_local i32 @test_rotate_symbolic(i8 %x, i8 %s) #0 {
+    %rotated = call i8 @llvm.fshl.i8(i8 %x, i8 %x, i8 %s)
+    %input = zext i8 %x to i32
+    %input_shifted = shl i32 %input, 16
+    %shift = zext i8 %s to i32
+    %shift_shifted = shl i32 %shift, 8
+    %low = zext i8 %rotated to i32
+    %1 = or i32 %input_shifted, %shift_shifted
+    %2 = or i32 %1, %low
+    ret i32 %2 ; expect the rotated input in the lowest byte
+}
+
+; fshr
+declare i8 @llvm.fshr.i8(i8, i8, i8)
+
+define dso_local i8 @test_fshr() #0 {
+    %1 = call i8 @llvm.fshr.i8(i8 u0x12, i8 u0x34, i8 4)
+    ret i8 %1 ; expect 0x23
+}
+
 ; umax
 declare i32 @llvm.umax.i32(i32, i32)
 declare <2 x i32> @llvm.umax.v2i32(<2 x i32>, <2 x i32>)
@@ -177,6 +327,7 @@ declare {i8, i1} @llvm.ssub.with.overflow.i8(i8, i8)
 declare {i8, i1} @llvm.usub.with.overflow.i8(i8, i8)
 declare {i8, i1} @llvm.smul.with.overflow.i8(i8, i8)
 declare {i8, i1} @llvm.umul.with.overflow.i8(i8, i8)
+declare {i32, i1} @llvm.sadd.with.overflow.i32(i32, i32)
 
 
 ; sadd
@@ -216,12 +367,61 @@ define dso_local {i8, i1} @test_uadd_with_overflow1() #0 {
 }
 
 ; ssub
+define dso_local {i8, i1} @test_ssub_with_overflow0() #0 {
+    %1 = call {i8, i1} @llvm.ssub.with.overflow.i8(i8 -100, i8 50) ; -150 = 0x6a
+    ret {i8, i1} %1 ; expect {0x6a, 0x01} -> 0x016a
+}
+
+define dso_local {i8, i1} @test_ssub_with_overflow1() #0 {
+    %1 = call {i8, i1} @llvm.ssub.with.overflow.i8(i8 10, i8 20) ; -10 = 0xf6
+    ret {i8, i1} %1 ; expect {0xf6, 0x00} -> 0x00f6
+}
 
 ; usub
+define dso_local {i8, i1} @test_usub_with_overflow0() #0 {
+    %1 = call {i8, i1} @llvm.usub.with.overflow.i8(i8 10, i8 20) ; -10 = 0xf6
+    ret {i8, i1} %1 ; expect {0xf6, 0x01} -> 0x01f6
+}
+
+define dso_local {i8, i1} @test_usub_with_overflow1() #0 {
+    %1 = call {i8, i1} @llvm.usub.with.overflow.i8(i8 20, i8 10) ; 10 = 0x0a
+    ret {i8, i1} %1 ; expect {0x0a, 0x00} -> 0x000a
+}
 
 ; smul
+define dso_local {i8, i1} @test_smul_with_overflow0() #0 {
+    %1 = call {i8, i1} @llvm.smul.with.overflow.i8(i8 16, i8 8) ; 128 = 0x80
+    ret {i8, i1} %1 ; expect {0x80, 0x01} -> 0x0180
+}
 
-; umum
+define dso_local {i8, i1} @test_smul_with_overflow1() #0 {
+    %1 = call {i8, i1} @llvm.smul.with.overflow.i8(i8 -8, i8 16) ; -128 = 0x80
+    ret {i8, i1} %1 ; expect {0x80, 0x00} -> 0x0080
+}
+
+; umul
+define dso_local {i8, i1} @test_umul_with_overflow0() #0 {
+    %1 = call {i8, i1} @llvm.umul.with.overflow.i8(i8 16, i8 16) ; 256 = 0x100
+    ret {i8, i1} %1 ; expect {0x00, 0x01} -> 0x0100
+}
+
+define dso_local {i8, i1} @test_umul_with_overflow1() #0 {
+    %1 = call {i8, i1} @llvm.umul.with.overflow.i8(i8 15, i8 17) ; 255 = 0xff
+    ret {i8, i1} %1 ; expect {0xff, 0x00} -> 0x00ff
+}
+
+; checked_add
+define dso_local i32 @test_checked_add(i32 %a, i32 %b) #0 {
+    %1 = call {i32, i1} @llvm.sadd.with.overflow.i32(i32 %a, i32 %b)
+    %overflow = extractvalue {i32, i1} %1, 1
+    br i1 %overflow, label %overflowed, label %ok
+
+overflowed:
+    ret i32 1
+
+ok:
+    ret i32 0
+}
 
 ; --------------------------------------------------------------------------------------------------
 ; Saturation arithmetic intrinsics
@@ -336,6 +536,20 @@ define dso_local i32 @test_expect() #0 {
     ret i32 %3 ; expect 100
 }
 
+declare i1 @llvm.expect.with.probability.i1(i1 %val, i1 %expected_val, double %prob)
+
+; Branch hint as emitted for `likely`, does not change the branch taken.
+define dso_local i32 @test_expect_with_probability() #0 {
+    %1 = add i32 0, 100
+    %2 = icmp ugt i32 %1, 50
+    %3 = call i1 @llvm.expect.with.probability.i1(i1 %2, i1 false, double 0.9)
+    br i1 %3, label %likely, label %unlikely
+likely:
+    ret i32 1 ; expect 1
+unlikely:
+    ret i32 2
+}
+
 define dso_local i32 @test_assume() #0 {
     %1 = alloca i32
     %val = load i32, i32* %1